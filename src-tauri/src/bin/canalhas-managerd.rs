@@ -0,0 +1,864 @@
+// Headless manager: runs the same commands as the desktop app behind an
+// authenticated HTTP + WebSocket API, so the server box needs no GUI.
+
+use std::{fs, net::SocketAddr, path::PathBuf, sync::Arc};
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Path, Query, Request, State,
+    },
+    http::{header, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{delete, get, post},
+    Json, Router,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use app_lib::commands::{
+    admin_backend::AdminBackendKind,
+    amxx_flags::get_admin_flags,
+    attach::reattach_instances,
+    bans::{ban, bans, start_ban_sweeper, unban, BanRecord, NewBan},
+    chat::{list_chat_filters, save_chat_filters, search_chat, ChatQuery, ChatRule},
+    hlds::{send_command, start_instance, stop_instance, HldsState, HldsStatus, InstanceRuntime},
+    instances::{
+        add_instance, delete_instance, get_instance, list_instances, read_instances,
+        rename_instance, resolve_instance_id, ServerInstance,
+    },
+    map_deps::{check_map_dependencies, generate_map_res},
+    map_install::{install_map_package, preview_map_package},
+    maps::{delete_map_files, list_maps, MapDeletion, MapInfo},
+    players::{get_player_profile, search_players, PlayerQuery},
+    ports::{get_port_owners, PortOwner},
+    query::get_server_status,
+    rcon::send_rcon_command,
+    rotation::{
+        generate_rotation, push_mapcycle, read_rotation, write_rotation, Rotation, RotationEntry,
+        RotationList, WeightedMap,
+    },
+    shutdown::StopOptions,
+    sink::{EventSink, Sink},
+    stats::{
+        get_map_leaderboard, get_player_leaderboard, get_player_stats, import_stats_logs,
+        StatsFilter,
+    },
+    status::{ban_player_target, kick, move_team, players, slay, PlayerTarget, StatusPlayer, Team},
+    supervisor::{crash_history, CrashRecord},
+    users::{
+        add_admin_entry, get_admin_backend, list_admins, remove_admin_entry, set_admin_backend,
+        update_admin_entry, AdminEntry, AdminReload,
+    },
+    webhook::{add_webhook, list_webhooks, remove_webhook},
+};
+
+const DEFAULT_LISTEN: &str = "127.0.0.1:7878";
+const EVENT_BUFFER: usize = 1024;
+
+struct Daemon {
+    hlds: HldsState,
+    sink: Sink,
+    events: broadcast::Sender<String>,
+    token: String,
+}
+
+type Shared = Arc<Daemon>;
+
+struct BroadcastSink(broadcast::Sender<String>);
+
+impl EventSink for BroadcastSink {
+    fn emit(&self, event: &str, payload: serde_json::Value) {
+        let message = serde_json::json!({ "event": event, "payload": payload });
+        // No subscribers is not an error: events are only for connected clients.
+        let _ = self.0.send(message.to_string());
+    }
+}
+
+struct ApiError(StatusCode, String);
+
+impl From<String> for ApiError {
+    fn from(message: String) -> Self {
+        Self(StatusCode::BAD_REQUEST, message)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(serde_json::json!({ "error": self.1 }))).into_response()
+    }
+}
+
+type ApiResult<T> = Result<Json<T>, ApiError>;
+
+fn token_path() -> PathBuf {
+    let mut path = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
+    path.push("canalhas-manager/daemon_token");
+    path
+}
+
+// CANALHAS_TOKEN wins; otherwise a random token is created once and kept in the config dir.
+fn load_token() -> Result<String, String> {
+    if let Some(token) = std::env::var("CANALHAS_TOKEN")
+        .ok()
+        .filter(|t| !t.is_empty())
+    {
+        return Ok(token);
+    }
+
+    let path = token_path();
+    if let Ok(token) = fs::read_to_string(&path) {
+        if !token.trim().is_empty() {
+            return Ok(token.trim().to_string());
+        }
+    }
+
+    let token = uuid::Uuid::new_v4().simple().to_string();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    fs::write(&path, &token).map_err(|e| e.to_string())?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = fs::set_permissions(&path, fs::Permissions::from_mode(0o600));
+    }
+
+    println!("🔑 Token de acesso gerado em {}", path.display());
+    Ok(token)
+}
+
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+            == 0
+}
+
+// Browsers cannot set headers on a WebSocket handshake, so `?token=` is accepted too.
+async fn require_token(
+    State(daemon): State<Shared>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let from_header = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::to_string);
+    let from_query = request.uri().query().and_then(|q| {
+        q.split('&')
+            .find_map(|pair| pair.strip_prefix("token="))
+            .map(str::to_string)
+    });
+
+    match from_header.or(from_query) {
+        Some(token) if constant_time_eq(&token, &daemon.token) => Ok(next.run(request).await),
+        _ => Err(ApiError(
+            StatusCode::UNAUTHORIZED,
+            "Token inválido ou ausente.".into(),
+        )),
+    }
+}
+
+// Unknown instances are a 404, not a failed command.
+async fn require_instance(id: &str) -> Result<(), ApiError> {
+    let wanted = id.to_string();
+    let known = blocking(move || Ok(read_instances()?.iter().any(|i| i.id == wanted))).await?;
+    if known {
+        Ok(())
+    } else {
+        Err(ApiError(
+            StatusCode::NOT_FOUND,
+            format!("Instância '{}' não encontrada.", id),
+        ))
+    }
+}
+
+// HldsState::runtime creates an entry for any id, so unknown instances are
+// rejected here before they leave a stray runtime behind.
+async fn instance_runtime(daemon: &Daemon, id: &str) -> Result<Arc<InstanceRuntime>, ApiError> {
+    require_instance(id).await?;
+    Ok(daemon.hlds.runtime(id))
+}
+
+// Most commands block (process control, RCON, file IO), so keep them off the async workers.
+async fn blocking<T, F>(f: F) -> Result<T, ApiError>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, String> + Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| ApiError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map_err(ApiError::from)
+}
+
+// Optional JSON bodies: an empty body means "use the defaults".
+fn optional_body<T: DeserializeOwned>(body: &str) -> Result<Option<T>, ApiError> {
+    if body.trim().is_empty() {
+        return Ok(None);
+    }
+    serde_json::from_str(body)
+        .map(Some)
+        .map_err(|e| ApiError(StatusCode::BAD_REQUEST, e.to_string()))
+}
+
+#[derive(Deserialize)]
+struct StartBody {
+    // Foreign processes on the game port that the client agreed to kill,
+    // as listed by GET /instances/{id}/ports.
+    #[serde(default)]
+    confirmed_pids: Vec<u32>,
+}
+
+#[derive(Deserialize, Default)]
+struct StopBody {
+    #[serde(flatten)]
+    options: StopOptions,
+    // Skips the countdown and kills the server right away.
+    #[serde(default)]
+    now: bool,
+}
+
+#[derive(Deserialize)]
+struct CommandBody {
+    command: String,
+}
+
+#[derive(Deserialize)]
+struct RenameBody {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct WebhookBody {
+    url: String,
+    events: Vec<String>,
+    #[serde(default)]
+    instances: Option<Vec<String>>,
+}
+
+#[derive(Deserialize)]
+struct KickBody {
+    target: PlayerTarget,
+    reason: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct PlayerBanBody {
+    target: PlayerTarget,
+    #[serde(default)]
+    minutes: u32,
+    reason: Option<String>,
+    banned_by: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SlayBody {
+    target: PlayerTarget,
+}
+
+#[derive(Deserialize)]
+struct MoveBody {
+    target: PlayerTarget,
+    team: Team,
+}
+
+// `path` is a file on the daemon's machine.
+#[derive(Deserialize)]
+struct PackageBody {
+    path: String,
+    #[serde(default)]
+    overwrite: bool,
+}
+
+#[derive(Deserialize)]
+struct RotationBody {
+    entries: Vec<RotationEntry>,
+    #[serde(default)]
+    push: bool,
+}
+
+#[derive(Deserialize)]
+struct GenerateRotationBody {
+    pool: Vec<WeightedMap>,
+    seed: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct RemoveWebhookQuery {
+    url: String,
+}
+
+#[derive(Serialize)]
+struct Done {
+    ok: bool,
+}
+
+fn ok() -> ApiResult<Done> {
+    Ok(Json(Done { ok: true }))
+}
+
+async fn instances() -> ApiResult<Vec<ServerInstance>> {
+    Ok(Json(blocking(list_instances).await?))
+}
+
+async fn create_instance(Json(instance): Json<ServerInstance>) -> ApiResult<Done> {
+    blocking(move || add_instance(instance)).await?;
+    ok()
+}
+
+async fn rename(Path(id): Path<String>, Json(body): Json<RenameBody>) -> ApiResult<Done> {
+    require_instance(&id).await?;
+    blocking(move || rename_instance(id, body.name)).await?;
+    ok()
+}
+
+async fn remove(State(daemon): State<Shared>, Path(id): Path<String>) -> ApiResult<Done> {
+    require_instance(&id).await?;
+    blocking(move || delete_instance(&daemon.hlds, &id)).await?;
+    ok()
+}
+
+async fn status(State(daemon): State<Shared>, Path(id): Path<String>) -> ApiResult<HldsStatus> {
+    instance_runtime(&daemon, &id).await?;
+    Ok(Json(daemon.hlds.status(&id)))
+}
+
+async fn start(
+    State(daemon): State<Shared>,
+    Path(id): Path<String>,
+    body: String,
+) -> ApiResult<Done> {
+    instance_runtime(&daemon, &id).await?;
+    let confirmed = optional_body::<StartBody>(&body)?
+        .map(|b| b.confirmed_pids)
+        .unwrap_or_default();
+    blocking(move || start_instance(&daemon.hlds, daemon.sink.clone(), &id, &confirmed)).await?;
+    ok()
+}
+
+async fn stop(
+    State(daemon): State<Shared>,
+    Path(id): Path<String>,
+    body: String,
+) -> Result<impl IntoResponse, ApiError> {
+    let runtime = instance_runtime(&daemon, &id).await?;
+    // No body means a graceful stop with the default countdown.
+    let body = optional_body::<StopBody>(&body)?.unwrap_or_default();
+    let options = (!body.now).then_some(body.options);
+    let method = blocking(move || Ok(stop_instance(&daemon.sink, &id, &runtime, options))).await?;
+    Ok(Json(serde_json::json!({ "method": method })))
+}
+
+async fn console(
+    State(daemon): State<Shared>,
+    Path(id): Path<String>,
+    Json(body): Json<CommandBody>,
+) -> ApiResult<Done> {
+    instance_runtime(&daemon, &id).await?;
+    blocking(move || send_command(&daemon.hlds, daemon.sink.clone(), &id, body.command)).await?;
+    ok()
+}
+
+async fn rcon(Path(id): Path<String>, Json(body): Json<CommandBody>) -> impl IntoResponse {
+    require_instance(&id).await?;
+    send_rcon_command(body.command, Some(id))
+        .await
+        .map(|output| Json(serde_json::json!({ "output": output })))
+        .map_err(ApiError::from)
+}
+
+async fn server_status(Path(id): Path<String>) -> impl IntoResponse {
+    require_instance(&id).await?;
+    get_server_status(Some(id))
+        .await
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+async fn port_owners(Path(id): Path<String>) -> ApiResult<Vec<PortOwner>> {
+    require_instance(&id).await?;
+    Ok(Json(blocking(move || get_port_owners(Some(id))).await?))
+}
+
+async fn crashes(
+    State(daemon): State<Shared>,
+    Path(id): Path<String>,
+) -> ApiResult<Vec<CrashRecord>> {
+    instance_runtime(&daemon, &id).await?;
+    Ok(Json(crash_history(&daemon.hlds, &id)))
+}
+
+async fn maps(Path(id): Path<String>) -> ApiResult<Vec<MapInfo>> {
+    require_instance(&id).await?;
+    Ok(Json(list_maps(Some(id)).await?))
+}
+
+async fn delete_map(Path((id, map)): Path<(String, String)>) -> ApiResult<MapDeletion> {
+    require_instance(&id).await?;
+    Ok(Json(delete_map_files(map, Some(id)).await?))
+}
+
+async fn map_dependencies(Path((id, map)): Path<(String, String)>) -> impl IntoResponse {
+    require_instance(&id).await?;
+    check_map_dependencies(map, Some(id))
+        .await
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+async fn map_res(Path((id, map)): Path<(String, String)>) -> impl IntoResponse {
+    require_instance(&id).await?;
+    generate_map_res(map, Some(id))
+        .await
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+async fn map_package_preview(
+    Path(id): Path<String>,
+    Json(body): Json<PackageBody>,
+) -> impl IntoResponse {
+    require_instance(&id).await?;
+    preview_map_package(body.path, Some(id))
+        .await
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+async fn map_package_install(
+    Path(id): Path<String>,
+    Json(body): Json<PackageBody>,
+) -> impl IntoResponse {
+    require_instance(&id).await?;
+    install_map_package(body.path, Some(body.overwrite), Some(id))
+        .await
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+async fn rotation(Path((id, list)): Path<(String, RotationList)>) -> ApiResult<Rotation> {
+    require_instance(&id).await?;
+    Ok(Json(
+        blocking(move || read_rotation(&get_instance(Some(&id))?, list)).await?,
+    ))
+}
+
+async fn change_rotation(
+    State(daemon): State<Shared>,
+    Path((id, list)): Path<(String, RotationList)>,
+    Json(body): Json<RotationBody>,
+) -> ApiResult<Rotation> {
+    let runtime = instance_runtime(&daemon, &id).await?;
+    Ok(Json(
+        blocking(move || {
+            let instance = get_instance(Some(&id))?;
+            let mut rotation = write_rotation(&instance, list, body.entries)?;
+            if body.push && list == RotationList::Mapcycle {
+                rotation.pushed = push_mapcycle(&runtime, &instance)?;
+            }
+            Ok(rotation)
+        })
+        .await?,
+    ))
+}
+
+async fn new_rotation(Json(body): Json<GenerateRotationBody>) -> ApiResult<Vec<String>> {
+    Ok(Json(generate_rotation(&body.pool, body.seed)?))
+}
+
+async fn admins(Path(id): Path<String>) -> ApiResult<Vec<AdminEntry>> {
+    require_instance(&id).await?;
+    Ok(Json(list_admins(Some(id)).await?))
+}
+
+async fn create_admin(
+    State(daemon): State<Shared>,
+    Path(id): Path<String>,
+    Json(entry): Json<AdminEntry>,
+) -> ApiResult<AdminReload> {
+    let runtime = instance_runtime(&daemon, &id).await?;
+    Ok(Json(add_admin_entry(runtime, id, entry).await?))
+}
+
+async fn edit_admin(
+    State(daemon): State<Shared>,
+    Path(id): Path<String>,
+    Json(entry): Json<AdminEntry>,
+) -> ApiResult<AdminReload> {
+    let runtime = instance_runtime(&daemon, &id).await?;
+    Ok(Json(update_admin_entry(runtime, id, entry).await?))
+}
+
+async fn delete_admin(
+    State(daemon): State<Shared>,
+    Path((id, auth)): Path<(String, String)>,
+) -> ApiResult<AdminReload> {
+    let runtime = instance_runtime(&daemon, &id).await?;
+    Ok(Json(remove_admin_entry(runtime, id, auth).await?))
+}
+
+async fn admin_backend(Path(id): Path<String>) -> ApiResult<AdminBackendKind> {
+    require_instance(&id).await?;
+    Ok(Json(blocking(move || get_admin_backend(Some(id))).await?))
+}
+
+async fn change_admin_backend(
+    Path(id): Path<String>,
+    Json(backend): Json<AdminBackendKind>,
+) -> ApiResult<Done> {
+    require_instance(&id).await?;
+    blocking(move || set_admin_backend(backend, Some(id))).await?;
+    ok()
+}
+
+async fn list_bans(Path(id): Path<String>) -> ApiResult<Vec<BanRecord>> {
+    require_instance(&id).await?;
+    Ok(Json(blocking(move || bans(&id)).await?))
+}
+
+async fn create_ban(
+    State(daemon): State<Shared>,
+    Path(id): Path<String>,
+    Json(new_ban): Json<NewBan>,
+) -> ApiResult<BanRecord> {
+    let runtime = instance_runtime(&daemon, &id).await?;
+    Ok(Json(blocking(move || ban(&runtime, &id, new_ban)).await?))
+}
+
+async fn delete_ban(
+    State(daemon): State<Shared>,
+    Path((id, target)): Path<(String, String)>,
+) -> ApiResult<BanRecord> {
+    let runtime = instance_runtime(&daemon, &id).await?;
+    Ok(Json(blocking(move || unban(&runtime, &id, &target)).await?))
+}
+
+async fn admin_flags() -> impl IntoResponse {
+    Json(get_admin_flags())
+}
+
+async fn webhooks() -> impl IntoResponse {
+    Json(list_webhooks())
+}
+
+async fn create_webhook(Json(body): Json<WebhookBody>) -> ApiResult<Done> {
+    blocking(move || add_webhook(body.url, body.events, body.instances)).await?;
+    ok()
+}
+
+async fn delete_webhook(Query(query): Query<RemoveWebhookQuery>) -> ApiResult<Done> {
+    blocking(move || remove_webhook(query.url)).await?;
+    ok()
+}
+
+async fn player_leaderboard(Query(filter): Query<StatsFilter>) -> impl IntoResponse {
+    get_player_leaderboard(Some(filter))
+        .await
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+async fn online_players(
+    State(daemon): State<Shared>,
+    Path(id): Path<String>,
+) -> ApiResult<Vec<StatusPlayer>> {
+    let runtime = instance_runtime(&daemon, &id).await?;
+    Ok(Json(blocking(move || players(&runtime, &id)).await?))
+}
+
+async fn kick_player(
+    State(daemon): State<Shared>,
+    Path(id): Path<String>,
+    Json(body): Json<KickBody>,
+) -> ApiResult<StatusPlayer> {
+    let runtime = instance_runtime(&daemon, &id).await?;
+    Ok(Json(
+        blocking(move || kick(&runtime, &id, &body.target, body.reason.as_deref())).await?,
+    ))
+}
+
+async fn ban_player(
+    State(daemon): State<Shared>,
+    Path(id): Path<String>,
+    Json(body): Json<PlayerBanBody>,
+) -> ApiResult<BanRecord> {
+    let runtime = instance_runtime(&daemon, &id).await?;
+    Ok(Json(
+        blocking(move || {
+            ban_player_target(
+                &runtime,
+                &id,
+                &body.target,
+                body.minutes,
+                body.reason,
+                body.banned_by,
+            )
+        })
+        .await?,
+    ))
+}
+
+async fn slay_player(
+    State(daemon): State<Shared>,
+    Path(id): Path<String>,
+    Json(body): Json<SlayBody>,
+) -> ApiResult<StatusPlayer> {
+    let runtime = instance_runtime(&daemon, &id).await?;
+    Ok(Json(
+        blocking(move || slay(&runtime, &id, &body.target)).await?,
+    ))
+}
+
+async fn move_player(
+    State(daemon): State<Shared>,
+    Path(id): Path<String>,
+    Json(body): Json<MoveBody>,
+) -> ApiResult<StatusPlayer> {
+    let runtime = instance_runtime(&daemon, &id).await?;
+    Ok(Json(
+        blocking(move || move_team(&runtime, &id, &body.target, body.team)).await?,
+    ))
+}
+
+async fn chat(Query(query): Query<ChatQuery>) -> impl IntoResponse {
+    search_chat(Some(query))
+        .await
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+async fn chat_filters() -> impl IntoResponse {
+    Json(list_chat_filters())
+}
+
+async fn change_chat_filters(Json(rules): Json<Vec<ChatRule>>) -> ApiResult<Done> {
+    blocking(move || save_chat_filters(rules)).await?;
+    ok()
+}
+
+async fn player_search(Query(query): Query<PlayerQuery>) -> impl IntoResponse {
+    search_players(Some(query))
+        .await
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+async fn player_profile(Path(player_id): Path<String>) -> impl IntoResponse {
+    get_player_profile(player_id)
+        .await
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+async fn player_stats(
+    Path(player_id): Path<String>,
+    Query(filter): Query<StatsFilter>,
+) -> impl IntoResponse {
+    get_player_stats(player_id, Some(filter))
+        .await
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+async fn map_leaderboard(Query(filter): Query<StatsFilter>) -> impl IntoResponse {
+    get_map_leaderboard(Some(filter))
+        .await
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+async fn import_logs(Path(id): Path<String>) -> impl IntoResponse {
+    require_instance(&id).await?;
+    import_stats_logs(Some(id))
+        .await
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+async fn events(State(daemon): State<Shared>, ws: WebSocketUpgrade) -> Response {
+    ws.on_upgrade(move |socket| stream_events(daemon, socket))
+}
+
+#[derive(Deserialize)]
+struct ConsoleMessage {
+    instance: Option<String>,
+    command: String,
+}
+
+// Pushes every server event to the client; text frames of the form
+// {"instance": "...", "command": "..."} are written to that server's console.
+async fn stream_events(daemon: Shared, mut socket: WebSocket) {
+    let mut events = daemon.events.subscribe();
+
+    loop {
+        tokio::select! {
+            event = events.recv() => match event {
+                Ok(text) => {
+                    if socket.send(Message::Text(text.into())).await.is_err() {
+                        break;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    println!("⚠️ Cliente WebSocket atrasado, {} eventos descartados", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            message = socket.recv() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    let reply = match serde_json::from_str::<ConsoleMessage>(&text) {
+                        Ok(message) => {
+                            let id = resolve_instance_id(message.instance);
+                            let daemon = daemon.clone();
+                            blocking(move || {
+                                get_instance(Some(&id))?;
+                                send_command(&daemon.hlds, daemon.sink.clone(), &id, message.command)
+                            })
+                            .await
+                            .err()
+                            .map(|e| e.1)
+                        }
+                        Err(e) => Some(e.to_string()),
+                    };
+                    if let Some(error) = reply {
+                        let text = serde_json::json!({ "error": error }).to_string();
+                        if socket.send(Message::Text(text.into())).await.is_err() {
+                            break;
+                        }
+                    }
+                }
+                Some(Ok(Message::Close(_))) | None | Some(Err(_)) => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+fn router(daemon: Shared) -> Router {
+    let api = Router::new()
+        .route("/instances", get(instances).post(create_instance))
+        .route("/instances/{id}", delete(remove).patch(rename))
+        .route("/instances/{id}/status", get(status))
+        .route("/instances/{id}/ports", get(port_owners))
+        .route("/instances/{id}/start", post(start))
+        .route("/instances/{id}/stop", post(stop))
+        .route("/instances/{id}/console", post(console))
+        .route("/instances/{id}/rcon", post(rcon))
+        .route("/instances/{id}/query", get(server_status))
+        .route("/instances/{id}/crashes", get(crashes))
+        .route("/instances/{id}/maps", get(maps))
+        .route("/instances/{id}/maps/preview", post(map_package_preview))
+        .route("/instances/{id}/maps/install", post(map_package_install))
+        .route("/instances/{id}/maps/{map}", delete(delete_map))
+        .route(
+            "/instances/{id}/maps/{map}/dependencies",
+            get(map_dependencies),
+        )
+        .route("/instances/{id}/maps/{map}/res", post(map_res))
+        .route(
+            "/instances/{id}/rotation/{list}",
+            get(rotation).put(change_rotation),
+        )
+        .route(
+            "/instances/{id}/admins",
+            get(admins).post(create_admin).put(edit_admin),
+        )
+        .route("/instances/{id}/admins/{auth}", delete(delete_admin))
+        .route(
+            "/instances/{id}/admin-backend",
+            get(admin_backend).put(change_admin_backend),
+        )
+        .route("/instances/{id}/stats/import", post(import_logs))
+        .route("/instances/{id}/bans", get(list_bans).post(create_ban))
+        .route("/instances/{id}/bans/{target}", delete(delete_ban))
+        .route("/instances/{id}/players", get(online_players))
+        .route("/instances/{id}/players/kick", post(kick_player))
+        .route("/instances/{id}/players/ban", post(ban_player))
+        .route("/instances/{id}/players/slay", post(slay_player))
+        .route("/instances/{id}/players/move", post(move_player))
+        .route("/rotation/generate", post(new_rotation))
+        .route("/admin-flags", get(admin_flags))
+        .route(
+            "/webhooks",
+            get(webhooks).post(create_webhook).delete(delete_webhook),
+        )
+        .route("/chat", get(chat))
+        .route("/chat-filters", get(chat_filters).put(change_chat_filters))
+        .route("/players", get(player_search))
+        .route("/players/{player_id}", get(player_profile))
+        .route("/stats/players", get(player_leaderboard))
+        .route("/stats/players/{player_id}", get(player_stats))
+        .route("/stats/maps", get(map_leaderboard))
+        .route("/events", get(events))
+        .route_layer(middleware::from_fn_with_state(
+            daemon.clone(),
+            require_token,
+        ));
+
+    Router::new().nest("/api", api).with_state(daemon)
+}
+
+#[tokio::main]
+async fn main() {
+    // Commands spawn webhook tasks through Tauri's runtime; share ours with it.
+    tauri::async_runtime::set(tokio::runtime::Handle::current());
+
+    let listen: SocketAddr = match std::env::var("CANALHAS_LISTEN")
+        .unwrap_or_else(|_| DEFAULT_LISTEN.into())
+        .parse()
+    {
+        Ok(addr) => addr,
+        Err(e) => {
+            eprintln!("❌ Endereço inválido em CANALHAS_LISTEN: {}", e);
+            std::process::exit(2);
+        }
+    };
+
+    let token = match load_token() {
+        Ok(token) => token,
+        Err(e) => {
+            eprintln!("❌ Não foi possível carregar o token: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let (events, _) = broadcast::channel(EVENT_BUFFER);
+    let daemon = Arc::new(Daemon {
+        hlds: HldsState::default(),
+        sink: Arc::new(BroadcastSink(events.clone())),
+        events,
+        token,
+    });
+
+    reattach_instances(&daemon.hlds, &daemon.sink);
+    let sweeper = daemon.clone();
+    start_ban_sweeper(move |id| sweeper.hlds.runtime(id));
+
+    let listener = match tokio::net::TcpListener::bind(listen).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("❌ Não foi possível escutar em {}: {}", listen, e);
+            std::process::exit(1);
+        }
+    };
+    println!("🚀 canalhas-managerd escutando em http://{}", listen);
+
+    let shutdown = async {
+        let _ = tokio::signal::ctrl_c().await;
+        println!("👋 Encerrando canalhas-managerd");
+    };
+
+    if let Err(e) = axum::serve(listener, router(daemon))
+        .with_graceful_shutdown(shutdown)
+        .await
+    {
+        eprintln!("❌ Erro no servidor HTTP: {}", e);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,955 @@
+// Command-line interface for scripting the manager from cron and shell.
+//
+// Exit codes: 0 success, 1 operation failed, 2 invalid usage, 3 server not running.
+
+use std::{
+    io::{self, BufRead, IsTerminal, Write},
+    process::ExitCode,
+    time::Duration,
+};
+
+use clap::{Args, Parser, Subcommand};
+use serde::Serialize;
+
+use app_lib::commands::{
+    admin_backend::AdminBackendKind,
+    attach::adopt,
+    bans::{ban, bans, unban, NewBan},
+    chat::{read_chat_filters, search, write_chat_filters, ChatAction, ChatQuery, ChatRule},
+    hlds::{start_detached, stop_process, HldsState, InstanceRuntime},
+    instances::{get_instance, list_instances, resolve_instance_id},
+    map_deps::{resolve_dependencies, write_res},
+    map_install::{install_map_package, preview_map_package, FileAction, MapPackage},
+    maps::{delete_map_files, list_maps},
+    pidfile::{read_run_record, RunRecord},
+    players::{self, profile, PlayerQuery},
+    ports::{get_port_owners, PortOwner},
+    rcon::rcon_execute,
+    rotation::{
+        generate_rotation, push_mapcycle, read_rotation, reorder, write_rotation, Rotation,
+        RotationList, WeightedMap,
+    },
+    shutdown::{StopMethod, StopOptions},
+    status::{
+        ban_player_target, current_target, kick, move_team, players, slay, PlayerTarget, Team,
+    },
+    users::{
+        add_admin_entry, get_admin_backend, list_admins, remove_admin_entry, set_admin_backend,
+        update_admin_entry, AdminEntry, AdminReload,
+    },
+    webhook::{add_webhook, list_webhooks, remove_webhook, wait_for_pending},
+};
+
+const EXIT_FAILURE: u8 = 1;
+const EXIT_USAGE: u8 = 2;
+const EXIT_NOT_RUNNING: u8 = 3;
+const WEBHOOK_GRACE: Duration = Duration::from_secs(10);
+
+#[derive(Parser)]
+#[command(
+    name = "canalhas",
+    version,
+    about = "Gerencia servidores HLDS pela linha de comando"
+)]
+struct Cli {
+    /// Instância alvo (padrão: "default")
+    #[arg(short, long, global = true)]
+    instance: Option<String>,
+
+    /// Saída em JSON
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Inicia o servidor em segundo plano
+    Start {
+        /// Encerra processos que não foram iniciados pelo manager e ocupam a porta
+        #[arg(long)]
+        force: bool,
+        /// PIDs que --force pode encerrar, obrigatório fora de um terminal
+        #[arg(long, value_delimiter = ',', requires = "force")]
+        force_pids: Vec<u32>,
+    },
+    /// Para o servidor com contagem regressiva no chat
+    Stop {
+        #[arg(long, default_value_t = 10)]
+        countdown: u32,
+        #[arg(long)]
+        message: Option<String>,
+        /// Segundos de espera após o `quit` antes de enviar SIGTERM
+        #[arg(long, default_value_t = 15)]
+        quit_timeout: u64,
+        /// Encerra imediatamente, sem contagem nem `quit`
+        #[arg(long)]
+        now: bool,
+    },
+    /// Mostra se o servidor está em execução
+    Status,
+    /// Envia um comando ao console via RCON
+    Send {
+        #[arg(required = true, trailing_var_arg = true)]
+        command: Vec<String>,
+    },
+    /// Lista as instâncias configuradas
+    Instances,
+    #[command(subcommand)]
+    Maps(MapsCommand),
+    #[command(subcommand)]
+    Admins(AdminsCommand),
+    #[command(subcommand)]
+    Bans(BansCommand),
+    #[command(subcommand)]
+    Players(PlayersCommand),
+    #[command(subcommand)]
+    Chat(ChatCommand),
+    #[command(subcommand)]
+    Rotation(RotationCommand),
+    #[command(subcommand)]
+    Webhooks(WebhooksCommand),
+}
+
+#[derive(Subcommand)]
+enum MapsCommand {
+    /// Lista os mapas instalados
+    List,
+    /// Move para a lixeira os arquivos de um mapa que nenhum outro usa
+    Delete { map: String },
+    /// Verifica WADs, modelos, sons, sprites e céu usados pelo mapa
+    Deps { map: String },
+    /// Gera ou corrige o .res do mapa
+    Res { map: String },
+    /// Instala um pacote .zip, .rar, .7z ou .tar.gz
+    Install {
+        archive: String,
+        /// Substitui arquivos existentes com conteúdo diferente
+        #[arg(long)]
+        overwrite: bool,
+        /// Só mostra o que seria instalado
+        #[arg(long)]
+        preview: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum AdminsCommand {
+    /// Lista os administradores do users.ini
+    List,
+    /// Adiciona um administrador
+    Add(AdminArgs),
+    /// Atualiza um administrador existente (pelo auth)
+    Update(AdminArgs),
+    /// Remove um administrador
+    Remove { auth: String },
+    /// Mostra ou troca onde os administradores ficam (users-ini ou sql)
+    Backend { backend: Option<AdminBackendKind> },
+}
+
+#[derive(Args)]
+struct AdminArgs {
+    /// SteamID, IP ou nick
+    #[arg(long)]
+    auth: String,
+    #[arg(long, default_value = "")]
+    password: String,
+    #[arg(long)]
+    access: String,
+    #[arg(long, default_value = "ce")]
+    flags: String,
+    #[arg(long)]
+    name: Option<String>,
+}
+
+impl From<AdminArgs> for AdminEntry {
+    fn from(args: AdminArgs) -> Self {
+        AdminEntry {
+            name: args.name,
+            auth: args.auth,
+            password: args.password,
+            access: args.access,
+            flags: args.flags,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum BansCommand {
+    /// Lista os banimentos
+    List,
+    /// Bane um SteamID ou IP
+    Add {
+        target: String,
+        /// Duração em minutos (0 = permanente)
+        #[arg(long, default_value_t = 0)]
+        minutes: u32,
+        #[arg(long)]
+        reason: Option<String>,
+        #[arg(long)]
+        by: Option<String>,
+    },
+    /// Remove um banimento
+    Remove { target: String },
+}
+
+#[derive(Subcommand)]
+enum PlayersCommand {
+    /// Busca jogadores por SteamID, nick ou IP
+    Search {
+        /// Trecho do SteamID ou de qualquer nick já usado
+        text: Option<String>,
+        /// IP exato, ou prefixo terminado em "." (ex.: 192.168.0.)
+        #[arg(long)]
+        ip: Option<String>,
+        #[arg(long, default_value_t = 50)]
+        limit: u32,
+    },
+    /// Mostra nicks, IPs e sessões de um jogador
+    Show { player_id: String },
+    /// Lista quem está no servidor agora (saída do `status`)
+    Online,
+    /// Expulsa um jogador pelo userid
+    Kick {
+        userid: u32,
+        #[arg(long)]
+        reason: Option<String>,
+    },
+    /// Bane um jogador conectado pelo SteamID (ou IP, sem SteamID válido)
+    Ban {
+        userid: u32,
+        /// Duração em minutos (0 = permanente)
+        #[arg(long, default_value_t = 0)]
+        minutes: u32,
+        #[arg(long)]
+        reason: Option<String>,
+        #[arg(long)]
+        by: Option<String>,
+    },
+    /// Mata um jogador (amx_slay)
+    Slay { userid: u32 },
+    /// Move um jogador de time (t, ct ou spec; requer amx_team)
+    Move { userid: u32, team: Team },
+}
+
+#[derive(Subcommand)]
+enum RotationCommand {
+    /// Mostra o mapcycle ou o maps.ini e os mapas não instalados
+    Show {
+        #[arg(default_value = "mapcycle")]
+        list: RotationList,
+    },
+    /// Substitui a lista pelos mapas informados, nesta ordem
+    Set {
+        list: RotationList,
+        #[arg(required = true)]
+        maps: Vec<String>,
+        /// Aplica o mapcycle no servidor em execução
+        #[arg(long)]
+        push: bool,
+    },
+    /// Gera uma rotação aleatória a partir de mapa[:peso]
+    Generate {
+        list: RotationList,
+        #[arg(required = true)]
+        pool: Vec<WeightedMap>,
+        #[arg(long)]
+        seed: Option<u64>,
+        /// Aplica o mapcycle no servidor em execução
+        #[arg(long)]
+        push: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ChatCommand {
+    /// Busca no histórico do chat
+    Search {
+        text: Option<String>,
+        /// SteamID ou nick
+        #[arg(long)]
+        player: Option<String>,
+        /// Só mensagens que violaram algum filtro
+        #[arg(long)]
+        flagged: bool,
+        #[arg(long, default_value_t = 50)]
+        limit: u32,
+    },
+    /// Lista os filtros de palavras
+    Filters,
+    /// Adiciona um filtro (warn, gag ou kick)
+    AddFilter {
+        pattern: String,
+        #[arg(long, default_value = "warn")]
+        action: ChatAction,
+        /// Minutos de gag
+        #[arg(long, default_value_t = 5)]
+        minutes: u32,
+        /// Aviso ao jogador ou motivo do kick
+        #[arg(long)]
+        message: Option<String>,
+    },
+    /// Remove um filtro pelo padrão
+    RemoveFilter { pattern: String },
+}
+
+#[derive(Subcommand)]
+enum WebhooksCommand {
+    /// Lista os webhooks
+    List,
+    /// Adiciona um webhook
+    Add {
+        url: String,
+        /// Evento a assinar (pode repetir)
+        #[arg(long = "event", required = true)]
+        events: Vec<String>,
+        /// Limita o webhook a uma instância (pode repetir)
+        #[arg(long = "only")]
+        instances: Vec<String>,
+    },
+    /// Remove um webhook pela URL
+    Remove { url: String },
+}
+
+#[derive(Serialize)]
+struct Status {
+    instance: String,
+    running: bool,
+    #[serde(flatten)]
+    record: Option<RunRecord>,
+}
+
+struct Output {
+    json: bool,
+}
+
+impl Output {
+    fn print<T: Serialize>(&self, value: &T, human: impl FnOnce(&T)) {
+        if self.json {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(value).unwrap_or_default()
+            );
+        } else {
+            human(value);
+        }
+    }
+
+    fn done(&self, message: &str) {
+        self.print(&serde_json::json!({ "ok": true }), |_| {
+            println!("✅ {}", message)
+        });
+    }
+
+    fn admins_changed(&self, message: &str, reload: &AdminReload) {
+        self.print(reload, |reload| {
+            println!("✅ {}", message);
+            match reload {
+                AdminReload::Live { admins } => {
+                    println!("🔄 Admins recarregados no servidor ({})", admins)
+                }
+                AdminReload::NotRunning => {
+                    println!("ℹ️ Servidor parado; a mudança vale no próximo início")
+                }
+                AdminReload::Unconfirmed { reason } => println!("⚠️ {}", reason),
+            }
+        });
+    }
+
+    fn error(&self, message: &str) {
+        if self.json {
+            println!("{}", serde_json::json!({ "error": message }));
+        } else {
+            eprintln!("❌ {}", message);
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    // Commands spawn webhook tasks through Tauri's runtime; share ours with it.
+    tauri::async_runtime::set(tokio::runtime::Handle::current());
+
+    let cli = Cli::parse();
+    let out = Output { json: cli.json };
+    let instance_id = resolve_instance_id(cli.instance);
+
+    let code = match run(cli.command, &instance_id, &out).await {
+        Ok(code) => code,
+        Err(e) => {
+            out.error(&e);
+            ExitCode::from(EXIT_FAILURE)
+        }
+    };
+
+    wait_for_pending(WEBHOOK_GRACE).await;
+    code
+}
+
+async fn run(command: Commands, instance_id: &str, out: &Output) -> Result<ExitCode, String> {
+    match command {
+        Commands::Start { force, force_pids } => {
+            let confirmed = if !force {
+                vec![]
+            } else if !force_pids.is_empty() {
+                force_pids
+            } else if io::stdin().is_terminal() {
+                confirm_port_owners(instance_id)?
+            } else {
+                // Nobody can confirm what gets killed from cron or a script.
+                out.error("--force fora de um terminal exige --force-pids <PID,...>");
+                return Ok(ExitCode::from(EXIT_USAGE));
+            };
+            let pid = start_detached(instance_id, &confirmed)?;
+            out.print(&serde_json::json!({ "pid": pid }), |_| {
+                println!("🚀 Servidor '{}' iniciado (PID {})", instance_id, pid)
+            });
+        }
+        Commands::Stop {
+            countdown,
+            message,
+            quit_timeout,
+            now,
+        } => {
+            let options = (!now).then_some(StopOptions {
+                countdown,
+                message,
+                quit_timeout,
+            });
+            let id = instance_id.to_string();
+            let method = tokio::task::spawn_blocking(move || {
+                let state = HldsState::default();
+                let runtime = state.runtime(&id);
+                if !adopt(&id, &runtime) {
+                    return StopMethod::NotRunning;
+                }
+                stop_process(&id, &runtime, options)
+            })
+            .await
+            .map_err(|e| e.to_string())?;
+
+            out.print(&serde_json::json!({ "method": method }), |_| {
+                println!("🛑 {}", method.description())
+            });
+        }
+        Commands::Status => {
+            let record = read_run_record(instance_id).filter(RunRecord::is_alive);
+            let status = Status {
+                instance: instance_id.to_string(),
+                running: record.is_some(),
+                record,
+            };
+            out.print(&status, |s| match &s.record {
+                Some(r) => println!(
+                    "🟢 '{}' em execução (PID {}, desde {})",
+                    s.instance,
+                    r.pid,
+                    r.started_at.format("%d/%m/%Y %H:%M:%S")
+                ),
+                None => println!("🔴 '{}' parado", s.instance),
+            });
+            if !status.running {
+                return Ok(ExitCode::from(EXIT_NOT_RUNNING));
+            }
+        }
+        Commands::Send { command } => {
+            if !read_run_record(instance_id).is_some_and(|r| r.is_alive()) {
+                out.error("O servidor não está em execução.");
+                return Ok(ExitCode::from(EXIT_NOT_RUNNING));
+            }
+            let config = get_instance(Some(instance_id))?.server_config;
+            let command = command.join(" ");
+            let output = tokio::task::spawn_blocking(move || rcon_execute(&config, &command))
+                .await
+                .map_err(|e| e.to_string())??;
+            out.print(&serde_json::json!({ "output": output }), |_| {
+                print!("{}", output)
+            });
+        }
+        Commands::Instances => {
+            let instances = list_instances()?;
+            out.print(&instances, |list| {
+                for i in list {
+                    let running = read_run_record(&i.id).is_some_and(|r| r.is_alive());
+                    println!(
+                        "{} {}\t{}\tporta {}",
+                        if running { "🟢" } else { "🔴" },
+                        i.id,
+                        i.name,
+                        i.server_config.port
+                    );
+                }
+            });
+        }
+        Commands::Maps(MapsCommand::List) => {
+            let maps = list_maps(Some(instance_id.to_string())).await?;
+            out.print(&maps, |maps| {
+                for m in maps {
+                    match (&m.metadata, &m.error) {
+                        (Some(info), _) => println!(
+                            "{}\t{} CT / {} T\t{}",
+                            m.name,
+                            info.ct_spawns,
+                            info.t_spawns,
+                            info.skyname.as_deref().unwrap_or("-")
+                        ),
+                        (None, error) => {
+                            println!("{}\t⚠️ {}", m.name, error.as_deref().unwrap_or(""))
+                        }
+                    }
+                }
+            });
+        }
+        Commands::Maps(MapsCommand::Delete { map }) => {
+            let deletion = delete_map_files(map, Some(instance_id.to_string())).await?;
+            out.print(&deletion, |d| {
+                for f in &d.trashed {
+                    println!("🗑️ {}", f);
+                }
+                for f in &d.kept {
+                    println!("📎 {} (mantido)", f);
+                }
+                if let Some(trash) = &d.trash_path {
+                    println!("✅ Mapa '{}' movido para {}", d.map, trash);
+                }
+            });
+        }
+        Commands::Maps(MapsCommand::Deps { map }) => {
+            let instance = get_instance(Some(instance_id))?;
+            let deps = resolve_dependencies(&instance, &map)?;
+            out.print(&deps, |deps| {
+                for a in &deps.assets {
+                    let status = match &a.found {
+                        Some(found) => format!("✅ {}", found),
+                        None => "❌ ausente".to_string(),
+                    };
+                    println!("{:?}\t{}\t{}", a.kind, a.path, status);
+                }
+            });
+            if !deps.missing.is_empty() {
+                return Ok(ExitCode::from(EXIT_FAILURE));
+            }
+        }
+        Commands::Maps(MapsCommand::Res { map }) => {
+            let instance = get_instance(Some(instance_id))?;
+            let update = write_res(&instance, &map)?;
+            out.print(&update, |u| {
+                println!(
+                    "📦 {} ({} arquivos, +{} -{})",
+                    u.path,
+                    u.entries.len(),
+                    u.added.len(),
+                    u.removed.len()
+                )
+            });
+        }
+        Commands::Maps(MapsCommand::Install {
+            archive,
+            overwrite,
+            preview,
+        }) => {
+            let package = if preview {
+                preview_map_package(archive, Some(instance_id.to_string())).await?
+            } else {
+                install_map_package(archive, Some(overwrite), Some(instance_id.to_string())).await?
+            };
+            out.print(&package, print_package);
+        }
+        Commands::Admins(AdminsCommand::List) => {
+            let admins = list_admins(Some(instance_id.to_string())).await?;
+            out.print(&admins, |admins| {
+                for a in admins {
+                    println!(
+                        "{}\t{}\t{}\t{}",
+                        a.auth,
+                        a.access,
+                        a.flags,
+                        a.name.as_deref().unwrap_or("")
+                    );
+                }
+            });
+        }
+        Commands::Admins(AdminsCommand::Add(args)) => {
+            let auth = args.auth.clone();
+            let runtime = HldsState::default().runtime(instance_id);
+            let reload = add_admin_entry(runtime, instance_id.to_string(), args.into()).await?;
+            out.admins_changed(&format!("Administrador '{}' adicionado", auth), &reload);
+        }
+        Commands::Admins(AdminsCommand::Update(args)) => {
+            let auth = args.auth.clone();
+            let runtime = HldsState::default().runtime(instance_id);
+            let reload = update_admin_entry(runtime, instance_id.to_string(), args.into()).await?;
+            out.admins_changed(&format!("Administrador '{}' atualizado", auth), &reload);
+        }
+        Commands::Admins(AdminsCommand::Remove { auth }) => {
+            let runtime = HldsState::default().runtime(instance_id);
+            let reload = remove_admin_entry(runtime, instance_id.to_string(), auth.clone()).await?;
+            out.admins_changed(&format!("Administrador '{}' removido", auth), &reload);
+        }
+        Commands::Admins(AdminsCommand::Backend { backend: None }) => {
+            let backend = get_admin_backend(Some(instance_id.to_string()))?;
+            out.print(&backend, |b| match b {
+                AdminBackendKind::UsersIni => println!("users-ini"),
+                AdminBackendKind::Sql => println!("sql"),
+            });
+        }
+        Commands::Admins(AdminsCommand::Backend {
+            backend: Some(backend),
+        }) => {
+            set_admin_backend(backend, Some(instance_id.to_string()))?;
+            out.done("Backend de administradores atualizado");
+        }
+        Commands::Bans(BansCommand::List) => {
+            let list = bans(instance_id)?;
+            out.print(&list, |list| {
+                for b in list {
+                    let expires = b.expires_at.map_or("permanente".to_string(), |at| {
+                        at.format("até %d/%m/%Y %H:%M").to_string()
+                    });
+                    println!(
+                        "{}\t{}\t{}",
+                        b.target,
+                        expires,
+                        b.reason.as_deref().unwrap_or("")
+                    );
+                }
+            });
+        }
+        Commands::Bans(BansCommand::Add {
+            target,
+            minutes,
+            reason,
+            by,
+        }) => {
+            let new_ban = NewBan {
+                target,
+                minutes,
+                reason,
+                banned_by: by,
+            };
+            let id = instance_id.to_string();
+            let record = tokio::task::spawn_blocking(move || {
+                let runtime = HldsState::default().runtime(&id);
+                ban(&runtime, &id, new_ban)
+            })
+            .await
+            .map_err(|e| e.to_string())??;
+            out.print(&record, |r| println!("🔨 {} banido", r.target));
+        }
+        Commands::Bans(BansCommand::Remove { target }) => {
+            let id = instance_id.to_string();
+            let record = tokio::task::spawn_blocking(move || {
+                let runtime = HldsState::default().runtime(&id);
+                unban(&runtime, &id, &target)
+            })
+            .await
+            .map_err(|e| e.to_string())??;
+            out.print(&record, |r| println!("🔓 {} desbanido", r.target));
+        }
+        Commands::Players(PlayersCommand::Search { text, ip, limit }) => {
+            let query = PlayerQuery {
+                text,
+                ip,
+                limit: Some(limit),
+            };
+            let players = players::search(&query)?;
+            out.print(&players, |players| {
+                for p in players {
+                    println!(
+                        "{}\t{}\t{}\t{}min",
+                        p.player_id,
+                        p.name,
+                        p.last_seen,
+                        p.playtime_secs / 60
+                    );
+                }
+            });
+        }
+        Commands::Players(PlayersCommand::Show { player_id }) => {
+            let player = profile(&player_id)?;
+            out.print(&player, |p| {
+                let s = &p.summary;
+                println!("{} ({})", s.name, s.player_id);
+                println!("Visto de {} a {}", s.first_seen, s.last_seen);
+                println!("Tempo de jogo: {}min", s.playtime_secs / 60);
+                for alias in &p.aliases {
+                    println!("nick\t{}\t{}", alias.value, alias.last_seen);
+                }
+                for ip in &p.ips {
+                    println!("ip\t{}\t{}", ip.value, ip.last_seen);
+                }
+            });
+        }
+        Commands::Players(PlayersCommand::Online) => {
+            let list = on_server(instance_id, players).await?;
+            out.print(&list, |list| {
+                for p in list {
+                    println!(
+                        "#{}\t{}\t{}\t{}\t{}ms\t{}",
+                        p.userid,
+                        p.name,
+                        p.unique_id,
+                        p.frags,
+                        p.ping,
+                        p.address.as_deref().unwrap_or("")
+                    );
+                }
+            });
+        }
+        Commands::Players(PlayersCommand::Kick { userid, reason }) => {
+            let player = on_player(instance_id, userid, move |runtime, id, target| {
+                kick(runtime, id, target, reason.as_deref())
+            })
+            .await?;
+            out.print(&player, |p| println!("👢 {} expulso", p.name));
+        }
+        Commands::Players(PlayersCommand::Ban {
+            userid,
+            minutes,
+            reason,
+            by,
+        }) => {
+            let record = on_player(instance_id, userid, move |runtime, id, target| {
+                ban_player_target(runtime, id, target, minutes, reason, by)
+            })
+            .await?;
+            out.print(&record, |r| println!("🔨 {} banido", r.target));
+        }
+        Commands::Players(PlayersCommand::Slay { userid }) => {
+            let player = on_player(instance_id, userid, slay).await?;
+            out.print(&player, |p| println!("💀 {} morto", p.name));
+        }
+        Commands::Players(PlayersCommand::Move { userid, team }) => {
+            let player = on_player(instance_id, userid, move |runtime, id, target| {
+                move_team(runtime, id, target, team)
+            })
+            .await?;
+            out.print(&player, |p| println!("🔀 {} movido de time", p.name));
+        }
+        Commands::Chat(ChatCommand::Search {
+            text,
+            player,
+            flagged,
+            limit,
+        }) => {
+            let query = ChatQuery {
+                text,
+                player,
+                instance: Some(instance_id.to_string()),
+                flagged_only: flagged,
+                limit: Some(limit),
+                ..Default::default()
+            };
+            let messages = search(&query)?;
+            out.print(&messages, |messages| {
+                for m in messages.iter().rev() {
+                    let flag = if m.flagged.is_some() { "🚩 " } else { "" };
+                    println!("[{}] {}{}: {}", m.sent_at, flag, m.name, m.message);
+                }
+            });
+        }
+        Commands::Chat(ChatCommand::Filters) => {
+            let rules = read_chat_filters();
+            out.print(&rules, |rules| {
+                for r in rules {
+                    println!(
+                        "{}\t{:?}\t{}",
+                        r.pattern,
+                        r.action,
+                        r.message.as_deref().unwrap_or("")
+                    );
+                }
+            });
+        }
+        Commands::Chat(ChatCommand::AddFilter {
+            pattern,
+            action,
+            minutes,
+            message,
+        }) => {
+            let mut rules = read_chat_filters();
+            rules.retain(|r| r.pattern != pattern);
+            rules.push(ChatRule {
+                pattern,
+                action,
+                minutes,
+                message,
+            });
+            write_chat_filters(&rules)?;
+            out.done("Filtro adicionado");
+        }
+        Commands::Chat(ChatCommand::RemoveFilter { pattern }) => {
+            let mut rules = read_chat_filters();
+            let before = rules.len();
+            rules.retain(|r| r.pattern != pattern);
+            if rules.len() == before {
+                return Err(format!("Filtro '{}' não encontrado.", pattern));
+            }
+            write_chat_filters(&rules)?;
+            out.done("Filtro removido");
+        }
+        Commands::Rotation(RotationCommand::Show { list }) => {
+            let rotation = read_rotation(&get_instance(Some(instance_id))?, list)?;
+            out.print(&rotation, print_rotation);
+            if !rotation.missing.is_empty() {
+                return Ok(ExitCode::from(EXIT_FAILURE));
+            }
+        }
+        Commands::Rotation(RotationCommand::Set { list, maps, push }) => {
+            let rotation = save_rotation_list(instance_id, list, maps, push)?;
+            out.print(&rotation, print_rotation);
+        }
+        Commands::Rotation(RotationCommand::Generate {
+            list,
+            pool,
+            seed,
+            push,
+        }) => {
+            let maps = generate_rotation(&pool, seed)?;
+            let rotation = save_rotation_list(instance_id, list, maps, push)?;
+            out.print(&rotation, print_rotation);
+        }
+        Commands::Webhooks(WebhooksCommand::List) => {
+            let hooks = list_webhooks();
+            out.print(&hooks, |hooks| {
+                for h in hooks {
+                    let scope = if h.instances.is_empty() {
+                        "todas".to_string()
+                    } else {
+                        h.instances.join(",")
+                    };
+                    println!("{}\t{}\t[{}]", h.url, h.events.join(","), scope);
+                }
+            });
+        }
+        Commands::Webhooks(WebhooksCommand::Add {
+            url,
+            events,
+            instances,
+        }) => {
+            add_webhook(url, events, Some(instances))?;
+            out.done("Webhook adicionado");
+        }
+        Commands::Webhooks(WebhooksCommand::Remove { url }) => {
+            remove_webhook(url)?;
+            out.done("Webhook removido");
+        }
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+async fn on_server<T, F>(instance_id: &str, f: F) -> Result<T, String>
+where
+    T: Send + 'static,
+    F: FnOnce(&InstanceRuntime, &str) -> Result<T, String> + Send + 'static,
+{
+    let id = instance_id.to_string();
+    tokio::task::spawn_blocking(move || f(&HldsState::default().runtime(&id), &id))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+async fn on_player<T, F>(instance_id: &str, userid: u32, f: F) -> Result<T, String>
+where
+    T: Send + 'static,
+    F: FnOnce(&InstanceRuntime, &str, &PlayerTarget) -> Result<T, String> + Send + 'static,
+{
+    on_server(instance_id, move |runtime, id| {
+        let target = current_target(runtime, id, userid)?;
+        f(runtime, id, &target)
+    })
+    .await
+}
+
+// Lists who holds the game port right now; only these PIDs may be killed.
+fn confirm_port_owners(instance_id: &str) -> Result<Vec<u32>, String> {
+    let owners: Vec<PortOwner> = get_port_owners(Some(instance_id.to_string()))?
+        .into_iter()
+        .filter(|o| !o.managed)
+        .collect();
+    if owners.is_empty() {
+        return Ok(vec![]);
+    }
+
+    for owner in &owners {
+        eprintln!(
+            "⚠️ {} (PID {}): {}",
+            owner.name.as_deref().unwrap_or("?"),
+            owner
+                .pid
+                .map(|p| p.to_string())
+                .unwrap_or_else(|| "?".into()),
+            owner.command.as_deref().unwrap_or("")
+        );
+    }
+
+    eprint!("Encerrar esses processos? [s/N] ");
+    let _ = io::stderr().flush();
+    let mut answer = String::new();
+    io::stdin()
+        .lock()
+        .read_line(&mut answer)
+        .map_err(|e| e.to_string())?;
+    if !answer.trim().eq_ignore_ascii_case("s") {
+        return Err("Início cancelado".into());
+    }
+
+    Ok(owners.into_iter().filter_map(|o| o.pid).collect())
+}
+
+fn print_package(package: &MapPackage) {
+    for f in &package.files {
+        let action = match f.action {
+            FileAction::New => "novo",
+            FileAction::Overwrite => "sobrescreve",
+            FileAction::Unchanged => "igual",
+        };
+        println!("{}\t{}", action, f.path);
+    }
+    for ignored in &package.ignored {
+        println!("ignorado\t{}", ignored);
+    }
+    println!("🗺️ Mapas: {}", package.maps.join(", "));
+}
+
+fn save_rotation_list(
+    instance_id: &str,
+    list: RotationList,
+    maps: Vec<String>,
+    push: bool,
+) -> Result<Rotation, String> {
+    let instance = get_instance(Some(instance_id))?;
+    let previous = read_rotation(&instance, list)?.entries;
+    let mut rotation = write_rotation(&instance, list, reorder(&maps, &previous))?;
+    if push && list == RotationList::Mapcycle {
+        let runtime = HldsState::default().runtime(instance_id);
+        rotation.pushed = push_mapcycle(&runtime, &instance)?;
+    }
+    Ok(rotation)
+}
+
+fn print_rotation(rotation: &Rotation) {
+    for (i, entry) in rotation.entries.iter().enumerate() {
+        let status = if rotation.missing.contains(&entry.map) {
+            "❌"
+        } else {
+            "✅"
+        };
+        println!(
+            "{:>3}. {} {}\t{}",
+            i + 1,
+            status,
+            entry.map,
+            entry.extra.as_deref().unwrap_or("")
+        );
+    }
+    if !rotation.missing.is_empty() {
+        println!("⚠️ Mapas não instalados: {}", rotation.missing.join(", "));
+    }
+    if rotation.pushed {
+        println!("🔁 Mapcycle aplicado no servidor (vale a partir da próxima troca de mapa)");
+    }
+}
@@ -0,0 +1,386 @@
+use std::{fmt, net::Ipv4Addr};
+
+use serde::Serialize;
+
+use crate::commands::users::AdminEntry;
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct FlagInfo {
+    pub flag: char,
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
+const fn flag(flag: char, name: &'static str, description: &'static str) -> FlagInfo {
+    FlagInfo {
+        flag,
+        name,
+        description,
+    }
+}
+
+// Access levels as defined in amxconst.inc (ADMIN_IMMUNITY .. ADMIN_USER).
+pub const ACCESS_FLAGS: &[FlagInfo] = &[
+    flag(
+        'a',
+        "Imunidade",
+        "Não pode ser kickado, banido ou afetado por outros admins",
+    ),
+    flag('b', "Reserva", "Pode entrar usando os slots reservados"),
+    flag('c', "Kick", "amx_kick"),
+    flag('d', "Ban", "amx_ban e amx_unban"),
+    flag('e', "Slay", "amx_slay e amx_slap"),
+    flag('f', "Mapa", "amx_map"),
+    flag('g', "Cvar", "amx_cvar (exceto cvars protegidas)"),
+    flag('h', "Config", "amx_cfg"),
+    flag('i', "Chat", "amx_chat e outros comandos de chat"),
+    flag('j', "Votação", "amx_vote e outras votações"),
+    flag('k', "Senha", "Alterar sv_password via amx_cvar"),
+    flag('l', "RCON", "amx_rcon e cvars protegidas"),
+    flag('m', "Nível A", "Nível personalizado A"),
+    flag('n', "Nível B", "Nível personalizado B"),
+    flag('o', "Nível C", "Nível personalizado C"),
+    flag('p', "Nível D", "Nível personalizado D"),
+    flag('q', "Nível E", "Nível personalizado E"),
+    flag('r', "Nível F", "Nível personalizado F"),
+    flag('s', "Nível G", "Nível personalizado G"),
+    flag('t', "Nível H", "Nível personalizado H"),
+    flag('u', "Menus", "Acesso aos menus de administração"),
+    flag('v', "Sem uso", "Não usada pelo AMX Mod X"),
+    flag('w', "Sem uso", "Não usada pelo AMX Mod X"),
+    flag('x', "Sem uso", "Não usada pelo AMX Mod X"),
+    flag('y', "Sem uso", "Não usada pelo AMX Mod X"),
+    flag('z', "Usuário", "Jogador comum, sem privilégios de admin"),
+];
+
+pub const ACCOUNT_FLAGS: &[FlagInfo] = &[
+    flag(
+        'a',
+        "Kick por senha",
+        "Desconecta o jogador se a senha estiver errada",
+    ),
+    flag('b', "Tag de clã", "O auth é uma tag contida no nick"),
+    flag('c', "SteamID", "O auth é um SteamID/VALVE ID"),
+    flag('d', "IP", "O auth é um endereço IP"),
+    flag('e', "Sem senha", "A senha não é verificada"),
+    flag(
+        'k',
+        "Maiúsculas",
+        "Nick ou tag diferenciam maiúsculas de minúsculas",
+    ),
+];
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct AccessPreset {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub access: &'static str,
+    pub flags: &'static str,
+}
+
+pub const PRESETS: &[AccessPreset] = &[
+    AccessPreset {
+        id: "vip",
+        name: "VIP (slot reservado)",
+        access: "b",
+        flags: "ce",
+    },
+    AccessPreset {
+        id: "moderator",
+        name: "Moderador",
+        access: "bceiju",
+        flags: "ce",
+    },
+    AccessPreset {
+        id: "admin",
+        name: "Administrador",
+        access: "bcdefgijmnopqrstu",
+        flags: "ce",
+    },
+    AccessPreset {
+        id: "full_admin",
+        name: "Administrador completo",
+        access: "abcdefghijklmnopqrstu",
+        flags: "ce",
+    },
+];
+
+// Same bit layout AMXX uses internally: 'a' is bit 0 ... 'z' is bit 25.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AccessFlags(u32);
+
+impl AccessFlags {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        if value.is_empty() {
+            return Err("Informe ao menos uma flag de acesso (use 'z' para jogador comum).".into());
+        }
+
+        value.chars().try_fold(Self(0), |acc, c| match c {
+            'a'..='z' => Ok(Self(acc.0 | 1 << (c as u32 - 'a' as u32))),
+            _ => Err(format!(
+                "Flag de acesso inválida: '{}'. Use letras de a a z.",
+                c
+            )),
+        })
+    }
+
+    pub fn contains(&self, flag: char) -> bool {
+        flag.is_ascii_lowercase() && self.0 & 1 << (flag as u32 - 'a' as u32) != 0
+    }
+
+    pub fn describe(&self) -> Vec<FlagInfo> {
+        ACCESS_FLAGS
+            .iter()
+            .filter(|info| self.contains(info.flag))
+            .copied()
+            .collect()
+    }
+}
+
+impl fmt::Display for AccessFlags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        ('a'..='z')
+            .filter(|c| self.contains(*c))
+            .try_for_each(|c| write!(f, "{}", c))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthKind {
+    Name,
+    ClanTag,
+    SteamId,
+    Ip,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub struct AccountFlags {
+    pub kick_on_bad_password: bool,
+    pub clan_tag: bool,
+    pub steam_id: bool,
+    pub ip: bool,
+    pub no_password: bool,
+    pub case_sensitive: bool,
+}
+
+impl AccountFlags {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let mut flags = Self::default();
+        for c in value.chars() {
+            match c {
+                'a' => flags.kick_on_bad_password = true,
+                'b' => flags.clan_tag = true,
+                'c' => flags.steam_id = true,
+                'd' => flags.ip = true,
+                'e' => flags.no_password = true,
+                'k' => flags.case_sensitive = true,
+                _ => {
+                    return Err(format!(
+                        "Flag de conta inválida: '{}'. Use a, b, c, d, e ou k.",
+                        c
+                    ))
+                }
+            }
+        }
+
+        if flags.steam_id && flags.ip {
+            return Err("As flags 'c' (SteamID) e 'd' (IP) não podem ser usadas juntas.".into());
+        }
+        if flags.clan_tag && (flags.steam_id || flags.ip) {
+            return Err("A flag 'b' (tag de clã) só vale para autenticação por nick.".into());
+        }
+        if flags.case_sensitive && (flags.steam_id || flags.ip) {
+            return Err("A flag 'k' só vale para autenticação por nick ou tag.".into());
+        }
+        if flags.kick_on_bad_password && flags.no_password {
+            return Err("As flags 'a' (kick por senha) e 'e' (sem senha) se contradizem.".into());
+        }
+        Ok(flags)
+    }
+
+    pub fn auth_kind(&self) -> AuthKind {
+        if self.steam_id {
+            AuthKind::SteamId
+        } else if self.ip {
+            AuthKind::Ip
+        } else if self.clan_tag {
+            AuthKind::ClanTag
+        } else {
+            AuthKind::Name
+        }
+    }
+
+    pub fn describe(&self) -> Vec<FlagInfo> {
+        let set = self.to_string();
+        ACCOUNT_FLAGS
+            .iter()
+            .filter(|info| set.contains(info.flag))
+            .copied()
+            .collect()
+    }
+
+    fn check_auth(&self, auth: &str) -> Result<(), String> {
+        match self.auth_kind() {
+            AuthKind::SteamId if !(auth.starts_with("STEAM_") || auth.starts_with("VALVE_")) => {
+                Err(format!(
+                    "'{}' não é um SteamID válido (ex: STEAM_0:1:12345).",
+                    auth
+                ))
+            }
+            // The stock users.ini ships a "loopback" entry for listen servers.
+            AuthKind::Ip if auth != "loopback" && auth.parse::<Ipv4Addr>().is_err() => {
+                Err(format!("'{}' não é um endereço IP válido.", auth))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for AccountFlags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let set = [
+            ('a', self.kick_on_bad_password),
+            ('b', self.clan_tag),
+            ('c', self.steam_id),
+            ('d', self.ip),
+            ('e', self.no_password),
+            ('k', self.case_sensitive),
+        ];
+        set.iter()
+            .filter(|(_, on)| *on)
+            .try_for_each(|(c, _)| write!(f, "{}", c))
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct AdminFlagCatalog {
+    pub access: &'static [FlagInfo],
+    pub account: &'static [FlagInfo],
+    pub presets: &'static [AccessPreset],
+}
+
+#[derive(Debug, Serialize)]
+pub struct AdminFlagsReport {
+    pub entry: AdminEntry,
+    pub auth_kind: AuthKind,
+    pub access: Vec<FlagInfo>,
+    pub account: Vec<FlagInfo>,
+}
+
+// Checks an entry against the flag rules and returns it with its flags in
+// canonical order, which is what gets written to users.ini.
+pub fn validate_entry(entry: &AdminEntry) -> Result<AdminEntry, String> {
+    let access = AccessFlags::parse(entry.access.trim())?;
+    let account = AccountFlags::parse(entry.flags.trim())?;
+    account.check_auth(entry.auth.trim())?;
+
+    Ok(AdminEntry {
+        access: access.to_string(),
+        flags: account.to_string(),
+        ..entry.clone()
+    })
+}
+
+#[tauri::command]
+pub fn get_admin_flags() -> AdminFlagCatalog {
+    AdminFlagCatalog {
+        access: ACCESS_FLAGS,
+        account: ACCOUNT_FLAGS,
+        presets: PRESETS,
+    }
+}
+
+#[tauri::command]
+pub fn validate_admin_flags(entry: AdminEntry) -> Result<AdminFlagsReport, String> {
+    let entry = validate_entry(&entry)?;
+    let access = AccessFlags::parse(&entry.access)?;
+    let account = AccountFlags::parse(&entry.flags)?;
+
+    Ok(AdminFlagsReport {
+        auth_kind: account.auth_kind(),
+        access: access.describe(),
+        account: account.describe(),
+        entry,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(auth: &str, access: &str, flags: &str) -> AdminEntry {
+        AdminEntry {
+            name: None,
+            auth: auth.into(),
+            password: String::new(),
+            access: access.into(),
+            flags: flags.into(),
+        }
+    }
+
+    #[test]
+    fn access_flags_are_letters_only() {
+        let flags = AccessFlags::parse("abcz").unwrap();
+        assert!(flags.contains('a') && flags.contains('z'));
+        assert!(!flags.contains('d'));
+        assert_eq!(flags.describe().len(), 4);
+
+        assert!(AccessFlags::parse("abcz1").unwrap_err().contains("'1'"));
+        assert!(AccessFlags::parse("abC").is_err());
+        assert!(AccessFlags::parse("").is_err());
+    }
+
+    #[test]
+    fn account_flag_conflicts() {
+        for flags in ["ae", "cd", "bc", "bd", "ck", "dk"] {
+            assert!(AccountFlags::parse(flags).is_err(), "{}", flags);
+        }
+        assert!(AccountFlags::parse("f").is_err());
+
+        assert_eq!(AccountFlags::parse("").unwrap().auth_kind(), AuthKind::Name);
+        assert_eq!(
+            AccountFlags::parse("bk").unwrap().auth_kind(),
+            AuthKind::ClanTag
+        );
+        assert_eq!(
+            AccountFlags::parse("ce").unwrap().auth_kind(),
+            AuthKind::SteamId
+        );
+        assert_eq!(AccountFlags::parse("ad").unwrap().auth_kind(), AuthKind::Ip);
+    }
+
+    #[test]
+    fn entries_are_written_in_canonical_order() {
+        let checked = validate_entry(&entry("STEAM_0:1:12345", " zcba ", "ec")).unwrap();
+        assert_eq!(checked.access, "abcz");
+        assert_eq!(checked.flags, "ce");
+
+        let checked = validate_entry(&entry("STEAM_0:1:12345", "ccbb", "cc")).unwrap();
+        assert_eq!(checked.access, "bc");
+        assert_eq!(checked.flags, "c");
+    }
+
+    #[test]
+    fn auth_must_match_its_kind() {
+        assert!(validate_entry(&entry("Fulano", "z", "ce")).is_err());
+        assert!(validate_entry(&entry("VALVE_0:1:12345", "z", "ce")).is_ok());
+        assert!(validate_entry(&entry("192.168.0.300", "z", "de")).is_err());
+        assert!(validate_entry(&entry("192.168.0.30", "z", "de")).is_ok());
+        assert!(validate_entry(&entry("loopback", "z", "de")).is_ok());
+        assert!(validate_entry(&entry("Fulano", "z", "a")).is_ok());
+    }
+
+    #[test]
+    fn presets_are_valid_and_canonical() {
+        for preset in PRESETS {
+            let checked =
+                validate_entry(&entry("STEAM_0:1:12345", preset.access, preset.flags)).unwrap();
+            assert_eq!(checked.access, preset.access, "{}", preset.id);
+            assert_eq!(checked.flags, preset.flags, "{}", preset.id);
+        }
+        assert!(!AccessFlags::parse(PRESETS[0].access).unwrap().contains('a'));
+        let full = PRESETS.iter().find(|p| p.id == "full_admin").unwrap();
+        assert_eq!(full.access, "abcdefghijklmnopqrstu");
+    }
+}
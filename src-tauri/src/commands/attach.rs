@@ -0,0 +1,318 @@
+use std::{
+    fs::{self, File},
+    io::{BufRead, BufReader, Seek, SeekFrom},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+use chrono::Local;
+
+use crate::commands::event::WebhookEvent;
+use crate::commands::hlds::{
+    handle_output_line, run_args, HldsState, InstanceRuntime, ServerConfig,
+};
+use crate::commands::instances::{get_instance, read_instances, ServerInstance};
+use crate::commands::pidfile::{read_run_record, remove_pid_file, RunRecord};
+use crate::commands::rcon::rcon_execute;
+use crate::commands::shutdown::{
+    graceful_shutdown, kill_group, terminate_group, ShutdownTarget, StopMethod, StopOptions,
+};
+use crate::commands::sink::Sink;
+use crate::commands::stats::StatsRecorder;
+use crate::commands::supervisor::CrashRecord;
+use crate::commands::webhook::emit_instance_event;
+
+const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+const TAIL_INTERVAL: Duration = Duration::from_millis(500);
+
+pub struct AttachedProcess {
+    pub record: RunRecord,
+    pub running: Arc<AtomicBool>,
+}
+
+// Called on startup: servers still alive from a previous session are picked up
+// instead of being killed by the next start.
+pub fn reattach_instances(state: &HldsState, sink: &Sink) {
+    let Ok(instances) = read_instances() else {
+        return;
+    };
+
+    for instance in instances {
+        let Some(record) = read_run_record(&instance.id) else {
+            continue;
+        };
+
+        if !record.is_alive() {
+            remove_pid_file(&instance.id);
+            continue;
+        }
+
+        println!(
+            "🔗 HLDS [{}] ainda em execução (PID {}), reanexando",
+            instance.id, record.pid
+        );
+        attach(sink, &instance, record, state.runtime(&instance.id));
+    }
+}
+
+// Takes over a server from its run record without following its logs, for
+// one-shot callers such as the CLI that only need to stop it.
+pub fn adopt(instance_id: &str, runtime: &InstanceRuntime) -> bool {
+    let Some(record) = read_run_record(instance_id).filter(RunRecord::is_alive) else {
+        return false;
+    };
+
+    *runtime.attached.lock().unwrap() = Some(AttachedProcess {
+        record,
+        running: Arc::new(AtomicBool::new(true)),
+    });
+    true
+}
+
+fn attach(
+    sink: &Sink,
+    instance: &ServerInstance,
+    record: RunRecord,
+    runtime: Arc<InstanceRuntime>,
+) {
+    let outdated = record.args != run_args(&instance.server_config);
+    if outdated {
+        println!(
+            "⚠️ HLDS [{}] foi iniciado com uma configuração diferente da atual; reinicie para aplicá-la",
+            instance.id
+        );
+    }
+
+    let running = Arc::new(AtomicBool::new(true));
+    *runtime.attached.lock().unwrap() = Some(AttachedProcess {
+        record: record.clone(),
+        running: running.clone(),
+    });
+
+    // Without stdout the log files are our only output, so make sure they are written.
+    let config = instance.server_config.clone();
+    thread::spawn(move || {
+        if let Err(e) = rcon_execute(&config, "log on") {
+            println!("⚠️ Não foi possível ativar os logs via RCON: {}", e);
+        }
+    });
+
+    if let Ok(game_path) = instance.game_path() {
+        tail_logs(
+            sink.clone(),
+            runtime.clone(),
+            instance.id.clone(),
+            game_path.join("logs"),
+            running.clone(),
+        );
+    }
+    watch(sink.clone(), instance.id.clone(), runtime, running);
+
+    sink.emit(
+        "hlds-attached",
+        serde_json::json!({
+            "instance": instance.id,
+            "pid": record.pid,
+            "started_at": record.started_at,
+            "outdated": outdated,
+        }),
+    );
+}
+
+fn newest_log(logs_path: &Path) -> Option<PathBuf> {
+    fs::read_dir(logs_path)
+        .ok()?
+        .filter_map(Result::ok)
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "log"))
+        .max_by_key(|e| e.metadata().and_then(|m| m.modified()).ok())
+        .map(|e| e.path())
+}
+
+// HLDS opens a new log file on every map change, so follow whichever file is newest.
+fn tail_logs(
+    sink: Sink,
+    runtime: Arc<InstanceRuntime>,
+    instance_id: String,
+    logs_path: PathBuf,
+    running: Arc<AtomicBool>,
+) {
+    thread::spawn(move || {
+        let mut recorder = StatsRecorder::new(&instance_id);
+        let mut pending = Vec::new();
+
+        // Lines written before we attached were already handled by the previous session.
+        let mut current = newest_log(&logs_path).and_then(|path| {
+            let mut file = File::open(&path).ok()?;
+            file.seek(SeekFrom::End(0)).ok()?;
+            Some((path, BufReader::new(file)))
+        });
+
+        while running.load(Ordering::Relaxed) {
+            if let Some((_, reader)) = current.as_mut() {
+                while let Ok(read) = reader.read_until(b'\n', &mut pending) {
+                    if read == 0 || !pending.ends_with(b"\n") {
+                        break;
+                    }
+                    let line = String::from_utf8_lossy(&pending).trim_end().to_string();
+                    handle_output_line(&sink, &runtime, &instance_id, &mut recorder, &line);
+                    pending.clear();
+                }
+            }
+
+            let newest = newest_log(&logs_path);
+            if newest.is_some() && newest.as_ref() != current.as_ref().map(|(path, _)| path) {
+                current = newest.and_then(|path| {
+                    let file = File::open(&path).ok()?;
+                    Some((path, BufReader::new(file)))
+                });
+                pending.clear();
+                if current.is_some() {
+                    continue;
+                }
+            }
+
+            thread::sleep(TAIL_INTERVAL);
+        }
+    });
+}
+
+// There is no child to wait on, so poll the PID. An attached server that dies
+// is reported as a crash but not restarted.
+fn watch(sink: Sink, instance_id: String, runtime: Arc<InstanceRuntime>, running: Arc<AtomicBool>) {
+    thread::spawn(move || loop {
+        thread::sleep(WATCH_INTERVAL);
+
+        let mut guard = runtime.attached.lock().unwrap();
+        let record = match guard.as_ref() {
+            Some(attached) if Arc::ptr_eq(&attached.running, &running) => attached.record.clone(),
+            _ => return,
+        };
+
+        if record.is_alive() {
+            continue;
+        }
+
+        guard.take();
+        drop(guard);
+        running.store(false, Ordering::Relaxed);
+        remove_pid_file(&instance_id);
+
+        println!("💥 HLDS reanexado [{}] encerrou", instance_id);
+        let crash = CrashRecord {
+            at: Local::now(),
+            exit_code: None,
+            signal: None,
+        };
+        runtime.crashes.lock().unwrap().push(crash.clone());
+        sink.emit(
+            "hlds-crash",
+            serde_json::json!({ "instance": instance_id, "crash": crash }),
+        );
+
+        tauri::async_runtime::spawn(async move {
+            emit_instance_event(
+                instance_id,
+                WebhookEvent::ServerCrashed {
+                    exit_code: None,
+                    signal: None,
+                },
+            )
+            .await;
+        });
+        return;
+    });
+}
+
+struct RconTarget {
+    record: RunRecord,
+    config: ServerConfig,
+}
+
+impl ShutdownTarget for RconTarget {
+    fn send_console(&mut self, command: &str) {
+        // `quit` never gets a reply, so a timeout here is expected.
+        let _ = rcon_execute(&self.config, command);
+    }
+
+    fn has_exited(&mut self) -> bool {
+        !self.record.is_alive()
+    }
+
+    fn terminate(&mut self) {
+        terminate_group(self.record.pid);
+    }
+
+    fn force_kill(&mut self) {
+        kill_group(self.record.pid);
+    }
+}
+
+pub fn stop_attached(
+    instance_id: &str,
+    runtime: &InstanceRuntime,
+    options: Option<StopOptions>,
+) -> StopMethod {
+    let Some(attached) = runtime.attached.lock().unwrap().take() else {
+        return StopMethod::NotRunning;
+    };
+    attached.running.store(false, Ordering::Relaxed);
+
+    if !attached.record.is_alive() {
+        remove_pid_file(instance_id);
+        return StopMethod::NotRunning;
+    }
+
+    let config = get_instance(Some(instance_id))
+        .map(|i| i.server_config)
+        .unwrap_or_default();
+    let mut target = RconTarget {
+        record: attached.record,
+        config,
+    };
+
+    let method = match options {
+        Some(options) => graceful_shutdown(&mut target, &options),
+        None => {
+            target.force_kill();
+            StopMethod::Killed
+        }
+    };
+    remove_pid_file(instance_id);
+
+    let id = instance_id.to_string();
+    tauri::async_runtime::spawn(async move {
+        emit_instance_event(id, WebhookEvent::ServerStopped(method)).await;
+    });
+
+    method
+}
+
+// Reattached servers have no stdin, so commands go through RCON and the reply
+// is shown in the console like regular output.
+pub fn send_attached_command(sink: Sink, instance_id: String, command: String) {
+    thread::spawn(move || {
+        let result = get_instance(Some(&instance_id))
+            .and_then(|instance| rcon_execute(&instance.server_config, &command));
+
+        let timestamp = Local::now().format("%H:%M:%S");
+        let lines: Vec<String> = match result {
+            Ok(output) => output
+                .lines()
+                .map(|line| format!("[{}] {}", timestamp, line))
+                .collect(),
+            Err(e) => vec![format!("[{}] ❌ RCON: {}", timestamp, e)],
+        };
+
+        for line in lines {
+            sink.emit(
+                "hlds-log",
+                serde_json::json!({ "instance": instance_id, "line": line }),
+            );
+        }
+    });
+}
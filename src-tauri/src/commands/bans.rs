@@ -0,0 +1,520 @@
+use std::{
+    fs::{self, File},
+    io::Write,
+    net::Ipv4Addr,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use chrono::{DateTime, Local};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::commands::console;
+use crate::commands::event::WebhookEvent;
+use crate::commands::hlds::{HldsState, InstanceRuntime};
+use crate::commands::instances::{get_instance, read_instances, resolve_instance_id};
+use crate::commands::webhook::emit_instance_event;
+
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+const PUSH_TIMEOUT: Duration = Duration::from_millis(500);
+
+// Reasons, authors and expiry for bans; the engine's cfg files only know the target.
+pub static BANS_DIR: Lazy<PathBuf> = Lazy::new(|| {
+    let mut path = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
+    path.push("canalhas-manager/bans");
+    path
+});
+
+static BANS_MUTEX: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BanKind {
+    SteamId,
+    Ip,
+}
+
+impl BanKind {
+    // Targets end up verbatim in console commands, so anything that isn't
+    // exactly a SteamID or an IPv4 address is refused.
+    pub fn detect(target: &str) -> Result<Self, String> {
+        if is_steam_id(target) {
+            Ok(Self::SteamId)
+        } else if target.parse::<Ipv4Addr>().is_ok() {
+            Ok(Self::Ip)
+        } else {
+            Err(format!(
+                "'{}' não é um SteamID nem um endereço IP válido.",
+                target
+            ))
+        }
+    }
+
+    fn file_name(&self) -> &'static str {
+        match self {
+            Self::SteamId => "banned.cfg",
+            Self::Ip => "listip.cfg",
+        }
+    }
+
+    fn add_command(&self) -> &'static str {
+        match self {
+            Self::SteamId => "banid",
+            Self::Ip => "addip",
+        }
+    }
+
+    fn remove_command(&self) -> &'static str {
+        match self {
+            Self::SteamId => "removeid",
+            Self::Ip => "removeip",
+        }
+    }
+
+    fn write_command(&self) -> &'static str {
+        match self {
+            Self::SteamId => "writeid",
+            Self::Ip => "writeip",
+        }
+    }
+}
+
+// STEAM_X:Y:Z / VALVE_X:Y:Z with X in 0-5 and Y in 0-1. STEAM_ID_LAN and
+// STEAM_ID_PENDING are shared by many players and never match.
+fn is_steam_id(target: &str) -> bool {
+    let Some(rest) = target
+        .strip_prefix("STEAM_")
+        .or_else(|| target.strip_prefix("VALVE_"))
+    else {
+        return false;
+    };
+    let parts: Vec<&str> = rest.split(':').collect();
+    match parts.as_slice() {
+        [universe, y, z] => {
+            matches!(*universe, "0" | "1" | "2" | "3" | "4" | "5")
+                && matches!(*y, "0" | "1")
+                && !z.is_empty()
+                && z.bytes().all(|b| b.is_ascii_digit())
+        }
+        _ => false,
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BanRecord {
+    pub kind: BanKind,
+    pub target: String,
+    #[serde(default)]
+    pub reason: Option<String>,
+    #[serde(default)]
+    pub banned_by: Option<String>,
+    #[serde(default)]
+    pub created_at: Option<DateTime<Local>>,
+    // None means permanent.
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Local>>,
+}
+
+impl BanRecord {
+    fn is_expired(&self) -> bool {
+        self.expires_at.is_some_and(|at| at <= Local::now())
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct NewBan {
+    pub target: String,
+    // 0 bans permanently.
+    #[serde(default)]
+    pub minutes: u32,
+    #[serde(default)]
+    pub reason: Option<String>,
+    #[serde(default)]
+    pub banned_by: Option<String>,
+}
+
+fn active(records: &[BanRecord]) -> Vec<BanRecord> {
+    records
+        .iter()
+        .filter(|record| !record.is_expired())
+        .cloned()
+        .collect()
+}
+
+fn metadata_path(instance_id: &str) -> PathBuf {
+    BANS_DIR.join(format!("{}.json", instance_id))
+}
+
+fn read_metadata(instance_id: &str) -> Vec<BanRecord> {
+    fs::read_to_string(metadata_path(instance_id))
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+fn write_metadata(instance_id: &str, records: &[BanRecord]) -> Result<(), String> {
+    fs::create_dir_all(&*BANS_DIR).map_err(|e| e.to_string())?;
+    let json = serde_json::to_string_pretty(records).map_err(|e| e.to_string())?;
+    fs::write(metadata_path(instance_id), json).map_err(|e| e.to_string())
+}
+
+fn ban_file(instance_id: &str, kind: BanKind) -> Result<PathBuf, String> {
+    Ok(get_instance(Some(instance_id))?
+        .game_path()?
+        .join(kind.file_name()))
+}
+
+// Lines look like "banid 0 STEAM_0:1:123" or "addip 0.0 10.0.0.1".
+fn read_ban_file(path: &Path) -> Vec<String> {
+    fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| {
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            match tokens.as_slice() {
+                ["banid" | "addip", _, target, ..] => Some(target.to_string()),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+// Used while the server is down; a running server rewrites these files itself on writeid/writeip.
+fn write_ban_file(path: &Path, kind: BanKind, target: &str, banned: bool) -> Result<(), String> {
+    let text = fs::read_to_string(path).unwrap_or_default();
+    let mut lines: Vec<String> = text
+        .lines()
+        .filter(|line| {
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            !matches!(tokens.as_slice(), [_, _, t, ..] if *t == target)
+        })
+        .map(String::from)
+        .collect();
+
+    if banned {
+        lines.push(format!("{} 0 {}", kind.add_command(), target));
+    }
+
+    let tmp = path.with_extension("cfg.tmp");
+    let result = File::create(&tmp)
+        .and_then(|mut file| {
+            for line in &lines {
+                writeln!(file, "{}", line)?;
+            }
+            file.sync_all()
+        })
+        .and_then(|_| fs::rename(&tmp, path));
+
+    result.map_err(|e| {
+        let _ = fs::remove_file(&tmp);
+        format!("Erro ao gravar {}: {}", path.display(), e)
+    })
+}
+
+// Sends the commands to the live server. Returns false if it isn't running.
+fn push(runtime: &InstanceRuntime, instance_id: &str, commands: &[String]) -> Result<bool, String> {
+    for command in commands {
+        if console::execute(runtime, instance_id, command, |_| true, PUSH_TIMEOUT)?.is_none() {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+pub fn bans(instance_id: &str) -> Result<Vec<BanRecord>, String> {
+    let metadata = read_metadata(instance_id);
+    let mut list = active(&metadata);
+
+    // Bans written by other tools (or amx_ban) have no metadata of ours.
+    // Expired ones stay in the file until the sweeper lifts them.
+    for kind in [BanKind::SteamId, BanKind::Ip] {
+        for target in read_ban_file(&ban_file(instance_id, kind)?) {
+            if !metadata.iter().any(|r| r.target == target) {
+                list.push(BanRecord {
+                    kind,
+                    target,
+                    reason: None,
+                    banned_by: None,
+                    created_at: None,
+                    expires_at: None,
+                });
+            }
+        }
+    }
+    Ok(list)
+}
+
+pub fn ban(
+    runtime: &InstanceRuntime,
+    instance_id: &str,
+    new_ban: NewBan,
+) -> Result<BanRecord, String> {
+    let target = new_ban.target.trim().to_string();
+    let kind = BanKind::detect(&target)?;
+    let now = Local::now();
+
+    let record = BanRecord {
+        kind,
+        target: target.clone(),
+        reason: new_ban.reason.filter(|r| !r.trim().is_empty()),
+        banned_by: new_ban.banned_by.filter(|b| !b.trim().is_empty()),
+        created_at: Some(now),
+        expires_at: (new_ban.minutes > 0)
+            .then(|| now + chrono::Duration::minutes(new_ban.minutes.into())),
+    };
+
+    let _guard = BANS_MUTEX.lock().unwrap();
+
+    // Always permanent for the engine: writeid/writeip skip timed bans, so they
+    // would vanish on the next restart. The sweeper lifts them at expires_at.
+    let mut add = format!("{} 0 {}", kind.add_command(), target);
+    if kind == BanKind::SteamId {
+        add.push_str(" kick");
+    }
+    let live = push(
+        runtime,
+        instance_id,
+        &[add, kind.write_command().to_string()],
+    )?;
+
+    if !live {
+        write_ban_file(&ban_file(instance_id, kind)?, kind, &target, true)?;
+    }
+
+    let mut metadata = read_metadata(instance_id);
+    metadata.retain(|r| r.target != target);
+    metadata.push(record.clone());
+    write_metadata(instance_id, &metadata)?;
+
+    println!("🔨 [{}] {} banido", instance_id, target);
+    let (id, event) = (instance_id.to_string(), record.clone());
+    tauri::async_runtime::spawn(async move {
+        emit_instance_event(id, WebhookEvent::PlayerBanned(event)).await;
+    });
+
+    Ok(record)
+}
+
+fn lift(runtime: &InstanceRuntime, instance_id: &str, target: &str) -> Result<BanRecord, String> {
+    let kind = BanKind::detect(target)?;
+    let mut metadata = read_metadata(instance_id);
+    let file = ban_file(instance_id, kind)?;
+
+    let known = metadata.iter().position(|r| r.target == target);
+    let in_file = read_ban_file(&file).iter().any(|t| t == target);
+    if known.is_none() && !in_file {
+        return Err(format!("Nenhum banimento encontrado para '{}'.", target));
+    }
+
+    let live = push(
+        runtime,
+        instance_id,
+        &[
+            format!("{} {}", kind.remove_command(), target),
+            kind.write_command().to_string(),
+        ],
+    )?;
+    if !live && in_file {
+        write_ban_file(&file, kind, target, false)?;
+    }
+
+    let record = match known {
+        Some(index) => metadata.remove(index),
+        None => BanRecord {
+            kind,
+            target: target.to_string(),
+            reason: None,
+            banned_by: None,
+            created_at: None,
+            expires_at: None,
+        },
+    };
+    write_metadata(instance_id, &metadata)?;
+    Ok(record)
+}
+
+pub fn unban(
+    runtime: &InstanceRuntime,
+    instance_id: &str,
+    target: &str,
+) -> Result<BanRecord, String> {
+    let _guard = BANS_MUTEX.lock().unwrap();
+    let record = lift(runtime, instance_id, target.trim())?;
+
+    println!("🔓 [{}] {} desbanido", instance_id, record.target);
+    let (id, target) = (instance_id.to_string(), record.target.clone());
+    tauri::async_runtime::spawn(async move {
+        emit_instance_event(id, WebhookEvent::BanRemoved(target)).await;
+    });
+
+    Ok(record)
+}
+
+fn sweep(runtime: &InstanceRuntime, instance_id: &str) {
+    let _guard = BANS_MUTEX.lock().unwrap();
+    let expired: Vec<String> = read_metadata(instance_id)
+        .into_iter()
+        .filter(BanRecord::is_expired)
+        .map(|r| r.target)
+        .collect();
+
+    for target in expired {
+        if let Err(e) = lift(runtime, instance_id, &target) {
+            println!("⚠️ Erro ao remover banimento expirado de {}: {}", target, e);
+            continue;
+        }
+
+        println!("⌛ [{}] Banimento de {} expirou", instance_id, target);
+        let id = instance_id.to_string();
+        tauri::async_runtime::spawn(async move {
+            emit_instance_event(id, WebhookEvent::BanExpired(target)).await;
+        });
+    }
+}
+
+// Lifts temporary bans once they expire, for every instance.
+pub fn start_ban_sweeper(runtime_for: impl Fn(&str) -> Arc<InstanceRuntime> + Send + 'static) {
+    thread::spawn(move || loop {
+        for instance in read_instances().unwrap_or_default() {
+            sweep(&runtime_for(&instance.id), &instance.id);
+        }
+        thread::sleep(SWEEP_INTERVAL);
+    });
+}
+
+#[tauri::command]
+pub fn list_bans(instance_id: Option<String>) -> Result<Vec<BanRecord>, String> {
+    bans(&resolve_instance_id(instance_id))
+}
+
+#[tauri::command]
+pub async fn add_ban(
+    state: State<'_, HldsState>,
+    ban: NewBan,
+    instance_id: Option<String>,
+) -> Result<BanRecord, String> {
+    let instance_id = resolve_instance_id(instance_id);
+    let runtime = state.runtime(&instance_id);
+    tauri::async_runtime::spawn_blocking(move || self::ban(&runtime, &instance_id, ban))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub async fn remove_ban(
+    state: State<'_, HldsState>,
+    target: String,
+    instance_id: Option<String>,
+) -> Result<BanRecord, String> {
+    let instance_id = resolve_instance_id(instance_id);
+    let runtime = state.runtime(&instance_id);
+    tauri::async_runtime::spawn_blocking(move || unban(&runtime, &instance_id, &target))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(target: &str, expires_in: Option<i64>) -> BanRecord {
+        BanRecord {
+            kind: BanKind::detect(target).unwrap(),
+            target: target.into(),
+            reason: None,
+            banned_by: None,
+            created_at: None,
+            expires_at: expires_in.map(|m| Local::now() + chrono::Duration::minutes(m)),
+        }
+    }
+
+    #[test]
+    fn detect_accepts_only_exact_targets() {
+        for target in [
+            "STEAM_0:1:123",
+            "STEAM_1:0:4242",
+            "VALVE_0:1:7",
+            "STEAM_5:1:0",
+        ] {
+            assert_eq!(BanKind::detect(target), Ok(BanKind::SteamId), "{}", target);
+        }
+        assert_eq!(BanKind::detect("10.0.0.1"), Ok(BanKind::Ip));
+
+        for target in [
+            "STEAM_0:1:1; quit",
+            "STEAM_0:1:1 kick",
+            "STEAM_0:1:1\nquit",
+            "STEAM_ID_LAN",
+            "STEAM_ID_PENDING",
+            "VALVE_ID_LAN",
+            "STEAM_6:1:1",
+            "STEAM_0:2:1",
+            "STEAM_0:1:",
+            "STEAM_0:1:1:1",
+            "STEAM_0:1:-1",
+            "steam_0:1:1",
+            "10.0.0.256",
+            "10.0.0.1;quit",
+            "",
+        ] {
+            assert!(BanKind::detect(target).is_err(), "{}", target);
+        }
+    }
+
+    #[test]
+    fn ban_file_round_trip() {
+        let dir = std::env::temp_dir().join(format!("canalhas-bans-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("banned.cfg");
+        assert!(read_ban_file(&path).is_empty());
+
+        fs::write(
+            &path,
+            "// banned by hand\nbanid 0 STEAM_0:1:1\nbanid 0.0 STEAM_0:0:2 kick\nexec other.cfg\n",
+        )
+        .unwrap();
+        assert_eq!(read_ban_file(&path), ["STEAM_0:1:1", "STEAM_0:0:2"]);
+
+        write_ban_file(&path, BanKind::SteamId, "STEAM_0:1:3", true).unwrap();
+        write_ban_file(&path, BanKind::SteamId, "STEAM_0:1:1", true).unwrap();
+        assert_eq!(
+            read_ban_file(&path),
+            ["STEAM_0:0:2", "STEAM_0:1:3", "STEAM_0:1:1"]
+        );
+
+        write_ban_file(&path, BanKind::SteamId, "STEAM_0:0:2", false).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "// banned by hand\nexec other.cfg\nbanid 0 STEAM_0:1:3\nbanid 0 STEAM_0:1:1\n"
+        );
+        assert!(!path.with_extension("cfg.tmp").exists());
+
+        let listip = dir.join("listip.cfg");
+        write_ban_file(&listip, BanKind::Ip, "10.0.0.1", true).unwrap();
+        assert_eq!(fs::read_to_string(&listip).unwrap(), "addip 0 10.0.0.1\n");
+        assert_eq!(read_ban_file(&listip), ["10.0.0.1"]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn expired_bans_are_filtered() {
+        let records = [
+            record("STEAM_0:1:1", None),
+            record("STEAM_0:1:2", Some(-1)),
+            record("10.0.0.1", Some(30)),
+            record("10.0.0.2", Some(0)),
+        ];
+        assert!(!records[0].is_expired());
+        assert!(records[1].is_expired());
+        assert!(!records[2].is_expired());
+        assert!(records[3].is_expired());
+
+        let targets: Vec<String> = active(&records).into_iter().map(|r| r.target).collect();
+        assert_eq!(targets, ["STEAM_0:1:1", "10.0.0.1"]);
+    }
+}
@@ -0,0 +1,526 @@
+use std::{
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    path::Path,
+};
+
+use serde::Serialize;
+
+const BSP_VERSION: i32 = 30;
+const LUMP_COUNT: usize = 15;
+const HEADER_SIZE: u64 = 4 + LUMP_COUNT as u64 * 8;
+const LUMP_ENTITIES: usize = 0;
+const LUMP_PLANES: usize = 1;
+const LUMP_MODELS: usize = 14;
+const MODEL_SIZE: u64 = 64;
+// Real entity lumps stay well below this; anything larger is a corrupt header.
+const MAX_ENTITY_LUMP: u64 = 16 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy)]
+struct Lump {
+    offset: u64,
+    length: u64,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct BspEntity {
+    pub properties: Vec<(String, String)>,
+}
+
+impl BspEntity {
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.properties
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn classname(&self) -> &str {
+        self.get("classname").unwrap_or("")
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+pub struct MapBounds {
+    pub mins: [f32; 3],
+    pub maxs: [f32; 3],
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MapObjective {
+    Bomb,
+    Hostage,
+    Vip,
+    Escape,
+}
+
+impl MapObjective {
+    fn from_classname(classname: &str) -> Option<Self> {
+        match classname {
+            "func_bomb_target" | "info_bomb_target" => Some(Self::Bomb),
+            "hostage_entity"
+            | "monster_scientist"
+            | "func_hostage_rescue"
+            | "info_hostage_rescue" => Some(Self::Hostage),
+            "func_vip_safetyzone" | "info_vip_start" => Some(Self::Vip),
+            "func_escapezone" => Some(Self::Escape),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct BspInfo {
+    pub skyname: Option<String>,
+    pub wads: Vec<String>,
+    pub message: Option<String>,
+    pub ct_spawns: usize,
+    pub t_spawns: usize,
+    pub objectives: Vec<MapObjective>,
+    pub bounds: MapBounds,
+    pub entity_count: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct Bsp {
+    pub entities: Vec<BspEntity>,
+    pub bounds: MapBounds,
+}
+
+fn read_i32(bytes: &[u8], at: usize) -> i32 {
+    i32::from_le_bytes(bytes[at..at + 4].try_into().unwrap())
+}
+
+fn read_f32(bytes: &[u8], at: usize) -> f32 {
+    f32::from_le_bytes(bytes[at..at + 4].try_into().unwrap())
+}
+
+fn read_lump(file: &mut File, lump: Lump) -> Result<Vec<u8>, String> {
+    let mut data = vec![0; lump.length as usize];
+    file.seek(SeekFrom::Start(lump.offset))
+        .and_then(|_| file.read_exact(&mut data))
+        .map_err(|e| format!("Erro ao ler BSP: {}", e))?;
+    Ok(data)
+}
+
+fn looks_like_entities(data: &[u8]) -> bool {
+    data.iter()
+        .find(|b| !b.is_ascii_whitespace())
+        .is_some_and(|b| *b == b'{')
+}
+
+// Reads only the header, entity lump and world model, so listing a folder of
+// large maps stays cheap.
+pub fn read_bsp(path: &Path) -> Result<Bsp, String> {
+    let mut file = File::open(path).map_err(|e| format!("Erro ao abrir BSP: {}", e))?;
+    let size = file.metadata().map_err(|e| e.to_string())?.len();
+    if size < HEADER_SIZE {
+        return Err("Arquivo BSP truncado.".into());
+    }
+
+    let mut header = [0u8; HEADER_SIZE as usize];
+    file.read_exact(&mut header)
+        .map_err(|e| format!("Erro ao ler BSP: {}", e))?;
+
+    let version = read_i32(&header, 0);
+    if version != BSP_VERSION {
+        let magic = String::from_utf8_lossy(&header[..4]);
+        return Err(match magic.as_ref() {
+            "IBSP" | "VBSP" => format!("BSP '{}' não é do GoldSrc.", magic),
+            _ => format!(
+                "Versão de BSP não suportada: {} (esperado {}).",
+                version, BSP_VERSION
+            ),
+        });
+    }
+
+    let mut lumps = [Lump {
+        offset: 0,
+        length: 0,
+    }; LUMP_COUNT];
+    for (i, lump) in lumps.iter_mut().enumerate() {
+        let (offset, length) = (read_i32(&header, 4 + i * 8), read_i32(&header, 8 + i * 8));
+        if offset < 0 || length < 0 || offset as u64 + length as u64 > size {
+            return Err(format!("Lump {} fora dos limites do arquivo.", i));
+        }
+        *lump = Lump {
+            offset: offset as u64,
+            length: length as u64,
+        };
+    }
+
+    // Blue Shift maps swap the entity and plane lumps.
+    let mut entity_lump = lumps[LUMP_ENTITIES];
+    if entity_lump.length > MAX_ENTITY_LUMP {
+        return Err("Lump de entidades corrompido.".into());
+    }
+    let mut data = read_lump(&mut file, entity_lump)?;
+    if !looks_like_entities(&data) && lumps[LUMP_PLANES].length <= MAX_ENTITY_LUMP {
+        entity_lump = lumps[LUMP_PLANES];
+        data = read_lump(&mut file, entity_lump)?;
+    }
+    let text = String::from_utf8_lossy(&data);
+    let entities = parse_entities(text.trim_end_matches('\0'))?;
+    if entities.first().map(BspEntity::classname) != Some("worldspawn") {
+        return Err("Mapa sem worldspawn.".into());
+    }
+
+    let models = lumps[LUMP_MODELS];
+    if models.length < MODEL_SIZE || models.length % MODEL_SIZE != 0 {
+        return Err("Lump de modelos corrompido.".into());
+    }
+    let world = read_lump(
+        &mut file,
+        Lump {
+            offset: models.offset,
+            length: MODEL_SIZE,
+        },
+    )?;
+    let bounds = MapBounds {
+        mins: [
+            read_f32(&world, 0),
+            read_f32(&world, 4),
+            read_f32(&world, 8),
+        ],
+        maxs: [
+            read_f32(&world, 12),
+            read_f32(&world, 16),
+            read_f32(&world, 20),
+        ],
+    };
+    if !bounds
+        .mins
+        .iter()
+        .chain(&bounds.maxs)
+        .all(|v| v.is_finite())
+        || (0..3).any(|i| bounds.mins[i] > bounds.maxs[i])
+    {
+        return Err("Limites do mapa inválidos.".into());
+    }
+
+    Ok(Bsp { entities, bounds })
+}
+
+// Same token rules as the engine's COM_Parse: quoted strings, bare words and braces.
+fn tokenize(text: &str) -> Vec<&str> {
+    let mut tokens = vec![];
+    let mut rest = text;
+
+    loop {
+        rest = rest.trim_start();
+        if rest.starts_with("//") {
+            rest = rest.split_once('\n').map_or("", |(_, r)| r);
+            continue;
+        }
+        let Some(c) = rest.chars().next() else {
+            break;
+        };
+
+        match c {
+            '{' | '}' => {
+                tokens.push(&rest[..1]);
+                rest = &rest[1..];
+            }
+            '"' => {
+                let body = &rest[1..];
+                let end = body.find('"').unwrap_or(body.len());
+                tokens.push(&body[..end]);
+                rest = body.get(end + 1..).unwrap_or("");
+            }
+            _ => {
+                let end = rest
+                    .find(|c: char| c.is_whitespace() || c == '{' || c == '}' || c == '"')
+                    .unwrap_or(rest.len());
+                tokens.push(&rest[..end]);
+                rest = &rest[end..];
+            }
+        }
+    }
+
+    tokens
+}
+
+pub fn parse_entities(text: &str) -> Result<Vec<BspEntity>, String> {
+    let mut entities = vec![];
+    let mut tokens = tokenize(text).into_iter();
+
+    while let Some(token) = tokens.next() {
+        if token != "{" {
+            return Err("Lump de entidades corrompido.".into());
+        }
+        let mut entity = BspEntity::default();
+        loop {
+            match tokens.next() {
+                Some("}") => break,
+                Some(key) => {
+                    let value = tokens
+                        .next()
+                        .filter(|v| *v != "}")
+                        .ok_or("Lump de entidades corrompido.")?;
+                    entity.properties.push((key.to_string(), value.to_string()));
+                }
+                None => return Err("Lump de entidades corrompido.".into()),
+            }
+        }
+        entities.push(entity);
+    }
+
+    Ok(entities)
+}
+
+// "\sierra\half-life\cstrike\cs_dust.wad;\...\decals.wad" -> ["cs_dust.wad", "decals.wad"]
+pub fn wad_names(wad: &str) -> Vec<String> {
+    wad.split(';')
+        .filter_map(|entry| entry.rsplit(['\\', '/']).next())
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+impl Bsp {
+    pub fn worldspawn(&self) -> &BspEntity {
+        &self.entities[0]
+    }
+
+    pub fn info(&self) -> BspInfo {
+        let world = self.worldspawn();
+        let count = |classname: &str| {
+            self.entities
+                .iter()
+                .filter(|e| e.classname() == classname)
+                .count()
+        };
+
+        let mut objectives = vec![];
+        for objective in self
+            .entities
+            .iter()
+            .filter_map(|e| MapObjective::from_classname(e.classname()))
+        {
+            if !objectives.contains(&objective) {
+                objectives.push(objective);
+            }
+        }
+
+        let non_empty = |key: &str| {
+            world
+                .get(key)
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+        };
+
+        BspInfo {
+            skyname: non_empty("skyname"),
+            wads: world.get("wad").map(wad_names).unwrap_or_default(),
+            message: non_empty("message"),
+            ct_spawns: count("info_player_start"),
+            t_spawns: count("info_player_deathmatch"),
+            objectives,
+            bounds: self.bounds,
+            entity_count: self.entities.len(),
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) fn set_lump(bytes: &mut [u8], lump: usize, offset: usize, length: usize) {
+        bytes[4 + lump * 8..8 + lump * 8].copy_from_slice(&(offset as i32).to_le_bytes());
+        bytes[8 + lump * 8..12 + lump * 8].copy_from_slice(&(length as i32).to_le_bytes());
+    }
+
+    // A minimal v30 BSP: header, entity lump and one world model.
+    pub(crate) fn bsp_bytes(entities: &str) -> Vec<u8> {
+        let entity_offset = HEADER_SIZE as usize;
+        let models_offset = entity_offset + entities.len();
+
+        let mut bytes = vec![0u8; HEADER_SIZE as usize];
+        bytes[..4].copy_from_slice(&BSP_VERSION.to_le_bytes());
+        set_lump(&mut bytes, LUMP_ENTITIES, entity_offset, entities.len());
+        set_lump(&mut bytes, LUMP_MODELS, models_offset, MODEL_SIZE as usize);
+
+        bytes.extend_from_slice(entities.as_bytes());
+        let mut model = [0u8; MODEL_SIZE as usize];
+        for (i, v) in [-64f32, -64.0, -64.0, 64.0, 64.0, 64.0].iter().enumerate() {
+            model[i * 4..i * 4 + 4].copy_from_slice(&v.to_le_bytes());
+        }
+        bytes.extend_from_slice(&model);
+        bytes
+    }
+
+    const ENTITIES: &str = r#"{
+"classname" "worldspawn"
+"skyname" "des"
+"wad" "\sierra\half-life\cstrike\cs_dust.wad;\sierra\half-life\valve\decals.wad"
+}
+{ "classname" "info_player_start" "origin" "0 0 0" }
+{ "classname" "info_player_start" }
+{ "classname" "info_player_deathmatch" }
+{ "classname" "func_bomb_target" }
+{ "classname" "info_bomb_target" }
+"#;
+
+    fn read(bytes: &[u8]) -> Result<Bsp, String> {
+        let path = std::env::temp_dir().join(format!("canalhas-bsp-{}.bsp", uuid::Uuid::new_v4()));
+        std::fs::write(&path, bytes).unwrap();
+        let bsp = read_bsp(&path);
+        std::fs::remove_file(&path).unwrap();
+        bsp
+    }
+
+    fn read_err(bytes: &[u8]) -> String {
+        read(bytes).unwrap_err()
+    }
+
+    #[test]
+    fn reads_map_info() {
+        let info = read(&bsp_bytes(ENTITIES)).unwrap().info();
+        assert_eq!(info.skyname.as_deref(), Some("des"));
+        assert_eq!(info.wads, ["cs_dust.wad", "decals.wad"]);
+        assert_eq!(info.message, None);
+        assert_eq!((info.ct_spawns, info.t_spawns), (2, 1));
+        assert_eq!(info.objectives, [MapObjective::Bomb]);
+        assert_eq!(info.entity_count, 6);
+        assert_eq!(
+            info.bounds,
+            MapBounds {
+                mins: [-64.0; 3],
+                maxs: [64.0; 3]
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_other_formats() {
+        let mut bytes = bsp_bytes(ENTITIES);
+        bytes[..4].copy_from_slice(&29i32.to_le_bytes());
+        assert_eq!(
+            read_err(&bytes),
+            "Versão de BSP não suportada: 29 (esperado 30)."
+        );
+
+        for magic in ["IBSP", "VBSP"] {
+            bytes[..4].copy_from_slice(magic.as_bytes());
+            assert_eq!(
+                read_err(&bytes),
+                format!("BSP '{}' não é do GoldSrc.", magic)
+            );
+        }
+
+        assert_eq!(
+            read_err(&bytes[..HEADER_SIZE as usize - 1]),
+            "Arquivo BSP truncado."
+        );
+    }
+
+    #[test]
+    fn rejects_lumps_outside_the_file() {
+        let mut bytes = bsp_bytes(ENTITIES);
+        let size = bytes.len();
+        set_lump(&mut bytes, LUMP_MODELS, size - 32, MODEL_SIZE as usize);
+        assert_eq!(read_err(&bytes), "Lump 14 fora dos limites do arquivo.");
+
+        let mut bytes = bsp_bytes(ENTITIES);
+        bytes[4 + 3 * 8..8 + 3 * 8].copy_from_slice(&(-1i32).to_le_bytes());
+        assert_eq!(read_err(&bytes), "Lump 3 fora dos limites do arquivo.");
+
+        // Header and models intact, entity data cut off.
+        let bytes = bsp_bytes(ENTITIES);
+        let cut = HEADER_SIZE as usize + 10;
+        assert_eq!(
+            read_err(&bytes[..cut]),
+            "Lump 0 fora dos limites do arquivo."
+        );
+    }
+
+    #[test]
+    fn rejects_bad_entities() {
+        let bytes = bsp_bytes(r#"{ "classname" "info_player_start" }"#);
+        assert_eq!(read_err(&bytes), "Mapa sem worldspawn.");
+
+        let bytes = bsp_bytes(r#"{ "classname" "worldspawn" "#);
+        assert_eq!(read_err(&bytes), "Lump de entidades corrompido.");
+
+        let bytes = bsp_bytes(r#"{ "classname" "worldspawn" "skyname" }"#);
+        assert_eq!(read_err(&bytes), "Lump de entidades corrompido.");
+    }
+
+    #[test]
+    fn rejects_bad_models() {
+        let models_offset = HEADER_SIZE as usize + ENTITIES.len();
+        for length in [0, MODEL_SIZE as usize - 1] {
+            let mut bytes = bsp_bytes(ENTITIES);
+            set_lump(&mut bytes, LUMP_MODELS, models_offset, length);
+            assert_eq!(read_err(&bytes), "Lump de modelos corrompido.");
+        }
+
+        let mut bytes = bsp_bytes(ENTITIES);
+        // mins.x above maxs.x
+        bytes[models_offset..models_offset + 4].copy_from_slice(&128f32.to_le_bytes());
+        assert_eq!(read_err(&bytes), "Limites do mapa inválidos.");
+
+        let mut bytes = bsp_bytes(ENTITIES);
+        bytes[models_offset..models_offset + 4].copy_from_slice(&f32::NAN.to_le_bytes());
+        assert_eq!(read_err(&bytes), "Limites do mapa inválidos.");
+    }
+
+    #[test]
+    fn reads_blue_shift_lump_order() {
+        let mut bytes = bsp_bytes(ENTITIES);
+        let entity_offset = HEADER_SIZE as usize;
+        let models_offset = entity_offset + ENTITIES.len();
+        // Blue Shift stores planes first: the entity slot now holds binary data.
+        set_lump(
+            &mut bytes,
+            LUMP_ENTITIES,
+            models_offset,
+            MODEL_SIZE as usize,
+        );
+        set_lump(&mut bytes, LUMP_PLANES, entity_offset, ENTITIES.len());
+
+        let bsp = read(&bytes).unwrap();
+        assert_eq!(bsp.worldspawn().get("skyname"), Some("des"));
+        assert_eq!(bsp.entities.len(), 6);
+    }
+
+    #[test]
+    fn tokenizes_like_the_engine() {
+        assert_eq!(
+            tokenize("{\"message\" \"Bem vindo {ao} servidor\"}// comentário\n{bare\"\"}"),
+            [
+                "{",
+                "message",
+                "Bem vindo {ao} servidor",
+                "}",
+                "{",
+                "bare",
+                "",
+                "}"
+            ]
+        );
+        assert_eq!(tokenize("\"sem fim"), ["sem fim"]);
+
+        let entities =
+            parse_entities("// cabeçalho\n{\n\"classname\" \"worldspawn\"\n\"message\" \"\"\n}\n")
+                .unwrap();
+        assert_eq!(entities.len(), 1);
+        assert_eq!(entities[0].classname(), "worldspawn");
+        assert_eq!(entities[0].get("message"), Some(""));
+        assert!(parse_entities("\"classname\" \"worldspawn\"").is_err());
+    }
+
+    #[test]
+    fn splits_wad_paths() {
+        assert_eq!(
+            wad_names("\\sierra\\half-life\\cstrike\\cs_dust.wad; /opt/hlds/valve/halflife.wad;;"),
+            ["cs_dust.wad", "halflife.wad"]
+        );
+        assert_eq!(wad_names("decals.wad"), ["decals.wad"]);
+        assert!(wad_names("").is_empty());
+    }
+}
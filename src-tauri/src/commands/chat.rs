@@ -0,0 +1,457 @@
+use std::{
+    fs::{self, File},
+    io::Write,
+    path::PathBuf,
+    str::FromStr,
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use chrono::Local;
+use once_cell::sync::Lazy;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+
+use crate::commands::console;
+use crate::commands::event::WebhookEvent;
+use crate::commands::hlds::InstanceRuntime;
+use crate::commands::log_parser::{GameEvent, LogEntry, LogPlayer};
+use crate::commands::stats::player_key;
+use crate::commands::webhook::emit_instance_event;
+
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+const ACTION_TIMEOUT: Duration = Duration::from_millis(500);
+const DEFAULT_WARNING: &str = "Linguagem proibida no servidor.";
+
+pub static CHAT_DB_PATH: Lazy<PathBuf> = Lazy::new(|| {
+    let mut path = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
+    path.push("canalhas-manager/chat.db");
+    path
+});
+
+pub static CHAT_FILTERS_PATH: Lazy<PathBuf> = Lazy::new(|| {
+    let mut path = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
+    path.push("canalhas-manager/chat_filters.json");
+    path
+});
+
+static CHAT_DB: Lazy<Mutex<Option<Connection>>> = Lazy::new(|| Mutex::new(None));
+
+// Every `say` line is checked against the rules, so they stay in memory, keyed
+// by the file's mtime like the webhook list and dropped on every local write.
+type CachedRules = Option<(SystemTime, Vec<ChatRule>)>;
+static CHAT_FILTERS_CACHE: Lazy<Mutex<CachedRules>> = Lazy::new(|| Mutex::new(None));
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS messages (
+    id INTEGER PRIMARY KEY,
+    instance TEXT NOT NULL,
+    sent_at TEXT NOT NULL,
+    player_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    team TEXT NOT NULL,
+    message TEXT NOT NULL,
+    team_only INTEGER NOT NULL,
+    dead INTEGER NOT NULL,
+    flagged TEXT
+);
+
+CREATE INDEX IF NOT EXISTS messages_sent_at ON messages (instance, sent_at);
+CREATE INDEX IF NOT EXISTS messages_player ON messages (player_id);
+";
+
+fn with_chat_db<T>(f: impl FnOnce(&Connection) -> rusqlite::Result<T>) -> Result<T, String> {
+    let mut guard = CHAT_DB.lock().unwrap();
+
+    if guard.is_none() {
+        if let Some(parent) = CHAT_DB_PATH.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let conn = Connection::open(&*CHAT_DB_PATH).map_err(|e| e.to_string())?;
+        conn.execute_batch(SCHEMA).map_err(|e| e.to_string())?;
+        *guard = Some(conn);
+    }
+
+    f(guard.as_ref().unwrap()).map_err(|e| e.to_string())
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChatAction {
+    Warn,
+    Gag,
+    Kick,
+}
+
+impl FromStr for ChatAction {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "warn" => Ok(Self::Warn),
+            "gag" => Ok(Self::Gag),
+            "kick" => Ok(Self::Kick),
+            _ => Err(format!(
+                "Ação inválida: '{}'. Use warn, gag ou kick.",
+                value
+            )),
+        }
+    }
+}
+
+fn default_gag_minutes() -> u32 {
+    5
+}
+
+// `pattern` is one or more words matched case-insensitively against whole
+// words of the message; a trailing `*` on a word matches any suffix.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ChatRule {
+    pub pattern: String,
+    pub action: ChatAction,
+    #[serde(default = "default_gag_minutes")]
+    pub minutes: u32,
+    // Shown to the player on warn, used as the reason on kick.
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+fn words(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric() && c != '*')
+        .filter(|w| !w.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+impl ChatRule {
+    pub fn matches(&self, message: &str) -> bool {
+        let pattern = words(&self.pattern);
+        let message: Vec<String> = words(message)
+            .into_iter()
+            .map(|w| w.replace('*', ""))
+            .collect();
+        if pattern.is_empty() || message.len() < pattern.len() {
+            return false;
+        }
+
+        message.windows(pattern.len()).any(|window| {
+            window
+                .iter()
+                .zip(&pattern)
+                .all(|(word, p)| match p.strip_suffix('*') {
+                    Some(prefix) => word.starts_with(prefix),
+                    None => word == p,
+                })
+        })
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if words(&self.pattern)
+            .iter()
+            .all(|w| w.trim_matches('*').is_empty())
+        {
+            return Err(format!("Padrão inválido: '{}'.", self.pattern));
+        }
+        if let Some(message) = &self.message {
+            if message.contains(['"', ';', '\n', '\r']) {
+                return Err(format!(
+                    "A mensagem da regra '{}' não pode conter aspas, ';' ou quebras de linha.",
+                    self.pattern
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn command(&self, userid: i32) -> String {
+        let message = self.message.as_deref().unwrap_or(DEFAULT_WARNING);
+        match self.action {
+            ChatAction::Warn => format!("amx_psay #{} \"{}\"", userid, message),
+            // amx_gag comes from the gag plugin, not the AMXX base install.
+            ChatAction::Gag => format!("amx_gag #{} {}", userid, self.minutes),
+            ChatAction::Kick => format!("kick #{} \"{}\"", userid, message),
+        }
+    }
+}
+
+pub fn read_chat_filters() -> Vec<ChatRule> {
+    let Ok(modified) = fs::metadata(&*CHAT_FILTERS_PATH).and_then(|m| m.modified()) else {
+        return vec![];
+    };
+
+    let mut cache = CHAT_FILTERS_CACHE.lock().unwrap();
+    if let Some((cached_at, rules)) = cache.as_ref() {
+        if *cached_at == modified {
+            return rules.clone();
+        }
+    }
+
+    let rules = load_chat_filters();
+    *cache = Some((modified, rules.clone()));
+    rules
+}
+
+fn load_chat_filters() -> Vec<ChatRule> {
+    fs::read_to_string(&*CHAT_FILTERS_PATH)
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+pub fn write_chat_filters(rules: &[ChatRule]) -> Result<(), String> {
+    for rule in rules {
+        rule.validate()?;
+    }
+
+    if let Some(parent) = CHAT_FILTERS_PATH.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let tmp = CHAT_FILTERS_PATH.with_extension("json.tmp");
+    let data = serde_json::to_string_pretty(rules).map_err(|e| e.to_string())?;
+    let mut file = File::create(&tmp).map_err(|e| e.to_string())?;
+    file.write_all(data.as_bytes()).map_err(|e| e.to_string())?;
+    file.sync_all().map_err(|e| e.to_string())?;
+    let result = fs::rename(&tmp, &*CHAT_FILTERS_PATH).map_err(|e| e.to_string());
+
+    *CHAT_FILTERS_CACHE.lock().unwrap() = None;
+    result
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ChatFlag {
+    pub player: LogPlayer,
+    pub message: String,
+    pub rule: ChatRule,
+}
+
+// Called for every parsed line; stores chat and enforces the filter rules.
+pub fn record_chat(runtime: &Arc<InstanceRuntime>, instance_id: &str, entry: &LogEntry) {
+    let GameEvent::Say {
+        player,
+        message,
+        team_only,
+        dead,
+    } = &entry.event
+    else {
+        return;
+    };
+
+    let rule = read_chat_filters()
+        .into_iter()
+        .find(|rule| rule.matches(message));
+    let sent_at = entry
+        .timestamp
+        .unwrap_or_else(|| Local::now().naive_local())
+        .format(TIMESTAMP_FORMAT)
+        .to_string();
+
+    let stored = with_chat_db(|conn| {
+        conn.execute(
+            "INSERT INTO messages (instance, sent_at, player_id, name, team, message, team_only, dead, flagged)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                instance_id,
+                sent_at,
+                player_key(player),
+                player.name,
+                player.team,
+                message,
+                team_only,
+                dead,
+                rule.as_ref().map(|r| &r.pattern),
+            ],
+        )
+    });
+    if let Err(e) = stored {
+        println!("❌ Erro ao salvar mensagem do chat: {}", e);
+    }
+
+    let Some(rule) = rule else {
+        return;
+    };
+    println!(
+        "🚩 [{}] {} violou a regra '{}': {}",
+        instance_id, player.name, rule.pattern, message
+    );
+
+    // This runs on the thread reading the server's output, which the console
+    // needs free to collect the command's reply.
+    let command = rule.command(player.user_id);
+    let (runtime, id) = (runtime.clone(), instance_id.to_string());
+    thread::spawn(move || {
+        if let Err(e) = console::execute(&runtime, &id, &command, |_| true, ACTION_TIMEOUT) {
+            println!("❌ Erro ao aplicar filtro do chat: {}", e);
+        }
+    });
+
+    let flag = ChatFlag {
+        player: player.clone(),
+        message: message.clone(),
+        rule,
+    };
+    let id = instance_id.to_string();
+    tauri::async_runtime::spawn(async move {
+        emit_instance_event(id, WebhookEvent::ChatFlagged(flag)).await;
+    });
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ChatMessage {
+    pub instance: String,
+    pub sent_at: String,
+    pub player_id: String,
+    pub name: String,
+    pub team: String,
+    pub message: String,
+    pub team_only: bool,
+    pub dead: bool,
+    pub flagged: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+pub struct ChatQuery {
+    pub text: Option<String>,
+    // SteamID or nick.
+    pub player: Option<String>,
+    pub instance: Option<String>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    #[serde(default)]
+    pub flagged_only: bool,
+    pub limit: Option<u32>,
+}
+
+fn like(value: &Option<String>) -> Option<String> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(|v| format!("%{}%", v))
+}
+
+pub fn search(query: &ChatQuery) -> Result<Vec<ChatMessage>, String> {
+    with_chat_db(|conn| {
+        conn.prepare(
+            "SELECT instance, sent_at, player_id, name, team, message, team_only, dead, flagged
+             FROM messages
+             WHERE (?1 IS NULL OR message LIKE ?1)
+               AND (?2 IS NULL OR player_id LIKE ?2 OR name LIKE ?2)
+               AND (?3 IS NULL OR instance = ?3)
+               AND (?4 IS NULL OR sent_at >= ?4)
+               AND (?5 IS NULL OR sent_at <= ?5)
+               AND (?6 = 0 OR flagged IS NOT NULL)
+             ORDER BY sent_at DESC, id DESC
+             LIMIT ?7",
+        )?
+        .query_map(
+            params![
+                like(&query.text),
+                like(&query.player),
+                query.instance,
+                query.from,
+                query.to,
+                query.flagged_only,
+                query.limit.unwrap_or(200),
+            ],
+            |row| {
+                Ok(ChatMessage {
+                    instance: row.get(0)?,
+                    sent_at: row.get(1)?,
+                    player_id: row.get(2)?,
+                    name: row.get(3)?,
+                    team: row.get(4)?,
+                    message: row.get(5)?,
+                    team_only: row.get(6)?,
+                    dead: row.get(7)?,
+                    flagged: row.get(8)?,
+                })
+            },
+        )?
+        .collect()
+    })
+}
+
+#[tauri::command]
+pub async fn search_chat(query: Option<ChatQuery>) -> Result<Vec<ChatMessage>, String> {
+    let query = query.unwrap_or_default();
+    tauri::async_runtime::spawn_blocking(move || search(&query))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub fn list_chat_filters() -> Vec<ChatRule> {
+    read_chat_filters()
+}
+
+#[tauri::command]
+pub fn save_chat_filters(rules: Vec<ChatRule>) -> Result<(), String> {
+    write_chat_filters(&rules)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(pattern: &str) -> ChatRule {
+        ChatRule {
+            pattern: pattern.to_string(),
+            action: ChatAction::Warn,
+            minutes: default_gag_minutes(),
+            message: None,
+        }
+    }
+
+    #[test]
+    fn matches_whole_words() {
+        let noob = rule("Noob");
+        assert!(noob.matches("que NOOB, hein"));
+        assert!(noob.matches("noob!"));
+        assert!(!noob.matches("noobs"));
+        assert!(!noob.matches("supernoob"));
+        assert!(!noob.matches(""));
+    }
+
+    #[test]
+    fn matches_wildcards() {
+        let rule = rule("hack*");
+        assert!(rule.matches("ele usa hack"));
+        assert!(rule.matches("HACKER detectado"));
+        assert!(!rule.matches("wallhack"));
+        // A `*` typed in chat is not a wildcard.
+        assert!(rule.matches("hac*k"));
+        assert!(!rule.matches("h*"));
+    }
+
+    #[test]
+    fn matches_word_sequences() {
+        let rule = rule("vai  tomar*");
+        assert!(rule.matches("ô, vai... TOMARA que ganhe"));
+        assert!(rule.matches("vai tomar banho"));
+        assert!(!rule.matches("tomar vai"));
+        assert!(!rule.matches("vai lá tomar"));
+        assert!(!rule.matches("vai"));
+    }
+
+    #[test]
+    fn validates_rules() {
+        assert!(rule("noob").validate().is_ok());
+        assert!(rule("").validate().is_err());
+        assert!(rule("***").validate().is_err());
+        assert!(rule("!!").validate().is_err());
+
+        for message in ["sem \"aspas\"", "ok; quit", "linha\nnova", "linha\rnova"] {
+            let mut rule = rule("noob");
+            rule.message = Some(message.to_string());
+            assert!(rule.validate().is_err(), "{:?}", message);
+        }
+
+        let mut kick = rule("noob");
+        kick.action = ChatAction::Kick;
+        kick.message = Some("Sem ofensas.".to_string());
+        assert!(kick.validate().is_ok());
+        assert_eq!(kick.command(7), "kick #7 \"Sem ofensas.\"");
+    }
+}
@@ -0,0 +1,84 @@
+use std::{
+    collections::HashMap,
+    io::Write,
+    sync::{
+        mpsc::{self, Sender},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use once_cell::sync::Lazy;
+
+use crate::commands::hlds::InstanceRuntime;
+use crate::commands::instances::get_instance;
+use crate::commands::pidfile::read_run_record;
+use crate::commands::rcon::rcon_execute;
+
+// Callers waiting for the output of a command sent through stdin, per instance.
+static LISTENERS: Lazy<Mutex<HashMap<String, Vec<Sender<String>>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+// Fed with every console line of a managed server.
+pub fn observe_line(instance_id: &str, line: &str) {
+    let mut listeners = LISTENERS.lock().unwrap();
+    if let Some(senders) = listeners.get_mut(instance_id) {
+        senders.retain(|tx| tx.send(line.to_string()).is_ok());
+    }
+}
+
+// Runs a console command and returns what it printed, or None if the server is
+// not running. Managed servers get it on stdin and we collect output until
+// `done` matches or `timeout` runs out; other servers answer through RCON.
+pub fn execute(
+    runtime: &InstanceRuntime,
+    instance_id: &str,
+    command: &str,
+    done: impl Fn(&str) -> bool,
+    timeout: Duration,
+) -> Result<Option<String>, String> {
+    let mut guard = runtime.process.lock().unwrap();
+    if let Some((child, _, _)) = guard.as_mut() {
+        let stdin = child
+            .stdin
+            .as_mut()
+            .ok_or("O console do servidor não está disponível.")?;
+
+        let (tx, rx) = mpsc::channel();
+        LISTENERS
+            .lock()
+            .unwrap()
+            .entry(instance_id.to_string())
+            .or_default()
+            .push(tx);
+
+        stdin
+            .write_all(format!("{}\n", command).as_bytes())
+            .map_err(|e| format!("Erro ao enviar comando: {}", e))?;
+        drop(guard);
+
+        let deadline = Instant::now() + timeout;
+        let mut lines = vec![];
+        while let Some(left) = deadline.checked_duration_since(Instant::now()) {
+            let Ok(line) = rx.recv_timeout(left) else {
+                break;
+            };
+            let finished = done(&line);
+            lines.push(line);
+            if finished {
+                break;
+            }
+        }
+        return Ok(Some(lines.join("\n")));
+    }
+    drop(guard);
+
+    let running = runtime.attached_record().is_some()
+        || read_run_record(instance_id).is_some_and(|r| r.is_alive());
+    if !running {
+        return Ok(None);
+    }
+
+    let config = get_instance(Some(instance_id))?.server_config;
+    rcon_execute(&config, command).map(Some)
+}
@@ -1,50 +1,160 @@
 use chrono::Local;
 
+use crate::commands::bans::BanRecord;
+use crate::commands::chat::{ChatAction, ChatFlag};
+use crate::commands::log_parser::GameEvent;
+use crate::commands::shutdown::StopMethod;
+
 #[derive(Debug, Clone)]
 pub enum WebhookEvent {
     ServerStarted,
-    ServerStopped,
+    ServerStopped(StopMethod),
     ErrorOccurred,
+    MissingAsset {
+        path: String,
+        map: Option<String>,
+        // Every file the current map references that is not on disk.
+        missing: Vec<String>,
+    },
+    ServerCrashed {
+        exit_code: Option<i32>,
+        signal: Option<i32>,
+    },
+    ServerRestarted {
+        attempt: u32,
+    },
+    RestartGaveUp {
+        restarts: u32,
+    },
     MapAdded(String),
     MapDeleted(String),
     MapChanged(String),
-    Custom { title: String, message: String },
+    PlayerBanned(BanRecord),
+    BanRemoved(String),
+    BanExpired(String),
+    ChatFlagged(ChatFlag),
+    Custom {
+        title: String,
+        message: String,
+    },
+    Game(GameEvent),
 }
 
 impl WebhookEvent {
     pub fn name(&self) -> &'static str {
         match self {
             Self::ServerStarted => "server_started",
-            Self::ServerStopped => "server_stopped",
+            Self::ServerStopped(_) => "server_stopped",
             Self::ErrorOccurred => "error_occurred",
+            Self::MissingAsset { .. } => "missing_asset",
+            Self::ServerCrashed { .. } => "server_crashed",
+            Self::ServerRestarted { .. } => "server_restarted",
+            Self::RestartGaveUp { .. } => "restart_gave_up",
             Self::MapAdded(_) => "map_added",
             Self::MapDeleted(_) => "map_deleted",
             Self::MapChanged(_) => "map_changed",
+            Self::PlayerBanned(_) => "player_banned",
+            Self::BanRemoved(_) => "ban_removed",
+            Self::BanExpired(_) => "ban_expired",
+            Self::ChatFlagged(_) => "chat_flagged",
             Self::Custom { .. } => "custom_event",
+            Self::Game(event) => event.name(),
         }
     }
 
     pub fn title(&self) -> String {
         match self {
             Self::ServerStarted => "Servidor Iniciado".into(),
-            Self::ServerStopped => "Servidor Parado".into(),
+            Self::ServerStopped(_) => "Servidor Parado".into(),
             Self::ErrorOccurred => "Erro no Servidor".into(),
+            Self::MissingAsset { path, .. } => format!("Arquivo Ausente: {}", path),
+            Self::ServerCrashed { .. } => "Servidor Caiu".into(),
+            Self::ServerRestarted { .. } => "Servidor Reiniciado".into(),
+            Self::RestartGaveUp { .. } => "Reinício Automático Abandonado".into(),
             Self::MapAdded(name) => format!("Mapa Adicionado: {}", name),
             Self::MapDeleted(name) => format!("Mapa Removido: {}", name),
             Self::MapChanged(name) => format!("Mapa Alterado: {}", name),
+            Self::PlayerBanned(ban) => format!("Jogador Banido: {}", ban.target),
+            Self::BanRemoved(target) => format!("Banimento Removido: {}", target),
+            Self::BanExpired(target) => format!("Banimento Expirado: {}", target),
+            Self::ChatFlagged(flag) => format!("Chat Sinalizado: {}", flag.player.name),
             Self::Custom { title, .. } => title.clone(),
+            Self::Game(event) => event.title(),
         }
     }
 
     pub fn description(&self) -> String {
         match self {
             Self::ServerStarted => "Servidor iniciado com sucesso.".into(),
-            Self::ServerStopped => "Servidor desligado com sucesso.".into(),
+            Self::ServerStopped(method) => {
+                format!("Servidor desligado com sucesso ({}).", method.description())
+            }
             Self::ErrorOccurred => "Um erro crítico ocorreu no servidor.".into(),
+            Self::MissingAsset { path, map, missing } => {
+                let mut text = match map {
+                    Some(map) => format!(
+                        "O servidor não conseguiu abrir `{}` no mapa `{}`.",
+                        path, map
+                    ),
+                    None => format!("O servidor não conseguiu abrir `{}`.", path),
+                };
+                let others: Vec<String> = missing
+                    .iter()
+                    .filter(|m| !m.eq_ignore_ascii_case(path))
+                    .map(|m| format!("`{}`", m))
+                    .collect();
+                if !others.is_empty() {
+                    text.push_str(&format!("\nTambém faltam: {}", others.join(", ")));
+                }
+                text
+            }
+            Self::ServerCrashed { exit_code, signal } => match (exit_code, signal) {
+                (_, Some(signal)) => format!("O servidor caiu com o sinal `{}`.", signal),
+                (Some(code), None) => format!("O servidor caiu com o código `{}`.", code),
+                (None, None) => "O servidor caiu inesperadamente.".into(),
+            },
+            Self::ServerRestarted { attempt } => format!(
+                "O servidor foi reiniciado automaticamente (tentativa {}).",
+                attempt
+            ),
+            Self::RestartGaveUp { restarts } => format!(
+                "O servidor caiu após {} reinícios seguidos e não será mais reiniciado.",
+                restarts
+            ),
             Self::MapAdded(name) => format!("O mapa `{}` foi adicionado com sucesso.", name),
             Self::MapDeleted(name) => format!("O mapa `{}` foi removido do servidor.", name),
             Self::MapChanged(name) => format!("O mapa atual foi alterado para `{}`.", name),
+            Self::PlayerBanned(ban) => {
+                let duration = match (ban.created_at, ban.expires_at) {
+                    (Some(from), Some(to)) => {
+                        format!("por {} minuto(s)", (to - from).num_minutes())
+                    }
+                    _ => "permanentemente".into(),
+                };
+                let mut text = format!("`{}` foi banido {}.", ban.target, duration);
+                if let Some(reason) = &ban.reason {
+                    text.push_str(&format!("\nMotivo: {}", reason));
+                }
+                if let Some(by) = &ban.banned_by {
+                    text.push_str(&format!("\nPor: {}", by));
+                }
+                text
+            }
+            Self::BanRemoved(target) => format!("O banimento de `{}` foi removido.", target),
+            Self::BanExpired(target) => format!("O banimento temporário de `{}` expirou.", target),
+            Self::ChatFlagged(flag) => {
+                let action = match flag.rule.action {
+                    ChatAction::Warn => "avisado".to_string(),
+                    ChatAction::Gag => format!("silenciado por {} minuto(s)", flag.rule.minutes),
+                    ChatAction::Kick => "expulso".to_string(),
+                };
+                format!(
+                    "`{}` ({}) disse: {}\nRegra: `{}` — jogador {}.",
+                    flag.player.name, flag.player.steam_id, flag.message, flag.rule.pattern, action
+                )
+            }
             Self::Custom { message, .. } => message.clone(),
+            Self::Game(event) => event.description(),
         }
     }
 }
@@ -1,4 +1,5 @@
 use std::{
+    collections::HashMap,
     fs::{self, File},
     io::{BufRead, BufReader, Read, Write},
     path::PathBuf,
@@ -10,224 +11,723 @@ use std::{
     thread::{self, JoinHandle},
 };
 
-use chrono::Local;
+use chrono::{DateTime, Local};
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 
-use tauri::Emitter;
-use tauri::{AppHandle, Manager, State};
+use tauri::{AppHandle, State};
 
+use crate::commands::attach::{send_attached_command, stop_attached, AttachedProcess};
+use crate::commands::chat::record_chat;
+use crate::commands::console::observe_line;
 use crate::commands::event::WebhookEvent;
-use crate::commands::webhook::emit_event;
+use crate::commands::instances::{
+    get_instance, read_instances, resolve_instance_id, update_instance, ServerInstance,
+};
+use crate::commands::log_parser::parse_log_line;
+use crate::commands::map_deps::{missing_asset, missing_asset_event};
+use crate::commands::pidfile::{read_run_record, remove_pid_file, write_run_record, RunRecord};
+use crate::commands::players::{close_open_sessions, record_player_event};
+use crate::commands::ports::release_port;
+use crate::commands::shutdown::{
+    force_kill, graceful_shutdown, ShutdownTarget, StopMethod, StopOptions,
+};
+use crate::commands::sink::{app_sink, Sink};
+use crate::commands::stats::StatsRecorder;
+use crate::commands::supervisor::{supervise, CrashRecord};
+use crate::commands::webhook::{emit_instance_event, has_subscribers};
+
+pub type HldsProcess = (Child, JoinHandle<()>, Arc<AtomicBool>);
+
+#[derive(Default)]
+pub struct InstanceRuntime {
+    pub process: Arc<Mutex<Option<HldsProcess>>>,
+    // A server launched by a previous run of the manager, reachable only through RCON and its log files.
+    pub attached: Arc<Mutex<Option<AttachedProcess>>>,
+    pub crashes: Arc<Mutex<Vec<CrashRecord>>>,
+}
+
+impl InstanceRuntime {
+    pub fn attached_record(&self) -> Option<RunRecord> {
+        self.attached
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|a| a.record.clone())
+            .filter(RunRecord::is_alive)
+    }
+}
 
+#[derive(Default)]
 pub struct HldsState {
-    pub process: Arc<Mutex<Option<(Child, JoinHandle<()>, Arc<AtomicBool>)>>>,
+    pub instances: Mutex<HashMap<String, Arc<InstanceRuntime>>>,
+}
+
+impl HldsState {
+    pub fn runtime(&self, instance_id: &str) -> Arc<InstanceRuntime> {
+        self.instances
+            .lock()
+            .unwrap()
+            .entry(instance_id.to_string())
+            .or_default()
+            .clone()
+    }
+
+    pub fn is_running(&self, instance_id: &str) -> bool {
+        let runtime = self.runtime(instance_id);
+        let mut guard = runtime.process.lock().unwrap();
+        let managed = matches!(
+            guard.as_mut().map(|(process, _, _)| process.try_wait()),
+            Some(Ok(None))
+        );
+        drop(guard);
+
+        managed || runtime.attached_record().is_some()
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug, Default)]
+#[derive(Serialize, Debug, Clone)]
+pub struct HldsStatus {
+    pub running: bool,
+    pub attached: bool,
+    pub pid: Option<u32>,
+    pub started_at: Option<DateTime<Local>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
 pub struct ServerConfig {
     pub port: String,
     pub map: String,
+    pub rcon_password: String,
+    pub game: String,
+    pub max_players: u32,
+    pub ip: Option<String>,
+    pub pingboost: Option<u8>,
+    pub sys_ticrate: Option<u32>,
+    pub sv_lan: bool,
+    pub insecure: bool,
+    pub exec: Option<String>,
+    pub debug: bool,
+    pub extra_args: Vec<String>,
 }
 
-fn free_port(port: &str) {
-    #[cfg(unix)]
-    {
-        let output = Command::new("lsof")
-            .arg("-t")
-            .arg(format!("-i:{}", port))
-            .output();
-
-        if let Ok(output) = output {
-            if output.status.success() {
-                let pids = String::from_utf8_lossy(&output.stdout);
-                for pid in pids.lines() {
-                    let _ = Command::new("kill").arg("-9").arg(pid).output();
-                    println!("🔪 Porta {} liberada: matou processo {}", port, pid);
-                }
-            }
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            port: "27015".into(),
+            map: "de_dust2".into(),
+            rcon_password: String::new(),
+            game: "cstrike".into(),
+            max_players: 32,
+            ip: None,
+            pingboost: None,
+            sys_ticrate: None,
+            sv_lan: false,
+            insecure: false,
+            exec: None,
+            debug: false,
+            extra_args: vec![],
         }
     }
+}
+
+// Every flag the typed profile (or the manager itself) sets; repeating one in
+// extra_args would silently override it.
+const MANAGED_ARGS: &[&str] = &[
+    "-console",
+    "-norestart",
+    "+log",
+    "-game",
+    "+port",
+    "-port",
+    "+map",
+    "+changelevel",
+    "+maxplayers",
+    "-maxplayers",
+    "+ip",
+    "+sv_lan",
+    "-pingboost",
+    "-sys_ticrate",
+    "+sys_ticrate",
+    "-insecure",
+    "-secure",
+    "-debug",
+    "+exec",
+];
+
+impl ServerConfig {
+    pub fn address(&self) -> String {
+        let host = match self.ip.as_deref() {
+            Some(ip) if !ip.is_empty() && ip != "0.0.0.0" => ip,
+            _ => "127.0.0.1",
+        };
+        format!("{}:{}", host, self.port)
+    }
 
-    #[cfg(windows)]
-    {
-        let output = Command::new("netstat").args(["-ano"]).output();
-
-        if let Ok(output) = output {
-            let output_str = String::from_utf8_lossy(&output.stdout);
-            for line in output_str.lines() {
-                if line.contains(&format!(":{}", port)) {
-                    if let Some(pid) = line.split_whitespace().last() {
-                        let _ = Command::new("taskkill").args(["/F", "/PID", pid]).output();
-                        println!("🔪 Porta {} liberada: matou processo {}", port, pid);
-                    }
-                }
+    pub fn validate(&self) -> Result<(), String> {
+        match self.port.trim().parse::<u16>() {
+            Ok(port) if port > 0 => {}
+            _ => return Err(format!("Porta inválida: '{}'.", self.port)),
+        }
+
+        if self.map.is_empty() || !is_safe_name(&self.map) {
+            return Err(format!("Nome de mapa inválido: '{}'.", self.map));
+        }
+
+        if self.game.is_empty() || !is_safe_name(&self.game) {
+            return Err(format!("Mod inválido: '{}'.", self.game));
+        }
+
+        if !(1..=32).contains(&self.max_players) {
+            return Err("maxplayers deve estar entre 1 e 32.".into());
+        }
+
+        if let Some(ip) = self.ip.as_deref().filter(|ip| !ip.is_empty()) {
+            ip.parse::<std::net::IpAddr>()
+                .map_err(|_| format!("IP inválido: '{}'.", ip))?;
+        }
+
+        if let Some(pingboost) = self.pingboost {
+            if !(1..=3).contains(&pingboost) {
+                return Err("pingboost deve ser 1, 2 ou 3.".into());
+            }
+        }
+
+        if let Some(ticrate) = self.sys_ticrate {
+            if !(100..=10000).contains(&ticrate) {
+                return Err("sys_ticrate deve estar entre 100 e 10000.".into());
             }
         }
+
+        if let Some(exec) = self.exec.as_deref().filter(|e| !e.is_empty()) {
+            let valid = exec.ends_with(".cfg")
+                && !exec.contains("..")
+                && !exec.starts_with('/')
+                && exec
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '/'));
+            if !valid {
+                return Err(format!("Arquivo de exec inválido: '{}'.", exec));
+            }
+        }
+
+        if self.rcon_password.contains('"') {
+            return Err("A senha RCON não pode conter aspas.".into());
+        }
+
+        for arg in &self.extra_args {
+            if arg.is_empty() || arg.chars().any(|c| c.is_control()) {
+                return Err(format!("Argumento extra inválido: '{}'.", arg));
+            }
+            if MANAGED_ARGS.contains(&arg.to_lowercase().as_str()) {
+                return Err(format!(
+                    "O argumento '{}' já é controlado pelo perfil de inicialização.",
+                    arg
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn launch_args(&self) -> Vec<String> {
+        let mut args: Vec<String> = vec!["-game".into(), self.game.clone()];
+
+        if self.insecure {
+            args.push("-insecure".into());
+        }
+        if self.debug {
+            args.push("-debug".into());
+        }
+        if let Some(pingboost) = self.pingboost {
+            args.extend(["-pingboost".into(), pingboost.to_string()]);
+        }
+        if let Some(ticrate) = self.sys_ticrate {
+            args.extend(["-sys_ticrate".into(), ticrate.to_string()]);
+        }
+        if let Some(ip) = self.ip.as_deref().filter(|ip| !ip.is_empty()) {
+            args.extend(["+ip".into(), ip.to_string()]);
+        }
+
+        args.extend(["+port".into(), self.port.trim().to_string()]);
+        args.extend(["+maxplayers".into(), self.max_players.to_string()]);
+        args.extend(["+sv_lan".into(), if self.sv_lan { "1" } else { "0" }.into()]);
+        args.extend(["+map".into(), self.map.clone()]);
+
+        if let Some(exec) = self.exec.as_deref().filter(|e| !e.is_empty()) {
+            args.extend(["+exec".into(), exec.to_string()]);
+        }
+
+        args.extend(self.extra_args.iter().cloned());
+        args
     }
 }
 
+fn is_safe_name(name: &str) -> bool {
+    name.len() <= 64
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '[' | ']'))
+        && !name.contains("..")
+}
+
 #[tauri::command]
-pub fn start_hlds_server(app: AppHandle, state: State<HldsState>) -> Result<(), String> {
-    let path = get_hlds_path()?.ok_or("HLDS path não configurado.")?;
-    let config = get_server_config()?.unwrap_or_default();
-    let port = config.port.clone();
-
-    free_port(&port);
-    stop_hlds_server(state.clone()).ok();
-
-    let mut process = Command::new(format!("{}/hlds_run", path))
-        .current_dir(&path)
-        .arg("-console")
-        .arg("-game")
-        .arg("cstrike")
-        .arg("+port")
-        .arg(&config.port)
-        .arg("+map")
-        .arg(&config.map)
-        .arg("+maxplayers")
-        .arg("32")
-        .stdout(Stdio::piped())
-        .stdin(Stdio::piped())
+pub fn start_hlds_server(
+    app: AppHandle,
+    state: State<HldsState>,
+    instance_id: Option<String>,
+    confirmed_pids: Option<Vec<u32>>,
+) -> Result<(), String> {
+    start_instance(
+        &state,
+        app_sink(app),
+        &resolve_instance_id(instance_id),
+        &confirmed_pids.unwrap_or_default(),
+    )
+}
+
+pub fn start_instance(
+    state: &HldsState,
+    sink: Sink,
+    instance_id: &str,
+    confirmed_pids: &[u32],
+) -> Result<(), String> {
+    let instance_id = instance_id.to_string();
+    let instance = get_instance(Some(&instance_id))?;
+    let runtime = state.runtime(&instance_id);
+
+    // A sibling instance on the same port must be stopped by the user, not killed here.
+    for other in read_instances()? {
+        if other.id != instance_id
+            && other.server_config.port == instance.server_config.port
+            && state.is_running(&other.id)
+        {
+            return Err(format!(
+                "A porta {} já está em uso pela instância '{}'.",
+                other.server_config.port, other.name
+            ));
+        }
+    }
+
+    if runtime.attached_record().is_some() {
+        return Err(
+            "O servidor já está em execução (reanexado). Pare-o antes de iniciar novamente.".into(),
+        );
+    }
+
+    stop_process(&instance_id, &runtime, None);
+    release_port(&instance.server_config.port, confirmed_pids)?;
+
+    let running_flag = Arc::new(AtomicBool::new(true));
+    let (process, handle) = spawn_hlds(&sink, &instance, runtime.clone())?;
+
+    *runtime.process.lock().unwrap() = Some((process, handle, running_flag.clone()));
+
+    supervise(sink, instance_id.clone(), runtime, running_flag);
+
+    tauri::async_runtime::spawn(async move {
+        emit_instance_event(instance_id, WebhookEvent::ServerStarted).await;
+    });
+
+    Ok(())
+}
+
+// Exactly what `hlds_run` receives, and what the run record keeps to tell on
+// reattach whether the profile changed since the server was started.
+pub fn run_args(config: &ServerConfig) -> Vec<String> {
+    // hlds_run restarts crashed servers on its own; the supervisor handles that instead.
+    // Events, stats, players and chat are all read from the server log.
+    let mut args = vec![
+        "-console".to_string(),
+        "-norestart".to_string(),
+        "+log".to_string(),
+        "on".to_string(),
+    ];
+    args.extend(config.launch_args());
+    args
+}
+
+fn hlds_command(instance: &ServerInstance) -> Result<(Command, Vec<String>), String> {
+    let path = instance.hlds_path()?;
+    let config = &instance.server_config;
+    config.validate()?;
+
+    if !instance.game_path()?.is_dir() {
+        return Err(format!(
+            "Mod '{}' não encontrado em {}.",
+            config.game,
+            path.display()
+        ));
+    }
+
+    let args = run_args(config);
+    let mut command = Command::new(path.join("hlds_run"));
+    command.current_dir(&path).args(&args);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+
+    Ok((command, args))
+}
+
+pub fn spawn_hlds(
+    sink: &Sink,
+    instance: &ServerInstance,
+    runtime: Arc<InstanceRuntime>,
+) -> Result<(Child, JoinHandle<()>), String> {
+    let (mut command, args) = hlds_command(instance)?;
+    command.stdout(Stdio::piped()).stdin(Stdio::piped());
+
+    let mut process = command
         .spawn()
         .map_err(|e| format!("Erro ao iniciar HLDS: {}", e))?;
+    write_run_record(&instance.id, process.id(), &args);
+    close_open_sessions(&instance.id);
 
     let stdout = process.stdout.take().ok_or("stdout não disponível")?;
-    let app_clone = app.clone();
-    let running_flag = Arc::new(AtomicBool::new(true));
-    let running_flag_thread = running_flag.clone();
+    let sink_clone = sink.clone();
+    let instance_id = instance.id.clone();
 
+    // Reads until the process exits, including during a stop countdown, so the
+    // pipe never fills up and blocks HLDS before it sees `quit`.
     let handle = thread::spawn(move || {
         let reader = BufReader::new(stdout);
-        for line in reader.lines().flatten() {
-            if !running_flag_thread.load(Ordering::Relaxed) {
-                break;
-            }
+        let mut recorder = StatsRecorder::new(&instance_id);
+        for line in reader.lines().map_while(Result::ok) {
+            handle_output_line(&sink_clone, &runtime, &instance_id, &mut recorder, &line);
+        }
+    });
 
-            if line.to_lowercase().contains("segmentation fault") {
-                tauri::async_runtime::spawn(async {
-                    emit_event(WebhookEvent::ErrorOccurred).await;
-                });
-            }
+    Ok((process, handle))
+}
 
-            if line.to_lowercase().contains("couldn't open") {
-                tauri::async_runtime::spawn(async {
-                    emit_event(WebhookEvent::ErrorOccurred).await;
-                });
-            }
+pub fn handle_output_line(
+    sink: &Sink,
+    runtime: &Arc<InstanceRuntime>,
+    instance_id: &str,
+    recorder: &mut StatsRecorder,
+    line: &str,
+) {
+    if line.to_lowercase().contains("segmentation fault") {
+        let id = instance_id.to_string();
+        tauri::async_runtime::spawn(async move {
+            emit_instance_event(id, WebhookEvent::ErrorOccurred).await;
+        });
+    }
 
-            let timestamp = Local::now().format("%H:%M:%S");
-            let formatted = format!("[{}] {}", timestamp, line);
-            let _ = app_clone.emit("hlds-log", &formatted);
+    if let Some(path) = missing_asset(line) {
+        if has_subscribers(instance_id, "missing_asset") {
+            let id = instance_id.to_string();
+            let map = recorder.map().map(str::to_string);
+            tauri::async_runtime::spawn(async move {
+                let event = missing_asset_event(&id, path, map).await;
+                emit_instance_event(id, event).await;
+            });
         }
-    });
+    } else if line.to_lowercase().contains("couldn't open") {
+        let id = instance_id.to_string();
+        tauri::async_runtime::spawn(async move {
+            emit_instance_event(id, WebhookEvent::ErrorOccurred).await;
+        });
+    }
+
+    if let Some(entry) = parse_log_line(line) {
+        sink.emit(
+            "hlds-event",
+            serde_json::json!({ "instance": instance_id, "event": entry }),
+        );
+        recorder.record_entry(&entry);
+        record_player_event(instance_id, &entry);
+        record_chat(runtime, instance_id, &entry);
+
+        if has_subscribers(instance_id, entry.event.name()) {
+            let id = instance_id.to_string();
+            tauri::async_runtime::spawn(async move {
+                emit_instance_event(id, WebhookEvent::Game(entry.event)).await;
+            });
+        }
+    }
+
+    // server.cfg runs on every map load, after our `+log on`; a `log off` in it
+    // would leave us without events until the server is restarted.
+    if line.contains("Server logging disabled") {
+        if let Some((child, _, _)) = &mut *runtime.process.lock().unwrap() {
+            child.send_console("log on");
+        }
+    }
+
+    observe_line(instance_id, line);
 
-    *state.process.lock().unwrap() = Some((process, handle, running_flag));
+    let timestamp = Local::now().format("%H:%M:%S");
+    let formatted = format!("[{}] {}", timestamp, line);
+    sink.emit(
+        "hlds-log",
+        serde_json::json!({ "instance": instance_id, "line": formatted }),
+    );
+}
+
+pub static CONSOLE_LOG_DIR: Lazy<PathBuf> = Lazy::new(|| {
+    let mut path = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
+    path.push("canalhas-manager/console");
+    path
+});
+
+// For callers that exit right after starting the server (the CLI): nobody reads
+// stdout, so it goes to a file, and the run record lets the app or the daemon
+// reattach later.
+pub fn start_detached(instance_id: &str, confirmed_pids: &[u32]) -> Result<u32, String> {
+    let instance = get_instance(Some(instance_id))?;
+
+    if read_run_record(instance_id).is_some_and(|r| r.is_alive()) {
+        return Err("O servidor já está em execução.".into());
+    }
+
+    for other in read_instances()? {
+        if other.id != instance.id
+            && other.server_config.port == instance.server_config.port
+            && read_run_record(&other.id).is_some_and(|r| r.is_alive())
+        {
+            return Err(format!(
+                "A porta {} já está em uso pela instância '{}'.",
+                other.server_config.port, other.name
+            ));
+        }
+    }
+
+    release_port(&instance.server_config.port, confirmed_pids)?;
 
-    tauri::async_runtime::spawn(async {
-        emit_event(WebhookEvent::ServerStarted).await;
+    fs::create_dir_all(&*CONSOLE_LOG_DIR).map_err(|e| e.to_string())?;
+    let log = File::create(CONSOLE_LOG_DIR.join(format!("{}.log", instance.id)))
+        .map_err(|e| e.to_string())?;
+    let log_err = log.try_clone().map_err(|e| e.to_string())?;
+
+    let (mut command, args) = hlds_command(&instance)?;
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::from(log))
+        .stderr(Stdio::from(log_err));
+
+    let process = command
+        .spawn()
+        .map_err(|e| format!("Erro ao iniciar HLDS: {}", e))?;
+    write_run_record(&instance.id, process.id(), &args);
+
+    let id = instance.id.clone();
+    tauri::async_runtime::spawn(async move {
+        emit_instance_event(id, WebhookEvent::ServerStarted).await;
     });
 
-    Ok(())
+    Ok(process.id())
 }
 
 #[tauri::command]
-pub fn stop_hlds_server(state: State<HldsState>) -> Result<(), String> {
-    if let Some((mut process, handle, flag)) = state.process.lock().unwrap().take() {
-        flag.store(false, Ordering::Relaxed);
-        let _ = process
-            .kill()
-            .map_err(|e| format!("Erro ao matar processo: {}", e))?;
-        let _ = process
-            .wait()
-            .map_err(|e| format!("Erro ao aguardar encerramento: {}", e))?;
+pub async fn stop_hlds_server(
+    app: AppHandle,
+    state: State<'_, HldsState>,
+    instance_id: Option<String>,
+    options: Option<StopOptions>,
+) -> Result<StopMethod, String> {
+    let instance_id = resolve_instance_id(instance_id);
+    let runtime = state.runtime(&instance_id);
+    let sink = app_sink(app);
+
+    tauri::async_runtime::spawn_blocking(move || {
+        stop_instance(&sink, &instance_id, &runtime, options)
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+// Blocks for the whole countdown; call it from a blocking thread.
+pub fn stop_instance(
+    sink: &Sink,
+    instance_id: &str,
+    runtime: &InstanceRuntime,
+    options: Option<StopOptions>,
+) -> StopMethod {
+    let method = stop_process(instance_id, runtime, options);
+    sink.emit(
+        "hlds-stopped",
+        serde_json::json!({ "instance": instance_id, "method": method }),
+    );
+    method
+}
+
+pub fn stop_process(
+    instance_id: &str,
+    runtime: &InstanceRuntime,
+    options: Option<StopOptions>,
+) -> StopMethod {
+    // The child stays in the runtime during the countdown so status and console
+    // commands still reach it; clearing the flag is what detaches the supervisor.
+    let flag = match runtime.process.lock().unwrap().as_ref() {
+        Some((_, _, flag)) => flag.clone(),
+        None => return stop_attached(instance_id, runtime, options),
+    };
+    flag.store(false, Ordering::Relaxed);
+
+    let mut target = ChildTarget {
+        runtime,
+        flag: &flag,
+    };
+    let method = match options {
+        Some(options) => graceful_shutdown(&mut target, &options),
+        None => {
+            target.force_kill();
+            StopMethod::Killed
+        }
+    };
 
+    let entry = {
+        let mut guard = runtime.process.lock().unwrap();
+        match guard.as_ref() {
+            Some((_, _, f)) if Arc::ptr_eq(f, &flag) => guard.take(),
+            _ => None,
+        }
+    };
+    remove_pid_file(instance_id);
+
+    if let Some((_, handle, _)) = entry {
         thread::spawn(move || {
             let _ = handle.join();
         });
+    }
 
-        tauri::async_runtime::spawn(async {
-            emit_event(WebhookEvent::ServerStopped).await;
-        });
+    let id = instance_id.to_string();
+    tauri::async_runtime::spawn(async move {
+        emit_instance_event(id, WebhookEvent::ServerStopped(method)).await;
+    });
+
+    method
+}
+
+// Our child as seen through the runtime, locking only for each step so the
+// countdown doesn't block other users of the process entry.
+struct ChildTarget<'a> {
+    runtime: &'a InstanceRuntime,
+    flag: &'a Arc<AtomicBool>,
+}
+
+impl ChildTarget<'_> {
+    fn with_child<T>(&self, f: impl FnOnce(&mut Child) -> T) -> Option<T> {
+        let mut guard = self.runtime.process.lock().unwrap();
+        match guard.as_mut() {
+            Some((child, _, flag)) if Arc::ptr_eq(flag, self.flag) => Some(f(child)),
+            _ => None,
+        }
     }
+}
 
-    Ok(())
+impl ShutdownTarget for ChildTarget<'_> {
+    fn send_console(&mut self, command: &str) {
+        self.with_child(|child| child.send_console(command));
+    }
+
+    fn has_exited(&mut self) -> bool {
+        self.with_child(|child| child.has_exited()).unwrap_or(true)
+    }
+
+    fn terminate(&mut self) {
+        self.with_child(|child| child.terminate());
+    }
+
+    fn force_kill(&mut self) {
+        self.with_child(force_kill);
+    }
 }
 
 #[tauri::command]
-pub fn send_command_to_hlds(command: String, state: State<HldsState>) -> Result<(), String> {
-    if let Some((proc, _, _)) = &mut *state.process.lock().unwrap() {
+pub fn send_command_to_hlds(
+    app: AppHandle,
+    command: String,
+    state: State<HldsState>,
+    instance_id: Option<String>,
+) -> Result<(), String> {
+    send_command(
+        &state,
+        app_sink(app),
+        &resolve_instance_id(instance_id),
+        command,
+    )
+}
+
+pub fn send_command(
+    state: &HldsState,
+    sink: Sink,
+    instance_id: &str,
+    command: String,
+) -> Result<(), String> {
+    let instance_id = instance_id.to_string();
+    let runtime = state.runtime(&instance_id);
+    if let Some((proc, _, _)) = &mut *runtime.process.lock().unwrap() {
         if let Some(stdin) = &mut proc.stdin {
             stdin
                 .write_all(format!("{}\n", command).as_bytes())
                 .map_err(|e| format!("Erro ao enviar comando: {}", e))?;
         }
+        return Ok(());
+    }
+
+    if runtime.attached_record().is_some() {
+        send_attached_command(sink, instance_id, command);
     }
     Ok(())
 }
 
 #[tauri::command]
-pub fn save_hlds_path(path: String) -> Result<(), String> {
-    let config_path = get_config_path().map_err(|e| e.to_string())?;
-    let json = serde_json::json!({ "hlds_path": path });
-    fs::write(config_path, json.to_string()).map_err(|e| e.to_string())
+pub fn save_hlds_path(path: String, instance_id: Option<String>) -> Result<(), String> {
+    update_instance(&resolve_instance_id(instance_id), |instance| {
+        instance.hlds_path = Some(path)
+    })
 }
 
 #[tauri::command]
-pub fn get_hlds_path() -> Result<Option<String>, String> {
-    let config = read_or_create_config()?;
-    Ok(config
-        .get("hlds_path")
-        .and_then(|v| v.as_str())
-        .map(String::from))
+pub fn get_hlds_path(instance_id: Option<String>) -> Result<Option<String>, String> {
+    Ok(get_instance(instance_id.as_deref())?.hlds_path)
 }
 
 #[tauri::command]
-pub fn save_server_config(config: ServerConfig) -> Result<(), String> {
-    let config_path = get_config_path().map_err(|e| e.to_string())?;
-    let mut contents = String::new();
-    let _ = File::open(&config_path).and_then(|mut f| f.read_to_string(&mut contents));
-
-    let mut json = if contents.is_empty() {
-        serde_json::json!({})
-    } else {
-        serde_json::from_str::<serde_json::Value>(&contents)
-            .unwrap_or_else(|_| serde_json::json!({}))
-    };
-
-    json["server_config"] = serde_json::json!({
-        "port": config.port,
-        "map": config.map
-    });
+pub fn save_server_config(config: ServerConfig, instance_id: Option<String>) -> Result<(), String> {
+    config.validate()?;
+    update_instance(&resolve_instance_id(instance_id), |instance| {
+        instance.server_config = config
+    })
+}
 
-    fs::write(config_path, json.to_string()).map_err(|e| e.to_string())
+#[tauri::command]
+pub fn get_server_config(instance_id: Option<String>) -> Result<Option<ServerConfig>, String> {
+    Ok(Some(get_instance(instance_id.as_deref())?.server_config))
 }
 
 #[tauri::command]
-pub fn get_server_config() -> Result<Option<ServerConfig>, String> {
-    let config = read_or_create_config()?;
-    if let Some(cfg) = config.get("server_config") {
-        let parsed: ServerConfig =
-            serde_json::from_value(cfg.clone()).map_err(|e| e.to_string())?;
-        Ok(Some(parsed))
-    } else {
-        Ok(None)
-    }
+pub fn is_hlds_running(state: State<HldsState>, instance_id: Option<String>) -> bool {
+    state.is_running(&resolve_instance_id(instance_id))
 }
 
 #[tauri::command]
-pub fn is_hlds_running(state: State<HldsState>) -> bool {
-    if let Some((ref mut process, _, _)) = *state.process.lock().unwrap() {
-        if let Ok(Some(_)) = process.try_wait() {
-            false
-        } else {
-            true
+pub fn get_hlds_status(state: State<HldsState>, instance_id: Option<String>) -> HldsStatus {
+    state.status(&resolve_instance_id(instance_id))
+}
+
+impl HldsState {
+    pub fn status(&self, instance_id: &str) -> HldsStatus {
+        let running = self.is_running(instance_id);
+        let record = running.then(|| read_run_record(instance_id)).flatten();
+
+        HldsStatus {
+            running,
+            attached: self.runtime(instance_id).attached_record().is_some(),
+            pid: record.as_ref().map(|r| r.pid),
+            started_at: record.map(|r| r.started_at),
         }
-    } else {
-        false
     }
 }
 
-fn read_or_create_config() -> Result<serde_json::Value, String> {
+pub fn read_or_create_config() -> Result<serde_json::Value, String> {
     let config_path = get_config_path().map_err(|e| e.to_string())?;
     if config_path.exists() {
         let mut contents = String::new();
@@ -241,9 +741,182 @@ fn read_or_create_config() -> Result<serde_json::Value, String> {
     }
 }
 
-fn get_config_path() -> Result<PathBuf, std::io::Error> {
+pub fn get_config_path() -> Result<PathBuf, std::io::Error> {
     let mut path = dirs::config_dir().ok_or(std::io::ErrorKind::NotFound)?;
     path.push("canalhas-manager");
     fs::create_dir_all(&path)?;
     Ok(path.join("hlds_config.json"))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn full_profile() -> ServerConfig {
+        ServerConfig {
+            port: " 27016 ".into(),
+            map: "de_inferno".into(),
+            rcon_password: "segredo".into(),
+            game: "cstrike".into(),
+            max_players: 20,
+            ip: Some("192.168.0.10".into()),
+            pingboost: Some(2),
+            sys_ticrate: Some(1000),
+            sv_lan: true,
+            insecure: true,
+            exec: Some("configs/match.cfg".into()),
+            debug: true,
+            extra_args: vec!["+sv_region".into(), "2".into()],
+        }
+    }
+
+    #[test]
+    fn full_profile_launch_args() {
+        let config = full_profile();
+        config.validate().unwrap();
+        assert_eq!(
+            run_args(&config),
+            [
+                "-console",
+                "-norestart",
+                "+log",
+                "on",
+                "-game",
+                "cstrike",
+                "-insecure",
+                "-debug",
+                "-pingboost",
+                "2",
+                "-sys_ticrate",
+                "1000",
+                "+ip",
+                "192.168.0.10",
+                "+port",
+                "27016",
+                "+maxplayers",
+                "20",
+                "+sv_lan",
+                "1",
+                "+map",
+                "de_inferno",
+                "+exec",
+                "configs/match.cfg",
+                "+sv_region",
+                "2",
+            ]
+        );
+    }
+
+    #[test]
+    fn default_profile_leaves_optional_flags_out() {
+        let config = ServerConfig::default();
+        config.validate().unwrap();
+        assert_eq!(
+            config.launch_args(),
+            [
+                "-game",
+                "cstrike",
+                "+port",
+                "27015",
+                "+maxplayers",
+                "32",
+                "+sv_lan",
+                "0",
+                "+map",
+                "de_dust2"
+            ]
+        );
+    }
+
+    #[test]
+    fn extra_args_cannot_repeat_managed_flags() {
+        for arg in MANAGED_ARGS {
+            let config = ServerConfig {
+                extra_args: vec![arg.to_uppercase(), "1".into()],
+                ..ServerConfig::default()
+            };
+            let err = config.validate().unwrap_err();
+            assert!(err.contains("controlado pelo perfil"), "{}", arg);
+        }
+
+        for extra in ["", "+sv_region\n2"] {
+            let config = ServerConfig {
+                extra_args: vec![extra.into()],
+                ..ServerConfig::default()
+            };
+            assert!(config.validate().is_err(), "{:?}", extra);
+        }
+    }
+
+    #[test]
+    fn profile_ranges_are_checked() {
+        let invalid = [
+            ServerConfig {
+                port: "0".into(),
+                ..ServerConfig::default()
+            },
+            ServerConfig {
+                port: "65536".into(),
+                ..ServerConfig::default()
+            },
+            ServerConfig {
+                port: "270l5".into(),
+                ..ServerConfig::default()
+            },
+            ServerConfig {
+                max_players: 0,
+                ..ServerConfig::default()
+            },
+            ServerConfig {
+                max_players: 33,
+                ..ServerConfig::default()
+            },
+            ServerConfig {
+                ip: Some("192.168.0.300".into()),
+                ..ServerConfig::default()
+            },
+            ServerConfig {
+                pingboost: Some(4),
+                ..ServerConfig::default()
+            },
+            ServerConfig {
+                sys_ticrate: Some(99),
+                ..ServerConfig::default()
+            },
+            ServerConfig {
+                map: "../de_dust2".into(),
+                ..ServerConfig::default()
+            },
+            ServerConfig {
+                exec: Some("/etc/passwd.cfg".into()),
+                ..ServerConfig::default()
+            },
+            ServerConfig {
+                rcon_password: "a\"b".into(),
+                ..ServerConfig::default()
+            },
+        ];
+        for config in invalid {
+            assert!(config.validate().is_err(), "{:?}", config);
+        }
+
+        let valid = [
+            ServerConfig {
+                port: "65535".into(),
+                max_players: 1,
+                ..ServerConfig::default()
+            },
+            ServerConfig {
+                ip: Some(String::new()),
+                ..ServerConfig::default()
+            },
+            ServerConfig {
+                ip: Some("::1".into()),
+                ..ServerConfig::default()
+            },
+        ];
+        for config in valid {
+            assert!(config.validate().is_ok(), "{:?}", config);
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use std::{fs, path::PathBuf, sync::Mutex};
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::commands::admin_backend::AdminBackendKind;
+use crate::commands::hlds::{get_config_path, read_or_create_config, HldsState, ServerConfig};
+
+pub const DEFAULT_INSTANCE: &str = "default";
+
+pub static CONFIG_MUTEX: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ServerInstance {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub hlds_path: Option<String>,
+    #[serde(default)]
+    pub server_config: ServerConfig,
+    #[serde(default)]
+    pub admin_backend: AdminBackendKind,
+}
+
+impl ServerInstance {
+    pub fn hlds_path(&self) -> Result<PathBuf, String> {
+        self.hlds_path
+            .as_ref()
+            .filter(|p| !p.is_empty())
+            .map(PathBuf::from)
+            .ok_or_else(|| format!("HLDS path não configurado para '{}'.", self.id))
+    }
+
+    pub fn game_path(&self) -> Result<PathBuf, String> {
+        Ok(self.hlds_path()?.join(&self.server_config.game))
+    }
+}
+
+pub fn resolve_instance_id(id: Option<String>) -> String {
+    id.filter(|id| !id.is_empty())
+        .unwrap_or_else(|| DEFAULT_INSTANCE.to_string())
+}
+
+// Configs written before instances existed only have top-level hlds_path/server_config.
+pub fn read_instances() -> Result<Vec<ServerInstance>, String> {
+    let config = read_or_create_config()?;
+
+    if let Some(list) = config.get("instances") {
+        return serde_json::from_value(list.clone()).map_err(|e| e.to_string());
+    }
+
+    let server_config = match config.get("server_config") {
+        Some(cfg) => serde_json::from_value(cfg.clone()).map_err(|e| e.to_string())?,
+        None => ServerConfig::default(),
+    };
+
+    Ok(vec![ServerInstance {
+        id: DEFAULT_INSTANCE.to_string(),
+        name: "Servidor principal".to_string(),
+        hlds_path: config
+            .get("hlds_path")
+            .and_then(|v| v.as_str())
+            .map(String::from),
+        server_config,
+        admin_backend: AdminBackendKind::default(),
+    }])
+}
+
+fn write_instances(instances: &[ServerInstance]) -> Result<(), String> {
+    let config_path = get_config_path().map_err(|e| e.to_string())?;
+    let mut json = read_or_create_config()?;
+    json["instances"] = serde_json::to_value(instances).map_err(|e| e.to_string())?;
+    fs::write(config_path, json.to_string()).map_err(|e| e.to_string())
+}
+
+pub fn get_instance(id: Option<&str>) -> Result<ServerInstance, String> {
+    let id = id.filter(|id| !id.is_empty()).unwrap_or(DEFAULT_INSTANCE);
+    read_instances()?
+        .into_iter()
+        .find(|i| i.id == id)
+        .ok_or_else(|| format!("Instância '{}' não encontrada.", id))
+}
+
+pub fn update_instance(id: &str, f: impl FnOnce(&mut ServerInstance)) -> Result<(), String> {
+    let _lock = CONFIG_MUTEX.lock().unwrap();
+    let mut instances = read_instances()?;
+
+    let instance = instances
+        .iter_mut()
+        .find(|i| i.id == id)
+        .ok_or_else(|| format!("Instância '{}' não encontrada.", id))?;
+    f(instance);
+
+    write_instances(&instances)
+}
+
+fn validate_instance_id(id: &str) -> Result<(), String> {
+    let valid = !id.is_empty()
+        && id.len() <= 32
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'));
+
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "ID de instância inválido: '{}'. Use letras, números, '-' ou '_'.",
+            id
+        ))
+    }
+}
+
+#[tauri::command]
+pub fn list_instances() -> Result<Vec<ServerInstance>, String> {
+    read_instances()
+}
+
+#[tauri::command]
+pub fn add_instance(instance: ServerInstance) -> Result<(), String> {
+    validate_instance_id(&instance.id)?;
+    instance.server_config.validate()?;
+
+    let _lock = CONFIG_MUTEX.lock().unwrap();
+    let mut instances = read_instances()?;
+
+    if instances.iter().any(|i| i.id == instance.id) {
+        return Err(format!("A instância '{}' já existe.", instance.id));
+    }
+
+    instances.push(instance);
+    write_instances(&instances)
+}
+
+#[tauri::command]
+pub fn rename_instance(instance_id: String, name: String) -> Result<(), String> {
+    update_instance(&instance_id, |instance| instance.name = name)
+}
+
+#[tauri::command]
+pub fn remove_instance(state: State<HldsState>, instance_id: String) -> Result<(), String> {
+    delete_instance(&state, &instance_id)
+}
+
+pub fn delete_instance(state: &HldsState, instance_id: &str) -> Result<(), String> {
+    if state.is_running(instance_id) {
+        return Err("Pare o servidor antes de remover a instância.".into());
+    }
+
+    let _lock = CONFIG_MUTEX.lock().unwrap();
+    let mut instances = read_instances()?;
+    let before = instances.len();
+    instances.retain(|i| i.id != instance_id);
+
+    if instances.len() == before {
+        return Err(format!("Instância '{}' não encontrada.", instance_id));
+    }
+
+    write_instances(&instances)?;
+    state.instances.lock().unwrap().remove(instance_id);
+    Ok(())
+}
@@ -0,0 +1,529 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LogPlayer {
+    pub name: String,
+    pub user_id: i32,
+    pub steam_id: String,
+    pub team: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GameEvent {
+    Kill {
+        killer: LogPlayer,
+        victim: LogPlayer,
+        weapon: String,
+        headshot: bool,
+    },
+    Connected {
+        player: LogPlayer,
+        address: String,
+    },
+    EnteredGame {
+        player: LogPlayer,
+    },
+    Disconnected {
+        player: LogPlayer,
+    },
+    ChangedName {
+        player: LogPlayer,
+        new_name: String,
+    },
+    JoinedTeam {
+        player: LogPlayer,
+        team: String,
+    },
+    Say {
+        player: LogPlayer,
+        message: String,
+        team_only: bool,
+        dead: bool,
+    },
+    MapStarted {
+        map: String,
+    },
+    RoundStart,
+    RoundEnd,
+    WorldTriggered {
+        action: String,
+    },
+    TeamTriggered {
+        team: String,
+        action: String,
+        ct_score: Option<u32>,
+        t_score: Option<u32>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LogEntry {
+    pub timestamp: Option<NaiveDateTime>,
+    #[serde(flatten)]
+    pub event: GameEvent,
+}
+
+impl GameEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Kill { .. } => "player_killed",
+            Self::Connected { .. } => "player_connected",
+            Self::EnteredGame { .. } => "player_entered",
+            Self::Disconnected { .. } => "player_disconnected",
+            Self::ChangedName { .. } => "player_changed_name",
+            Self::JoinedTeam { .. } => "player_joined_team",
+            Self::Say { .. } => "player_say",
+            Self::MapStarted { .. } => "map_started",
+            Self::RoundStart => "round_start",
+            Self::RoundEnd => "round_end",
+            Self::WorldTriggered { .. } => "world_triggered",
+            Self::TeamTriggered { .. } => "team_triggered",
+        }
+    }
+
+    pub fn title(&self) -> String {
+        match self {
+            Self::Kill { .. } => "Abate".into(),
+            Self::Connected { .. } => "Jogador Conectado".into(),
+            Self::EnteredGame { .. } => "Jogador Entrou no Jogo".into(),
+            Self::Disconnected { .. } => "Jogador Desconectado".into(),
+            Self::ChangedName { .. } => "Troca de Nome".into(),
+            Self::JoinedTeam { .. } => "Troca de Time".into(),
+            Self::Say { .. } => "Mensagem no Chat".into(),
+            Self::MapStarted { map } => format!("Mapa Iniciado: {}", map),
+            Self::RoundStart => "Início de Round".into(),
+            Self::RoundEnd => "Fim de Round".into(),
+            Self::WorldTriggered { action } => format!("Evento do Mundo: {}", action),
+            Self::TeamTriggered { team, .. } => format!("Evento do Time {}", team),
+        }
+    }
+
+    pub fn description(&self) -> String {
+        match self {
+            Self::Kill {
+                killer,
+                victim,
+                weapon,
+                headshot,
+            } => format!(
+                "**{}** matou **{}** com `{}`{}.",
+                killer.name,
+                victim.name,
+                weapon,
+                if *headshot { " (headshot)" } else { "" }
+            ),
+            Self::Connected { player, address } => {
+                format!("**{}** conectou de `{}`.", player.name, address)
+            }
+            Self::EnteredGame { player } => format!("**{}** entrou no jogo.", player.name),
+            Self::Disconnected { player } => format!("**{}** desconectou.", player.name),
+            Self::ChangedName { player, new_name } => {
+                format!("**{}** mudou o nome para **{}**.", player.name, new_name)
+            }
+            Self::JoinedTeam { player, team } => {
+                format!("**{}** entrou no time `{}`.", player.name, team)
+            }
+            Self::Say {
+                player,
+                message,
+                team_only,
+                ..
+            } => format!(
+                "**{}**{}: {}",
+                player.name,
+                if *team_only { " (time)" } else { "" },
+                message
+            ),
+            Self::MapStarted { map } => format!("O mapa `{}` foi iniciado.", map),
+            Self::RoundStart => "Um novo round começou.".into(),
+            Self::RoundEnd => "O round terminou.".into(),
+            Self::WorldTriggered { action } => format!("O mundo disparou `{}`.", action),
+            Self::TeamTriggered {
+                team,
+                action,
+                ct_score,
+                t_score,
+            } => match (ct_score, t_score) {
+                (Some(ct), Some(t)) => {
+                    format!(
+                        "O time `{}` disparou `{}` (CT {} x {} TR).",
+                        team, action, ct, t
+                    )
+                }
+                _ => format!("O time `{}` disparou `{}`.", team, action),
+            },
+        }
+    }
+}
+
+pub fn parse_log_line(line: &str) -> Option<LogEntry> {
+    let (timestamp, body) = split_timestamp(line.trim());
+    let event = parse_event(body.trim())?;
+    Some(LogEntry { timestamp, event })
+}
+
+fn split_timestamp(line: &str) -> (Option<NaiveDateTime>, &str) {
+    // "L 08/13/2024 - 21:45:12: <body>"
+    let Some(rest) = line.strip_prefix("L ") else {
+        return (None, line);
+    };
+
+    match (
+        rest.get(..21),
+        rest.get(21..).and_then(|b| b.strip_prefix(':')),
+    ) {
+        (Some(stamp), Some(body)) => (
+            NaiveDateTime::parse_from_str(stamp, "%m/%d/%Y - %H:%M:%S").ok(),
+            body,
+        ),
+        _ => (None, line),
+    }
+}
+
+fn parse_event(body: &str) -> Option<GameEvent> {
+    if let Some(rest) = body.strip_prefix("World triggered ") {
+        let (action, _) = quoted(rest)?;
+        return Some(match action {
+            "Round_Start" => GameEvent::RoundStart,
+            "Round_End" => GameEvent::RoundEnd,
+            _ => GameEvent::WorldTriggered {
+                action: action.to_string(),
+            },
+        });
+    }
+
+    if let Some(rest) = body.strip_prefix("Started map ") {
+        let (map, _) = quoted(rest)?;
+        return Some(GameEvent::MapStarted {
+            map: map.to_string(),
+        });
+    }
+
+    if let Some(rest) = body.strip_prefix("Team ") {
+        return parse_team_trigger(rest);
+    }
+
+    if body.starts_with('"') {
+        return parse_player_event(body);
+    }
+
+    None
+}
+
+fn parse_team_trigger(rest: &str) -> Option<GameEvent> {
+    // Team "CT" triggered "CTs_Win" (CT "3") (T "1")
+    let (team, rest) = quoted(rest)?;
+    let (action, rest) = quoted(rest.strip_prefix(" triggered ")?)?;
+
+    Some(GameEvent::TeamTriggered {
+        team: team.to_string(),
+        action: action.to_string(),
+        ct_score: score_for(rest, "(CT "),
+        t_score: score_for(rest, "(T "),
+    })
+}
+
+fn score_for(rest: &str, marker: &str) -> Option<u32> {
+    let start = rest.find(marker)? + marker.len();
+    let (score, _) = quoted(&rest[start..])?;
+    score.parse().ok()
+}
+
+fn parse_player_event(body: &str) -> Option<GameEvent> {
+    let (player, rest) = parse_player_prefix(body)?;
+
+    if let Some(rest) = rest.strip_prefix(" killed ") {
+        let (victim, rest) = parse_player_prefix(rest)?;
+        let (weapon, rest) = quoted(rest.strip_prefix(" with ")?)?;
+        return Some(GameEvent::Kill {
+            killer: player,
+            victim,
+            weapon: weapon.to_string(),
+            headshot: rest.contains("(headshot)"),
+        });
+    }
+
+    if let Some(rest) = rest.strip_prefix(" connected, address ") {
+        let (address, _) = quoted(rest)?;
+        return Some(GameEvent::Connected {
+            player,
+            address: address.to_string(),
+        });
+    }
+
+    if rest.starts_with(" entered the game") {
+        return Some(GameEvent::EnteredGame { player });
+    }
+
+    // Newer builds append (reason "...") to the disconnect line.
+    if rest.starts_with(" disconnected") {
+        return Some(GameEvent::Disconnected { player });
+    }
+
+    if let Some(rest) = rest.strip_prefix(" changed name to ") {
+        let new_name = rest.strip_prefix('"')?.strip_suffix('"')?;
+        return Some(GameEvent::ChangedName {
+            player,
+            new_name: new_name.to_string(),
+        });
+    }
+
+    if let Some(rest) = rest.strip_prefix(" joined team ") {
+        let (team, _) = quoted(rest)?;
+        return Some(GameEvent::JoinedTeam {
+            player,
+            team: team.to_string(),
+        });
+    }
+
+    let (rest, team_only) = match rest.strip_prefix(" say_team ") {
+        Some(rest) => (rest, true),
+        None => (rest.strip_prefix(" say ")?, false),
+    };
+    let (rest, dead) = match rest.strip_suffix(" (dead)") {
+        Some(rest) => (rest, true),
+        None => (rest, false),
+    };
+
+    // Messages may contain quotes, so take everything up to the last one.
+    let message = rest.strip_prefix('"')?.strip_suffix('"')?;
+    Some(GameEvent::Say {
+        player,
+        message: message.to_string(),
+        team_only,
+        dead,
+    })
+}
+
+fn parse_player_prefix(s: &str) -> Option<(LogPlayer, &str)> {
+    // "Name<uid><STEAM_ID><TEAM>" followed by the rest of the line. Names may contain
+    // '>"' themselves, so take the first closing quote that yields a valid player.
+    let inner = s.strip_prefix('"')?;
+    inner.match_indices(">\"").find_map(|(end, _)| {
+        let player = parse_player(&inner[..=end])?;
+        Some((player, &inner[end + 2..]))
+    })
+}
+
+fn parse_player(token: &str) -> Option<LogPlayer> {
+    let rest = token.strip_suffix('>')?;
+    let (rest, team) = rest.rsplit_once('<')?;
+    let (rest, steam_id) = rest.strip_suffix('>')?.rsplit_once('<')?;
+    let (name, user_id) = rest.strip_suffix('>')?.rsplit_once('<')?;
+
+    Some(LogPlayer {
+        name: name.to_string(),
+        user_id: user_id.parse().ok()?,
+        steam_id: steam_id.to_string(),
+        team: team.to_string(),
+    })
+}
+
+fn quoted(s: &str) -> Option<(&str, &str)> {
+    let inner = s.strip_prefix('"')?;
+    let end = inner.find('"')?;
+    Some((&inner[..end], &inner[end + 1..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(line: &str) -> GameEvent {
+        parse_log_line(line)
+            .unwrap_or_else(|| panic!("linha não reconhecida: {}", line))
+            .event
+    }
+
+    fn player(name: &str, user_id: i32, steam_id: &str, team: &str) -> LogPlayer {
+        LogPlayer {
+            name: name.into(),
+            user_id,
+            steam_id: steam_id.into(),
+            team: team.into(),
+        }
+    }
+
+    #[test]
+    fn parses_timestamp() {
+        let entry =
+            parse_log_line("L 08/13/2024 - 21:45:12: World triggered \"Round_Start\"").unwrap();
+        assert_eq!(
+            entry.timestamp,
+            NaiveDateTime::parse_from_str("2024-08-13 21:45:12", "%Y-%m-%d %H:%M:%S").ok()
+        );
+        assert_eq!(entry.event, GameEvent::RoundStart);
+    }
+
+    #[test]
+    fn parses_kills() {
+        assert_eq!(
+            event(
+                r#"L 08/13/2024 - 21:45:12: "Fulano<2><STEAM_0:1:12345><CT>" killed "Ciclano<3><STEAM_0:0:54321><TERRORIST>" with "ak47""#
+            ),
+            GameEvent::Kill {
+                killer: player("Fulano", 2, "STEAM_0:1:12345", "CT"),
+                victim: player("Ciclano", 3, "STEAM_0:0:54321", "TERRORIST"),
+                weapon: "ak47".into(),
+                headshot: false,
+            }
+        );
+        assert_eq!(
+            event(
+                r#""Fulano<2><STEAM_0:1:12345><CT>" killed "Bot Joao<5><BOT><TERRORIST>" with "deagle" (headshot)"#
+            ),
+            GameEvent::Kill {
+                killer: player("Fulano", 2, "STEAM_0:1:12345", "CT"),
+                victim: player("Bot Joao", 5, "BOT", "TERRORIST"),
+                weapon: "deagle".into(),
+                headshot: true,
+            }
+        );
+    }
+
+    #[test]
+    fn parses_connection_lifecycle() {
+        assert_eq!(
+            event(
+                r#"L 08/13/2024 - 21:40:01: "Fulano<2><STEAM_ID_PENDING><>" connected, address "192.168.0.10:27005""#
+            ),
+            GameEvent::Connected {
+                player: player("Fulano", 2, "STEAM_ID_PENDING", ""),
+                address: "192.168.0.10:27005".into(),
+            }
+        );
+        assert_eq!(
+            event(r#""HLTV Proxy<6><HLTV><>" connected, address "127.0.0.1:27020""#),
+            GameEvent::Connected {
+                player: player("HLTV Proxy", 6, "HLTV", ""),
+                address: "127.0.0.1:27020".into(),
+            }
+        );
+        assert_eq!(
+            event(r#""Fulano<2><STEAM_0:1:12345><>" entered the game"#),
+            GameEvent::EnteredGame {
+                player: player("Fulano", 2, "STEAM_0:1:12345", ""),
+            }
+        );
+        assert_eq!(
+            event(r#""Fulano<2><STEAM_0:1:12345><Unassigned>" joined team "CT""#),
+            GameEvent::JoinedTeam {
+                player: player("Fulano", 2, "STEAM_0:1:12345", "Unassigned"),
+                team: "CT".into(),
+            }
+        );
+        assert_eq!(
+            event(r#""Fulano<2><STEAM_0:1:12345><CT>" changed name to "Fulano [BR]""#),
+            GameEvent::ChangedName {
+                player: player("Fulano", 2, "STEAM_0:1:12345", "CT"),
+                new_name: "Fulano [BR]".into(),
+            }
+        );
+        assert_eq!(
+            event(r#""Fulano<2><STEAM_0:1:12345><CT>" disconnected (reason "Kicked by Console")"#),
+            GameEvent::Disconnected {
+                player: player("Fulano", 2, "STEAM_0:1:12345", "CT"),
+            }
+        );
+    }
+
+    #[test]
+    fn parses_chat() {
+        assert_eq!(
+            event(r#""Fulano<2><STEAM_0:1:12345><CT>" say "gg "wp" galera""#),
+            GameEvent::Say {
+                player: player("Fulano", 2, "STEAM_0:1:12345", "CT"),
+                message: r#"gg "wp" galera"#.into(),
+                team_only: false,
+                dead: false,
+            }
+        );
+        assert_eq!(
+            event(r#""Fulano<2><STEAM_0:1:12345><CT>" say_team "rush b" (dead)"#),
+            GameEvent::Say {
+                player: player("Fulano", 2, "STEAM_0:1:12345", "CT"),
+                message: "rush b".into(),
+                team_only: true,
+                dead: true,
+            }
+        );
+    }
+
+    #[test]
+    fn parses_world_and_team_events() {
+        assert_eq!(
+            event(r#"L 08/13/2024 - 21:30:00: Started map "de_dust2" (CRC "-1480873540")"#),
+            GameEvent::MapStarted {
+                map: "de_dust2".into()
+            }
+        );
+        assert_eq!(
+            event(r#"World triggered "Round_Start""#),
+            GameEvent::RoundStart
+        );
+        assert_eq!(event(r#"World triggered "Round_End""#), GameEvent::RoundEnd);
+        assert_eq!(
+            event(r#"World triggered "Game_Commencing""#),
+            GameEvent::WorldTriggered {
+                action: "Game_Commencing".into()
+            }
+        );
+        assert_eq!(
+            event(r#"Team "CT" triggered "CTs_Win" (CT "3") (T "1")"#),
+            GameEvent::TeamTriggered {
+                team: "CT".into(),
+                action: "CTs_Win".into(),
+                ct_score: Some(3),
+                t_score: Some(1),
+            }
+        );
+        assert_eq!(
+            event(r#"Team "TERRORIST" triggered "Target_Bombed""#),
+            GameEvent::TeamTriggered {
+                team: "TERRORIST".into(),
+                action: "Target_Bombed".into(),
+                ct_score: None,
+                t_score: None,
+            }
+        );
+    }
+
+    #[test]
+    fn parses_names_with_markup() {
+        assert_eq!(
+            event(r#""<<Pro>> x<3<7><STEAM_0:0:1><TERRORIST>" entered the game"#),
+            GameEvent::EnteredGame {
+                player: player("<<Pro>> x<3", 7, "STEAM_0:0:1", "TERRORIST"),
+            }
+        );
+        assert_eq!(
+            event(r#""o "mito"<8><STEAM_0:0:2><CT>" killed "a>"b<9><BOT><TERRORIST>" with "awp""#),
+            GameEvent::Kill {
+                killer: player(r#"o "mito""#, 8, "STEAM_0:0:2", "CT"),
+                victim: player(r#"a>"b"#, 9, "BOT", "TERRORIST"),
+                weapon: "awp".into(),
+                headshot: false,
+            }
+        );
+    }
+
+    #[test]
+    fn ignores_garbage() {
+        for line in [
+            "",
+            "hello world",
+            "Server cvars start",
+            r#"Server cvar "mp_timelimit" = "30""#,
+            r#"L 08/13/2024 - 21:30:00: Log file started (file "logs/L0813000.log")"#,
+            r#""Fulano<x><STEAM_0:1:1><CT>" entered the game"#,
+            r#""Fulano<2><STEAM_0:1:1>" entered the game"#,
+            r#""Fulano<2><STEAM_0:1:1><CT>" killed "Ciclano<3><BOT><T>""#,
+            r#""Fulano<2><STEAM_0:1:1><CT>" triggered "Planted_The_Bomb""#,
+            r#"World triggered Round_Start"#,
+        ] {
+            assert_eq!(parse_log_line(line), None, "{}", line);
+        }
+    }
+}
@@ -0,0 +1,622 @@
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+
+use crate::commands::bsp::{read_bsp, wad_names, Bsp};
+use crate::commands::event::WebhookEvent;
+use crate::commands::instances::{get_instance, ServerInstance};
+
+// Sky textures are six images named after the skyname.
+const SKY_SIDES: [&str; 6] = ["up", "dn", "lf", "rt", "ft", "bk"];
+
+// Shipped with every Counter-Strike 1.6 client, so never listed for download.
+const STOCK_WADS: &[&str] = &[
+    "cached.wad",
+    "cs_747.wad",
+    "cs_assault.wad",
+    "cs_bdog.wad",
+    "cs_cbble.wad",
+    "cs_dust.wad",
+    "cs_havana.wad",
+    "cs_office.wad",
+    "cstraining.wad",
+    "cstrike.wad",
+    "de_airstrip.wad",
+    "de_aztec.wad",
+    "de_piranesi.wad",
+    "de_storm.wad",
+    "de_vertigo.wad",
+    "decals.wad",
+    "fonts.wad",
+    "gfx.wad",
+    "halflife.wad",
+    "itsitaly.wad",
+    "liquids.wad",
+    "n0th1ng.wad",
+    "pldecal.wad",
+    "prodigy.wad",
+    "spraypaint.wad",
+    "tempdecal.wad",
+    "torntextures.wad",
+    "xeno.wad",
+];
+
+const STOCK_SKIES: &[&str] = &[
+    "2desert",
+    "backalley",
+    "blue",
+    "city",
+    "cliff",
+    "cx",
+    "de_storm",
+    "des",
+    "desert",
+    "doom1",
+    "dusk",
+    "green",
+    "hav",
+    "morning",
+    "night",
+    "office",
+    "snow",
+    "space",
+    "tornsky",
+    "trainyard",
+    "tsccity",
+];
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetKind {
+    Wad,
+    Sky,
+    Model,
+    Sprite,
+    Sound,
+    Other,
+}
+
+impl AssetKind {
+    fn from_path(path: &str) -> Self {
+        match Path::new(path)
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+            .as_deref()
+        {
+            Some("wad") => Self::Wad,
+            Some("mdl") => Self::Model,
+            Some("spr") => Self::Sprite,
+            Some("wav" | "mp3") => Self::Sound,
+            _ if path.starts_with("gfx/env/") => Self::Sky,
+            _ => Self::Other,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetSource {
+    Entity,
+    ResFile,
+    // Files the engine looks for by map name (overview, briefing, detail textures).
+    Convention,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct MapAsset {
+    // Relative to the mod folder, with forward slashes.
+    pub path: String,
+    pub kind: AssetKind,
+    pub source: AssetSource,
+    // Where the file was found, e.g. "cstrike/models/tree.mdl".
+    pub found: Option<String>,
+    pub stock: bool,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct MapDependencies {
+    pub map: String,
+    pub assets: Vec<MapAsset>,
+    pub missing: Vec<String>,
+}
+
+pub fn validate_map_name(map: &str) -> Result<(), String> {
+    if map.is_empty() || map.contains(['/', '\\']) || map.contains("..") {
+        return Err(format!("Nome de mapa inválido: '{}'.", map));
+    }
+    Ok(())
+}
+
+// Maps made on Windows use backslashes.
+fn normalize(path: &str) -> String {
+    path.trim()
+        .replace('\\', "/")
+        .trim_start_matches('/')
+        .to_string()
+}
+
+fn entity_assets(bsp: &Bsp) -> Vec<String> {
+    let mut paths = vec![];
+    let world = bsp.worldspawn();
+
+    paths.extend(world.get("wad").map(wad_names).unwrap_or_default());
+    if let Some(sky) = world
+        .get("skyname")
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        for side in SKY_SIDES {
+            paths.push(format!("gfx/env/{}{}.tga", sky, side));
+        }
+    }
+
+    for entity in &bsp.entities {
+        for (_, value) in &entity.properties {
+            let value = normalize(value);
+            let lower = value.to_lowercase();
+            if lower.ends_with(".mdl") || lower.ends_with(".spr") {
+                paths.push(value);
+            } else if lower.ends_with(".wav") || lower.ends_with(".mp3") {
+                // "!NAME" is a sentence and "*" only marks streaming.
+                if value.starts_with('!') {
+                    continue;
+                }
+                let sound = value.trim_start_matches('*');
+                let lower = sound.to_lowercase();
+                if lower.starts_with("sound/") || lower.starts_with("media/") {
+                    paths.push(sound.to_string());
+                } else {
+                    paths.push(format!("sound/{}", sound));
+                }
+            }
+        }
+    }
+
+    paths
+}
+
+fn convention_assets(map: &str) -> Vec<String> {
+    vec![
+        format!("maps/{}.txt", map),
+        format!("maps/{}_detail.txt", map),
+        format!("overviews/{}.bmp", map),
+        format!("overviews/{}.tga", map),
+        format!("overviews/{}.txt", map),
+    ]
+}
+
+pub fn res_path(instance: &ServerInstance, map: &str) -> Result<PathBuf, String> {
+    Ok(instance
+        .game_path()?
+        .join("maps")
+        .join(format!("{}.res", map)))
+}
+
+pub fn read_res(path: &Path) -> Vec<String> {
+    fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .map(|line| line.split("//").next().unwrap_or("").trim())
+        .filter(|line| !line.is_empty())
+        // Entries may be quoted, like every other GoldSrc list.
+        .map(|line| normalize(line.trim_matches('"')))
+        .collect()
+}
+
+// Mod folders the engine searches, most specific first.
+pub fn search_roots(instance: &ServerInstance) -> Result<Vec<(String, PathBuf)>, String> {
+    let game = instance.server_config.game.clone();
+    let mut roots = vec![(game.clone(), instance.game_path()?)];
+    if game != "valve" {
+        roots.push(("valve".into(), instance.hlds_path()?.join("valve")));
+    }
+    Ok(roots)
+}
+
+// Map authors rarely match the case of files on disk, and Linux servers care.
+pub fn find_file(root: &Path, relative: &str) -> Option<PathBuf> {
+    let exact = root.join(relative);
+    if exact.is_file() {
+        return Some(exact);
+    }
+
+    let mut current = root.to_path_buf();
+    for part in relative.split('/').filter(|p| !p.is_empty()) {
+        let wanted = part.to_lowercase();
+        current = fs::read_dir(&current)
+            .ok()?
+            .filter_map(Result::ok)
+            .find(|e| e.file_name().to_string_lossy().to_lowercase() == wanted)?
+            .path();
+    }
+    current.is_file().then_some(current)
+}
+
+fn locate(roots: &[(String, PathBuf)], relative: &str) -> Option<(String, PathBuf)> {
+    // The software renderer loads skies as .bmp when there is no .tga.
+    let alternatives = match relative.strip_suffix(".tga") {
+        Some(stem) if relative.starts_with("gfx/env/") => {
+            vec![relative.to_string(), format!("{}.bmp", stem)]
+        }
+        _ => vec![relative.to_string()],
+    };
+
+    roots.iter().find_map(|(name, root)| {
+        alternatives.iter().find_map(|candidate| {
+            let found = find_file(root, candidate)?;
+            let shown = found.strip_prefix(root).unwrap_or(&found);
+            Some((
+                format!("{}/{}", name, shown.to_string_lossy().replace('\\', "/")),
+                found,
+            ))
+        })
+    })
+}
+
+fn stock_sky(path: &str) -> bool {
+    let Some(file) = path.strip_prefix("gfx/env/") else {
+        return false;
+    };
+    let stem = file.rsplit_once('.').map_or(file, |(stem, _)| stem);
+    SKY_SIDES
+        .iter()
+        .find_map(|side| stem.strip_suffix(side))
+        .is_some_and(|sky| STOCK_SKIES.contains(&sky))
+}
+
+pub fn is_stock(path: &str, found: Option<&str>) -> bool {
+    let lower = path.to_lowercase();
+    found.is_some_and(|f| f.starts_with("valve/"))
+        || STOCK_WADS.contains(&lower.as_str())
+        || stock_sky(&lower)
+}
+
+// Every file the map's entities and .res point at, without touching the disk
+// beyond the BSP and .res themselves.
+pub fn referenced_assets(
+    instance: &ServerInstance,
+    map: &str,
+) -> Result<BTreeMap<String, AssetSource>, String> {
+    validate_map_name(map)?;
+    let bsp_path = instance
+        .game_path()?
+        .join("maps")
+        .join(format!("{}.bsp", map));
+    let bsp = read_bsp(&bsp_path).map_err(|e| format!("{}: {}", map, e))?;
+
+    let mut wanted = BTreeMap::new();
+    for path in entity_assets(&bsp) {
+        wanted.entry(path).or_insert(AssetSource::Entity);
+    }
+    for path in read_res(&res_path(instance, map)?) {
+        wanted.entry(path).or_insert(AssetSource::ResFile);
+    }
+    Ok(wanted)
+}
+
+pub fn resolve_dependencies(
+    instance: &ServerInstance,
+    map: &str,
+) -> Result<MapDependencies, String> {
+    let wanted = referenced_assets(instance, map)?;
+    let roots = search_roots(instance)?;
+
+    let mut assets = vec![];
+    let mut missing = vec![];
+    for (path, source) in wanted {
+        let found = locate(&roots, &path).map(|(shown, _)| shown);
+        if found.is_none() {
+            missing.push(path.clone());
+        }
+        assets.push(MapAsset {
+            kind: AssetKind::from_path(&path),
+            stock: is_stock(&path, found.as_deref()),
+            path,
+            source,
+            found,
+        });
+    }
+
+    // Optional files only count when they exist.
+    for path in convention_assets(map) {
+        if assets.iter().any(|a| a.path.eq_ignore_ascii_case(&path)) {
+            continue;
+        }
+        if let Some((shown, _)) = locate(&roots, &path) {
+            assets.push(MapAsset {
+                kind: AssetKind::from_path(&path),
+                stock: is_stock(&path, Some(&shown)),
+                path,
+                source: AssetSource::Convention,
+                found: Some(shown),
+            });
+        }
+    }
+
+    assets.sort_by(|a, b| a.kind.cmp(&b.kind).then_with(|| a.path.cmp(&b.path)));
+    Ok(MapDependencies {
+        map: map.to_string(),
+        assets,
+        missing,
+    })
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ResUpdate {
+    pub path: String,
+    pub entries: Vec<String>,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+// Rewrites maps/<map>.res with every custom file the map needs that exists on
+// disk: stale entries are dropped and newly found dependencies added.
+pub fn write_res(instance: &ServerInstance, map: &str) -> Result<ResUpdate, String> {
+    let deps = resolve_dependencies(instance, map)?;
+    let path = res_path(instance, map)?;
+    let previous = read_res(&path);
+
+    let entries: Vec<String> = deps
+        .assets
+        .iter()
+        .filter(|a| a.found.is_some() && !a.stock)
+        .map(|a| a.path.clone())
+        .collect();
+    let added = entries
+        .iter()
+        .filter(|e| !previous.contains(e))
+        .cloned()
+        .collect();
+    let removed = previous
+        .iter()
+        .filter(|p| !entries.contains(p))
+        .cloned()
+        .collect();
+
+    let mut content = format!("// {}.res - gerado pelo Canalhas Manager\n", map);
+    for entry in &entries {
+        content.push_str(entry);
+        content.push('\n');
+    }
+
+    let tmp = path.with_extension("res.tmp");
+    let mut file = File::create(&tmp).map_err(|e| e.to_string())?;
+    file.write_all(content.as_bytes())
+        .map_err(|e| e.to_string())?;
+    file.sync_all().map_err(|e| e.to_string())?;
+    fs::rename(&tmp, &path).map_err(|e| e.to_string())?;
+
+    println!("📦 {}.res atualizado ({} arquivos)", map, entries.len());
+    Ok(ResUpdate {
+        path: path.to_string_lossy().into_owned(),
+        entries,
+        added,
+        removed,
+    })
+}
+
+const ASSET_EXTENSIONS: &[&str] = &[
+    ".wad", ".mdl", ".spr", ".wav", ".mp3", ".tga", ".bmp", ".bsp",
+];
+
+// The file named by a load error such as "Couldn't open de_foo.wad" or
+// "Host_Error: Mod_NumForName: models/props/tree.mdl not found".
+pub fn missing_asset(line: &str) -> Option<String> {
+    let lower = line.to_lowercase();
+    if !["couldn't open", "couldn't load", "not found"]
+        .iter()
+        .any(|error| lower.contains(error))
+    {
+        return None;
+    }
+
+    line.split_whitespace()
+        .map(|word| {
+            word.trim_matches(|c: char| matches!(c, '"' | '\'' | ',' | ':' | '(' | ')'))
+                .trim_end_matches('.')
+        })
+        .find(|word| {
+            let lower = word.to_lowercase();
+            ASSET_EXTENSIONS.iter().any(|ext| lower.ends_with(ext))
+        })
+        .map(normalize)
+}
+
+// Resolves the current map too, so a single alert lists everything to upload.
+pub async fn missing_asset_event(
+    instance_id: &str,
+    path: String,
+    map: Option<String>,
+) -> WebhookEvent {
+    let missing = match map.clone() {
+        Some(map) => {
+            let instance_id = instance_id.to_string();
+            tauri::async_runtime::spawn_blocking(move || {
+                resolve_dependencies(&get_instance(Some(&instance_id))?, &map)
+            })
+            .await
+            .ok()
+            .and_then(Result::ok)
+            .map(|deps| deps.missing)
+            .unwrap_or_default()
+        }
+        None => vec![],
+    };
+    WebhookEvent::MissingAsset { path, map, missing }
+}
+
+#[tauri::command]
+pub async fn check_map_dependencies(
+    map_name: String,
+    instance_id: Option<String>,
+) -> Result<MapDependencies, String> {
+    let instance = get_instance(instance_id.as_deref())?;
+    tauri::async_runtime::spawn_blocking(move || resolve_dependencies(&instance, &map_name))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub async fn generate_map_res(
+    map_name: String,
+    instance_id: Option<String>,
+) -> Result<ResUpdate, String> {
+    let instance = get_instance(instance_id.as_deref())?;
+    tauri::async_runtime::spawn_blocking(move || write_res(&instance, &map_name))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::bsp::{parse_entities, MapBounds};
+
+    fn bsp(entities: &str) -> Bsp {
+        Bsp {
+            entities: parse_entities(entities).unwrap(),
+            bounds: MapBounds {
+                mins: [0.0; 3],
+                maxs: [0.0; 3],
+            },
+        }
+    }
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("canalhas-deps-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn collects_entity_assets() {
+        let bsp = bsp(r#"
+            { "classname" "worldspawn" "wad" "\half-life\cstrike\custom.wad" "skyname" "space" }
+            { "classname" "cycler" "model" "models\props\tree.MDL" }
+            { "classname" "env_sprite" "model" "sprites/glow01.spr" }
+            { "classname" "ambient_generic" "message" "ambience/wind1.wav" }
+            { "classname" "ambient_generic" "message" "*ambience/rain.wav" }
+            { "classname" "ambient_generic" "message" "*sound/misc/siren.wav" }
+            { "classname" "ambient_generic" "message" "!HG_ALERT.wav" }
+            { "classname" "ambient_generic" "message" "!HG_ALERT" }
+            { "classname" "trigger_cdaudio" "message" "media/Half-Life01.mp3" }
+            { "classname" "func_wall" "model" "*12" }
+        "#);
+
+        assert_eq!(
+            entity_assets(&bsp),
+            [
+                "custom.wad",
+                "gfx/env/spaceup.tga",
+                "gfx/env/spacedn.tga",
+                "gfx/env/spacelf.tga",
+                "gfx/env/spacert.tga",
+                "gfx/env/spaceft.tga",
+                "gfx/env/spacebk.tga",
+                "models/props/tree.MDL",
+                "sprites/glow01.spr",
+                "sound/ambience/wind1.wav",
+                "sound/ambience/rain.wav",
+                "sound/misc/siren.wav",
+                "media/Half-Life01.mp3",
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_res_files() {
+        let dir = temp_dir();
+        let res = dir.join("de_test.res");
+        fs::write(
+            &res,
+            "// de_test.res\n\
+             \n\
+             \"models\\props\\tree.mdl\"\n\
+             sound/ambience/wind1.wav // vento\n\
+             /gfx/env/spaceup.tga\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            read_res(&res),
+            [
+                "models/props/tree.mdl",
+                "sound/ambience/wind1.wav",
+                "gfx/env/spaceup.tga",
+            ]
+        );
+        assert!(read_res(&dir.join("missing.res")).is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn recognizes_stock_skies() {
+        assert!(stock_sky("gfx/env/desup.tga"));
+        assert!(stock_sky("gfx/env/2desertbk.bmp"));
+        assert!(!stock_sky("gfx/env/customup.tga"));
+        assert!(!stock_sky("gfx/env/des.tga"));
+        assert!(!stock_sky("maps/desup.tga"));
+
+        assert!(is_stock("gfx/env/DESUP.TGA", None));
+        assert!(is_stock("Decals.wad", None));
+        assert!(is_stock(
+            "models/player.mdl",
+            Some("valve/models/player.mdl")
+        ));
+        assert!(!is_stock("custom.wad", Some("cstrike/custom.wad")));
+    }
+
+    #[test]
+    fn missing_asset_is_read_from_load_errors() {
+        for (line, expected) in [
+            ("Couldn't open de_foo.wad", "de_foo.wad"),
+            (
+                "Host_Error: Mod_NumForName: models/props/tree.mdl not found",
+                "models/props/tree.mdl",
+            ),
+            (
+                "Mod_NumForName: sprites\\fire.spr not found",
+                "sprites/fire.spr",
+            ),
+            (
+                "S_LoadSound: Couldn't load sound/ambience/wind.wav.",
+                "sound/ambience/wind.wav",
+            ),
+            ("couldn't open \"gfx/env/foort.tga\"", "gfx/env/foort.tga"),
+        ] {
+            assert_eq!(missing_asset(line).as_deref(), Some(expected), "{}", line);
+        }
+
+        for line in [
+            "Couldn't open file",
+            "[AMXX] Plugin file not found: foo.amxx",
+            "Precaching models/props/tree.mdl",
+            "L 08/13/2024 - 21:30:00: Started map \"de_foo\" (CRC \"-1480873540\")",
+        ] {
+            assert_eq!(missing_asset(line), None, "{}", line);
+        }
+    }
+
+    #[test]
+    fn finds_files_ignoring_case() {
+        let root = temp_dir();
+        fs::create_dir_all(root.join("Models/Props")).unwrap();
+        fs::write(root.join("Models/Props/Tree.MDL"), b"").unwrap();
+        fs::write(root.join("exact.wad"), b"").unwrap();
+
+        assert_eq!(find_file(&root, "exact.wad"), Some(root.join("exact.wad")));
+        assert_eq!(
+            find_file(&root, "models/props/tree.mdl"),
+            Some(root.join("Models/Props/Tree.MDL"))
+        );
+        assert_eq!(find_file(&root, "models/props"), None);
+        assert_eq!(find_file(&root, "models/props/rock.mdl"), None);
+        assert_eq!(find_file(&root, "sound/missing.wav"), None);
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,816 @@
+use std::{
+    collections::BTreeSet,
+    fs::{self, File},
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use chrono::Local;
+use flate2::read::GzDecoder;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+use crate::commands::bsp::read_bsp;
+use crate::commands::instances::{get_instance, resolve_instance_id, ServerInstance};
+use crate::commands::map_deps::validate_map_name;
+use crate::commands::maps::{notify_map_added, owning_map};
+
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+// Folders of the mod directory a map package may write to.
+const GAME_DIRS: &[&str] = &[
+    "maps",
+    "models",
+    "sound",
+    "gfx",
+    "overviews",
+    "sprites",
+    "resource",
+];
+
+pub static MAP_MANIFESTS_DIR: Lazy<PathBuf> = Lazy::new(|| {
+    let mut path = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
+    path.push("canalhas-manager/map_manifests");
+    path
+});
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum FileAction {
+    New,
+    Overwrite,
+    // Already on disk with the same content.
+    Unchanged,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct PackageFile {
+    // Relative to the mod folder, e.g. "models/tree.mdl".
+    pub path: String,
+    pub size_bytes: u64,
+    pub action: FileAction,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct MapPackage {
+    pub archive: String,
+    pub maps: Vec<String>,
+    pub files: Vec<PackageFile>,
+    // Archive entries outside the GoldSrc layout (readmes, screenshots...).
+    pub ignored: Vec<String>,
+}
+
+impl MapPackage {
+    pub fn overwrites(&self) -> Vec<&str> {
+        self.files
+            .iter()
+            .filter(|f| f.action == FileAction::Overwrite)
+            .map(|f| f.path.as_str())
+            .collect()
+    }
+}
+
+// Which files a package put on disk, so the map can later be removed cleanly.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MapManifest {
+    pub map: String,
+    pub archive: String,
+    pub installed_at: String,
+    // Files the package added; only these belong to the map.
+    pub files: Vec<String>,
+    // Files that were already on disk and got replaced. Never the map's own.
+    #[serde(default)]
+    pub overwritten: Vec<String>,
+}
+
+// In a package with several maps, maps/<map>.* and overviews/<map>.* go to
+// their own map only; everything else is shared by all of them.
+fn belongs_to(file: &str, map: &str, maps: &[String]) -> bool {
+    match owning_map(file) {
+        Some(owner) if maps.iter().any(|m| m.eq_ignore_ascii_case(owner)) => {
+            owner.eq_ignore_ascii_case(map)
+        }
+        _ => true,
+    }
+}
+
+fn manifest_for(package: &MapPackage, map: &str, installed_at: &str) -> MapManifest {
+    let paths = |action: FileAction| {
+        package
+            .files
+            .iter()
+            .filter(|f| f.action == action && belongs_to(&f.path, map, &package.maps))
+            .map(|f| f.path.clone())
+            .collect()
+    };
+    MapManifest {
+        map: map.to_string(),
+        archive: package.archive.clone(),
+        installed_at: installed_at.to_string(),
+        files: paths(FileAction::New),
+        overwritten: paths(FileAction::Overwrite),
+    }
+}
+
+// Reinstalling or updating a package finds the map's earlier files unchanged
+// or overwritten; they stay the map's own as long as they are on disk.
+fn merge_manifest(
+    previous: Option<MapManifest>,
+    mut manifest: MapManifest,
+    game: &Path,
+) -> MapManifest {
+    let Some(previous) = previous else {
+        return manifest;
+    };
+
+    let mut files: Vec<String> = previous
+        .files
+        .into_iter()
+        .filter(|f| game.join(f).is_file())
+        .collect();
+    for file in manifest.files {
+        if !files.contains(&file) {
+            files.push(file);
+        }
+    }
+
+    let mut overwritten = previous.overwritten;
+    for file in manifest.overwritten {
+        if !overwritten.contains(&file) {
+            overwritten.push(file);
+        }
+    }
+    overwritten.retain(|f| !files.contains(f));
+
+    manifest.files = files;
+    manifest.overwritten = overwritten;
+    manifest
+}
+
+pub fn manifest_path(instance_id: &str, map: &str) -> PathBuf {
+    MAP_MANIFESTS_DIR
+        .join(instance_id)
+        .join(format!("{}.json", map))
+}
+
+pub fn read_manifest(instance_id: &str, map: &str) -> Option<MapManifest> {
+    fs::read_to_string(manifest_path(instance_id, map))
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+}
+
+fn write_manifest(instance_id: &str, manifest: &MapManifest) -> Result<PathBuf, String> {
+    let path = manifest_path(instance_id, &manifest.map);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    let data = serde_json::to_string_pretty(manifest).map_err(|e| e.to_string())?;
+    let mut file = File::create(&tmp).map_err(|e| e.to_string())?;
+    file.write_all(data.as_bytes()).map_err(|e| e.to_string())?;
+    file.sync_all().map_err(|e| e.to_string())?;
+    fs::rename(&tmp, &path).map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+// Scratch folder next to the game files, so installing is a rename. Removed on drop.
+struct Staging {
+    dir: PathBuf,
+}
+
+impl Staging {
+    fn new(instance: &ServerInstance) -> Result<Self, String> {
+        let dir = instance.hlds_path()?.join(format!(
+            ".canalhas-install-{}",
+            uuid::Uuid::new_v4().simple()
+        ));
+        fs::create_dir_all(&dir).map_err(|e| format!("Erro ao criar pasta temporária: {}", e))?;
+        Ok(Self { dir })
+    }
+
+    fn files(&self) -> PathBuf {
+        self.dir.join("files")
+    }
+
+    fn backup(&self) -> PathBuf {
+        self.dir.join("backup")
+    }
+}
+
+impl Drop for Staging {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+// Drops absolute paths and ".." so no entry can land outside the staging folder.
+fn safe_relative(name: &str) -> Option<PathBuf> {
+    let mut path = PathBuf::new();
+    for part in name.replace('\\', "/").split('/') {
+        match part {
+            "" | "." => continue,
+            ".." => return None,
+            part if part.contains(':') => return None,
+            part => path.push(part),
+        }
+    }
+    (!path.as_os_str().is_empty()).then_some(path)
+}
+
+fn write_entry(dest: &Path, name: &str, reader: &mut dyn Read) -> io::Result<()> {
+    let Some(relative) = safe_relative(name) else {
+        return Ok(());
+    };
+    let path = dest.join(relative);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    io::copy(reader, &mut File::create(path)?)?;
+    Ok(())
+}
+
+fn extract_zip(archive: &Path, dest: &Path) -> Result<(), String> {
+    let file = File::open(archive).map_err(|e| e.to_string())?;
+    let mut zip = zip::ZipArchive::new(file).map_err(|e| format!("ZIP inválido: {}", e))?;
+    for i in 0..zip.len() {
+        let mut entry = zip
+            .by_index(i)
+            .map_err(|e| format!("ZIP inválido: {}", e))?;
+        if entry.is_file() {
+            let name = entry.name().to_string();
+            write_entry(dest, &name, &mut entry).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+fn extract_tar(reader: impl Read, dest: &Path) -> Result<(), String> {
+    let mut tar = tar::Archive::new(reader);
+    for entry in tar.entries().map_err(|e| format!("TAR inválido: {}", e))? {
+        let mut entry = entry.map_err(|e| format!("TAR inválido: {}", e))?;
+        // Links and devices are skipped; only regular files are installed.
+        if entry.header().entry_type().is_file() {
+            let name = entry
+                .path()
+                .map_err(|e| e.to_string())?
+                .to_string_lossy()
+                .into_owned();
+            write_entry(dest, &name, &mut entry).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+fn extract_7z(archive: &Path, dest: &Path) -> Result<(), String> {
+    sevenz_rust::decompress_file_with_extract_fn(archive, dest, |entry, reader, _| {
+        if !entry.is_directory() {
+            write_entry(dest, entry.name(), reader)?;
+        }
+        Ok(true)
+    })
+    .map_err(|e| format!("7z inválido: {}", e))
+}
+
+// There is no pure Rust RAR decoder, so this uses whichever tool the host has.
+fn extract_rar(archive: &Path, dest: &Path) -> Result<(), String> {
+    let target = format!("{}{}", dest.display(), std::path::MAIN_SEPARATOR);
+    let tools = [
+        ("unrar", vec!["x".to_string(), "-o+".into(), "-y".into()]),
+        (
+            "7z",
+            vec!["x".to_string(), "-y".into(), format!("-o{}", target)],
+        ),
+    ];
+
+    for (tool, mut args) in tools {
+        args.push(archive.to_string_lossy().into_owned());
+        if tool == "unrar" {
+            args.push(target.clone());
+        }
+        match Command::new(tool).args(&args).output() {
+            Ok(output) if output.status.success() => return Ok(()),
+            Ok(output) => {
+                return Err(format!(
+                    "{} falhou: {}",
+                    tool,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ))
+            }
+            Err(_) => continue,
+        }
+    }
+    Err("Para instalar .rar é preciso ter o unrar ou o 7z instalado.".into())
+}
+
+fn extract(archive: &Path, dest: &Path) -> Result<(), String> {
+    let name = archive
+        .file_name()
+        .map(|n| n.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    if name.ends_with(".zip") {
+        extract_zip(archive, dest)
+    } else if name.ends_with(".7z") {
+        extract_7z(archive, dest)
+    } else if name.ends_with(".rar") {
+        extract_rar(archive, dest)
+    } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+        let file = File::open(archive).map_err(|e| e.to_string())?;
+        extract_tar(GzDecoder::new(file), dest)
+    } else if name.ends_with(".tar") {
+        extract_tar(File::open(archive).map_err(|e| e.to_string())?, dest)
+    } else {
+        Err("Formato não suportado. Use .zip, .rar, .7z ou .tar.gz.".into())
+    }
+}
+
+// Regular files only: symlinks left by external extractors are never followed.
+fn walk(root: &Path, dir: &Path, files: &mut Vec<String>) -> Result<(), String> {
+    for entry in fs::read_dir(dir).map_err(|e| e.to_string())? {
+        let entry = entry.map_err(|e| e.to_string())?;
+        let kind = entry.file_type().map_err(|e| e.to_string())?;
+        if kind.is_dir() {
+            walk(root, &entry.path(), files)?;
+        } else if kind.is_file() {
+            let path = entry.path();
+            let relative = path.strip_prefix(root).unwrap_or(&path);
+            files.push(relative.to_string_lossy().replace('\\', "/"));
+        }
+    }
+    Ok(())
+}
+
+// "pack/cstrike/Maps/de_foo.bsp" -> "maps/de_foo.bsp". Loose BSPs go to maps/
+// and loose WADs to the mod folder.
+fn destination(entry: &str) -> Option<String> {
+    let parts: Vec<&str> = entry.split('/').collect();
+    let (file, dirs) = parts.split_last()?;
+
+    if let Some(i) = dirs
+        .iter()
+        .position(|d| GAME_DIRS.contains(&d.to_lowercase().as_str()))
+    {
+        let mut path = vec![dirs[i].to_lowercase()];
+        path.extend(parts[i + 1..].iter().map(|p| p.to_string()));
+        return Some(path.join("/"));
+    }
+
+    let extension = file.rsplit_once('.')?.1.to_lowercase();
+    match extension.as_str() {
+        "bsp" | "res" => Some(format!("maps/{}", file)),
+        "wad" => Some(file.to_string()),
+        _ => None,
+    }
+}
+
+fn same_content(a: &Path, b: &Path) -> bool {
+    match (fs::metadata(a), fs::metadata(b)) {
+        (Ok(ma), Ok(mb)) if ma.len() == mb.len() => {
+            matches!((fs::read(a), fs::read(b)), (Ok(da), Ok(db)) if da == db)
+        }
+        _ => false,
+    }
+}
+
+// Extracts the archive and works out where every file goes, without touching
+// the game folder. Returns each planned file next to its staged copy.
+fn stage(
+    instance: &ServerInstance,
+    staging: &Staging,
+    archive: &Path,
+) -> Result<(MapPackage, Vec<PathBuf>), String> {
+    if !archive.is_file() {
+        return Err(format!("Arquivo não encontrado: {}", archive.display()));
+    }
+    let game = instance.game_path()?;
+    let root = staging.files();
+    fs::create_dir_all(&root).map_err(|e| e.to_string())?;
+    extract(archive, &root)?;
+
+    let mut entries = vec![];
+    walk(&root, &root, &mut entries)?;
+    entries.sort();
+
+    let mut files = vec![];
+    let mut staged = vec![];
+    let mut ignored = vec![];
+    let mut seen = BTreeSet::new();
+    for entry in entries {
+        let Some(path) = destination(&entry).filter(|p| seen.insert(p.clone())) else {
+            ignored.push(entry);
+            continue;
+        };
+        let source = root.join(&entry);
+        let target = game.join(&path);
+        let action = if !target.exists() {
+            FileAction::New
+        } else if same_content(&source, &target) {
+            FileAction::Unchanged
+        } else {
+            FileAction::Overwrite
+        };
+        files.push(PackageFile {
+            size_bytes: fs::metadata(&source).map(|m| m.len()).unwrap_or(0),
+            path,
+            action,
+        });
+        staged.push(source);
+    }
+
+    let mut maps = vec![];
+    for (file, source) in files.iter().zip(&staged) {
+        let Some(map) = file
+            .path
+            .strip_prefix("maps/")
+            .and_then(|name| name.strip_suffix(".bsp"))
+        else {
+            continue;
+        };
+        validate_map_name(map)?;
+        read_bsp(source).map_err(|e| format!("{}.bsp: {}", map, e))?;
+        maps.push(map.to_string());
+    }
+    if maps.is_empty() {
+        return Err("Nenhum .bsp encontrado no pacote.".into());
+    }
+
+    let package = MapPackage {
+        archive: archive.to_string_lossy().into_owned(),
+        maps,
+        files,
+        ignored,
+    };
+    Ok((package, staged))
+}
+
+pub fn preview_package(instance: &ServerInstance, archive: &Path) -> Result<MapPackage, String> {
+    let staging = Staging::new(instance)?;
+    stage(instance, &staging, archive).map(|(package, _)| package)
+}
+
+pub fn move_file(from: &Path, to: &Path) -> io::Result<()> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // Falls back to copying when the mod folder is on another filesystem.
+    fs::rename(from, to).or_else(|_| {
+        fs::copy(from, to)?;
+        fs::remove_file(from)
+    })
+}
+
+// Puts every file in place, or none: on any failure the files already moved
+// are taken back out and the overwritten originals restored.
+pub fn install_package(
+    instance: &ServerInstance,
+    archive: &Path,
+    overwrite: bool,
+) -> Result<MapPackage, String> {
+    let staging = Staging::new(instance)?;
+    let (package, staged) = stage(instance, &staging, archive)?;
+
+    let overwrites = package.overwrites();
+    if !overwrite && !overwrites.is_empty() {
+        return Err(format!(
+            "O pacote sobrescreveria {} arquivo(s): {}",
+            overwrites.len(),
+            overwrites.join(", ")
+        ));
+    }
+
+    let game = instance.game_path()?;
+    let mut placed: Vec<(PathBuf, Option<PathBuf>)> = vec![];
+    let mut manifests = vec![];
+
+    let result = (|| {
+        for (file, source) in package.files.iter().zip(&staged) {
+            let target = game.join(&file.path);
+            let backup = match file.action {
+                FileAction::Unchanged => continue,
+                FileAction::New => None,
+                FileAction::Overwrite => {
+                    let backup = staging.backup().join(&file.path);
+                    move_file(&target, &backup)
+                        .map_err(|e| format!("Erro ao substituir {}: {}", file.path, e))?;
+                    Some(backup)
+                }
+            };
+            placed.push((target.clone(), backup));
+            move_file(source, &target)
+                .map_err(|e| format!("Erro ao instalar {}: {}", file.path, e))?;
+        }
+
+        let installed_at = Local::now().format(TIMESTAMP_FORMAT).to_string();
+        for map in &package.maps {
+            let previous = read_manifest(&instance.id, map);
+            let manifest = merge_manifest(
+                previous.clone(),
+                manifest_for(&package, map, &installed_at),
+                &game,
+            );
+            manifests.push((map, previous));
+            write_manifest(&instance.id, &manifest)?;
+        }
+        Ok::<(), String>(())
+    })();
+
+    if let Err(e) = result {
+        println!("↩️ Instalação falhou, desfazendo: {}", e);
+        for (target, backup) in placed.into_iter().rev() {
+            let _ = fs::remove_file(&target);
+            if let Some(backup) = backup {
+                if let Err(e) = move_file(&backup, &target) {
+                    println!("❌ Erro ao restaurar {}: {}", target.display(), e);
+                }
+            }
+        }
+        for (map, previous) in manifests {
+            match previous {
+                Some(previous) => {
+                    let _ = write_manifest(&instance.id, &previous);
+                }
+                None => {
+                    let _ = fs::remove_file(manifest_path(&instance.id, map));
+                }
+            }
+        }
+        return Err(e);
+    }
+
+    println!(
+        "🗺️ Pacote instalado em '{}': {} ({} arquivos)",
+        instance.id,
+        package.maps.join(", "),
+        package.files.len()
+    );
+    Ok(package)
+}
+
+#[tauri::command]
+pub async fn preview_map_package(
+    path: String,
+    instance_id: Option<String>,
+) -> Result<MapPackage, String> {
+    let instance = get_instance(instance_id.as_deref())?;
+    tauri::async_runtime::spawn_blocking(move || preview_package(&instance, Path::new(&path)))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub async fn install_map_package(
+    path: String,
+    overwrite: Option<bool>,
+    instance_id: Option<String>,
+) -> Result<MapPackage, String> {
+    let instance_id = resolve_instance_id(instance_id);
+    let instance = get_instance(Some(&instance_id))?;
+    let package = tauri::async_runtime::spawn_blocking(move || {
+        install_package(&instance, Path::new(&path), overwrite.unwrap_or(false))
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    for map in &package.maps {
+        notify_map_added(map.clone(), Some(instance_id.clone()))?;
+    }
+    Ok(package)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::bsp::tests::bsp_bytes;
+    use crate::commands::hlds::ServerConfig;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("canalhas-install-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn instance(hlds: &Path) -> ServerInstance {
+        ServerInstance {
+            id: "test".into(),
+            name: "Teste".into(),
+            hlds_path: Some(hlds.to_string_lossy().into_owned()),
+            server_config: ServerConfig::default(),
+            admin_backend: Default::default(),
+        }
+    }
+
+    fn write_zip(path: &Path, entries: &[(&str, &[u8])]) {
+        let mut zip = zip::ZipWriter::new(File::create(path).unwrap());
+        for (name, data) in entries {
+            zip.start_file(*name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(data).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    fn file(path: &str, action: FileAction) -> PackageFile {
+        PackageFile {
+            path: path.into(),
+            size_bytes: 0,
+            action,
+        }
+    }
+
+    #[test]
+    fn destination_maps_entries_to_the_mod_folder() {
+        for (entry, expected) in [
+            ("pack/cstrike/Maps/de_foo.bsp", Some("maps/de_foo.bsp")),
+            ("de_foo.bsp", Some("maps/de_foo.bsp")),
+            ("extras/de_foo.res", Some("maps/de_foo.res")),
+            ("textures/de_foo.WAD", Some("de_foo.WAD")),
+            (
+                "cstrike/SOUND/Ambience/wind.wav",
+                Some("sound/Ambience/wind.wav"),
+            ),
+            ("gfx/env/foort.tga", Some("gfx/env/foort.tga")),
+            ("de_foo/overviews/de_foo.bmp", Some("overviews/de_foo.bmp")),
+            ("readme.txt", None),
+            ("screenshots/de_foo.jpg", None),
+            ("Makefile", None),
+        ] {
+            assert_eq!(destination(entry).as_deref(), expected, "{}", entry);
+        }
+    }
+
+    #[test]
+    fn safe_relative_stays_inside_the_folder() {
+        assert_eq!(
+            safe_relative("maps/de_foo.bsp"),
+            Some(PathBuf::from("maps/de_foo.bsp"))
+        );
+        assert_eq!(
+            safe_relative("./pack\\maps//de_foo.bsp"),
+            Some(PathBuf::from("pack/maps/de_foo.bsp"))
+        );
+        assert_eq!(
+            safe_relative("/etc/passwd"),
+            Some(PathBuf::from("etc/passwd"))
+        );
+        for name in [
+            "../de_foo.bsp",
+            "maps/../../x",
+            "..\\x",
+            "C:/x",
+            "C:\\x",
+            "",
+            "./",
+        ] {
+            assert_eq!(safe_relative(name), None, "{}", name);
+        }
+    }
+
+    #[test]
+    fn manifest_owns_only_new_files_of_its_map() {
+        let package = MapPackage {
+            archive: "pack.zip".into(),
+            maps: vec!["de_a".into(), "de_b".into()],
+            files: vec![
+                file("maps/de_a.bsp", FileAction::New),
+                file("maps/de_a.txt", FileAction::New),
+                file("maps/de_b.bsp", FileAction::New),
+                file("overviews/de_b.bmp", FileAction::New),
+                file("models/shared.mdl", FileAction::New),
+                file("sound/ambience/wind.wav", FileAction::Unchanged),
+                file("sprites/fire.spr", FileAction::Overwrite),
+                file("maps/de_b.res", FileAction::Overwrite),
+            ],
+            ignored: vec![],
+        };
+
+        let a = manifest_for(&package, "de_a", "2024-01-01 00:00:00");
+        assert_eq!(a.map, "de_a");
+        assert_eq!(
+            a.files,
+            ["maps/de_a.bsp", "maps/de_a.txt", "models/shared.mdl"]
+        );
+        assert_eq!(a.overwritten, ["sprites/fire.spr"]);
+
+        let b = manifest_for(&package, "de_b", "2024-01-01 00:00:00");
+        assert_eq!(
+            b.files,
+            ["maps/de_b.bsp", "overviews/de_b.bmp", "models/shared.mdl"]
+        );
+        assert_eq!(b.overwritten, ["sprites/fire.spr", "maps/de_b.res"]);
+    }
+
+    #[test]
+    fn failed_zip_install_rolls_back() {
+        let dir = temp_dir();
+        let hlds = dir.join("hlds");
+        let game = hlds.join("cstrike");
+        fs::create_dir_all(&game).unwrap();
+        fs::write(game.join("de_x.wad"), "old wad").unwrap();
+        // A file where the models folder should be makes the last move fail.
+        fs::write(game.join("models"), "").unwrap();
+
+        let archive = dir.join("de_x.zip");
+        let bsp = bsp_bytes("{\n\"classname\" \"worldspawn\"\n\"wad\" \"de_x.wad\"\n}\n");
+        write_zip(
+            &archive,
+            &[
+                ("de_x/de_x.wad", b"new wad"),
+                ("de_x/maps/de_x.bsp", &bsp),
+                ("de_x/models/de_x/tree.mdl", b"mdl"),
+                ("de_x/readme.txt", b"hi"),
+            ],
+        );
+        let instance = instance(&hlds);
+
+        let package = preview_package(&instance, &archive).unwrap();
+        assert_eq!(package.maps, ["de_x"]);
+        assert_eq!(package.ignored, ["de_x/readme.txt"]);
+        assert_eq!(package.overwrites(), ["de_x.wad"]);
+
+        let err = install_package(&instance, &archive, false).unwrap_err();
+        assert!(err.contains("de_x.wad"), "{}", err);
+
+        let err = install_package(&instance, &archive, true).unwrap_err();
+        assert!(err.contains("models/de_x/tree.mdl"), "{}", err);
+        assert_eq!(
+            fs::read_to_string(game.join("de_x.wad")).unwrap(),
+            "old wad"
+        );
+        assert!(!game.join("maps/de_x.bsp").exists());
+        assert!(fs::read_dir(&hlds)
+            .unwrap()
+            .all(|e| e.unwrap().file_name() == "cstrike"));
+        assert!(!manifest_path(&instance.id, "de_x").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn reinstall_keeps_the_map_files() {
+        let dir = temp_dir();
+        let hlds = dir.join("hlds");
+        let game = hlds.join("cstrike");
+        fs::create_dir_all(&game).unwrap();
+        let instance = ServerInstance {
+            id: format!("test-{}", uuid::Uuid::new_v4()),
+            ..instance(&hlds)
+        };
+
+        let archive = dir.join("de_y.zip");
+        let bsp = bsp_bytes("{\n\"classname\" \"worldspawn\"\n}\n");
+        let entries = |bsp: &[u8]| {
+            write_zip(
+                &archive,
+                &[
+                    ("de_y/maps/de_y.bsp", bsp),
+                    ("de_y/models/de_y/tree.mdl", b"mdl"),
+                    ("de_y/sound/de_y/wind.wav", b"wav"),
+                ],
+            )
+        };
+        let owned = |files: &[&str]| {
+            let mut owned = read_manifest(&instance.id, "de_y").unwrap().files;
+            owned.sort();
+            assert_eq!(owned, files);
+        };
+        let all = [
+            "maps/de_y.bsp",
+            "models/de_y/tree.mdl",
+            "sound/de_y/wind.wav",
+        ];
+
+        entries(&bsp);
+        install_package(&instance, &archive, false).unwrap();
+        owned(&all);
+
+        let package = install_package(&instance, &archive, false).unwrap();
+        assert!(package
+            .files
+            .iter()
+            .all(|f| f.action == FileAction::Unchanged));
+        owned(&all);
+
+        // An update replaces the BSP; it stays the map's own, not an overwrite.
+        let mut updated = bsp.clone();
+        updated.push(0);
+        entries(&updated);
+        install_package(&instance, &archive, true).unwrap();
+        owned(&all);
+        let manifest = read_manifest(&instance.id, "de_y").unwrap();
+        assert!(
+            manifest.overwritten.is_empty(),
+            "{:?}",
+            manifest.overwritten
+        );
+
+        // Files removed by hand since are no longer claimed.
+        fs::remove_file(game.join("sound/de_y/wind.wav")).unwrap();
+        write_zip(&archive, &[("de_y/maps/de_y.bsp", &updated)]);
+        install_package(&instance, &archive, false).unwrap();
+        owned(&["maps/de_y.bsp", "models/de_y/tree.mdl"]);
+
+        fs::remove_dir_all(MAP_MANIFESTS_DIR.join(&instance.id)).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
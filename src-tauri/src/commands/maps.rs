@@ -1,68 +1,485 @@
-use std::fs;
-use std::path::Path;
+use std::{
+    collections::{BTreeSet, HashSet},
+    fs,
+    path::{Path, PathBuf},
+};
 
+use chrono::Local;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+
+use crate::commands::bsp::{read_bsp, BspInfo};
 use crate::commands::event::WebhookEvent;
-use crate::commands::hlds::get_hlds_path;
-use crate::commands::webhook::emit_event;
+use crate::commands::instances::{get_instance, resolve_instance_id, ServerInstance};
+use crate::commands::map_deps::{
+    is_stock, referenced_assets, resolve_dependencies, validate_map_name,
+};
+use crate::commands::map_install::{manifest_path, move_file, read_manifest};
+use crate::commands::webhook::emit_instance_event;
+
+// Deleted maps land here, mirroring the mod folder, so they can be put back by hand.
+pub static TRASH_DIR: Lazy<PathBuf> = Lazy::new(|| {
+    let mut path = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
+    path.push("canalhas-manager/trash");
+    path
+});
+
+#[derive(Serialize, Debug, Clone)]
+pub struct MapInfo {
+    pub name: String,
+    pub size_bytes: u64,
+    pub metadata: Option<BspInfo>,
+    // Why the BSP could not be read; such maps would crash the server on load.
+    pub error: Option<String>,
+}
 
 #[tauri::command]
-pub fn list_maps() -> Result<Vec<String>, String> {
-    let path = get_hlds_path()?.ok_or("HLDS path não configurado.")?;
-    let maps_path = Path::new(&path).join("cstrike/maps");
+pub async fn list_maps(instance_id: Option<String>) -> Result<Vec<MapInfo>, String> {
+    let maps_path = get_instance(instance_id.as_deref())?
+        .game_path()?
+        .join("maps");
+    tauri::async_runtime::spawn_blocking(move || read_maps(&maps_path))
+        .await
+        .map_err(|e| e.to_string())?
+}
 
+// Reads the header and entities of every BSP, so keep it off the main thread.
+fn read_maps(maps_path: &Path) -> Result<Vec<MapInfo>, String> {
     if !maps_path.exists() {
         return Err("Pasta de mapas não encontrada.".to_string());
     }
 
     let mut maps = vec![];
-    for entry in fs::read_dir(&maps_path).map_err(|e| e.to_string())? {
+    for entry in fs::read_dir(maps_path).map_err(|e| e.to_string())? {
         let entry = entry.map_err(|e| e.to_string())?;
         let path = entry.path();
 
-        if let Some(ext) = path.extension() {
-            if ext == "bsp" {
-                if let Some(name) = path.file_stem() {
-                    maps.push(name.to_string_lossy().into_owned());
-                }
-            }
+        if path.extension().is_some_and(|ext| ext == "bsp") {
+            let Some(name) = path.file_stem() else {
+                continue;
+            };
+            let (metadata, error) = match read_bsp(&path) {
+                Ok(bsp) => (Some(bsp.info()), None),
+                Err(e) => (None, Some(e)),
+            };
+            maps.push(MapInfo {
+                name: name.to_string_lossy().into_owned(),
+                size_bytes: entry.metadata().map(|m| m.len()).unwrap_or(0),
+                metadata,
+                error,
+            });
         }
     }
 
+    maps.sort_by(|a, b| a.name.cmp(&b.name));
     Ok(maps)
 }
 
-#[tauri::command]
-pub fn delete_map_files(map_name: String) -> Result<(), String> {
-    let path = get_hlds_path()?.ok_or("HLDS path não configurado.")?;
-    let maps_path = Path::new(&path).join("cstrike/maps");
-    let mut deleted = false;
+#[derive(Serialize, Debug, Clone)]
+pub struct MapDeletion {
+    pub map: String,
+    // Relative to the mod folder.
+    pub trashed: Vec<String>,
+    // Files the map uses but never installed (stock or someone else's), and
+    // its own files that other installed maps still use.
+    pub kept: Vec<String>,
+    pub trash_path: Option<String>,
+}
 
-    for entry in fs::read_dir(&maps_path).map_err(|e| e.to_string())? {
-        let entry = entry.map_err(|e| e.to_string())?;
-        let file_path = entry.path();
+fn stem(file: &str) -> &str {
+    file.rsplit_once('.').map_or(file, |(stem, _)| stem)
+}
+
+// maps/<map>.* and overviews/<map>.* are named after the map they belong to.
+pub fn owning_map(file: &str) -> Option<&str> {
+    file.strip_prefix("maps/")
+        .or_else(|| file.strip_prefix("overviews/"))
+        .map(stem)
+}
 
-        if let Some(file_name) = file_path.file_name().and_then(|n| n.to_str()) {
-            if file_name.starts_with(&map_name) {
-                fs::remove_file(&file_path)
-                    .map_err(|e| format!("Erro ao remover {}: {}", file_name, e))?;
-                deleted = true;
+// Sky sides may be installed as .tga or .bmp, so they compare without extension.
+fn asset_key(path: &str) -> String {
+    let lower = path.to_lowercase();
+    match lower.strip_prefix("gfx/env/") {
+        Some(_) => stem(&lower).to_string(),
+        None => lower,
+    }
+}
+
+pub fn installed_maps(maps_path: &Path) -> Vec<String> {
+    fs::read_dir(maps_path)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .map(|e| e.file_name().to_string_lossy().into_owned())
+                .filter(|name| name.to_lowercase().ends_with(".bsp"))
+                .map(|name| stem(&name).to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// maps/<map>.* and overviews/<map>.*, matched on the whole name so that
+// de_dust never takes de_dust2 with it.
+fn own_files(game: &Path, map: &str) -> Vec<String> {
+    let mut files = vec![];
+    for dir in ["maps", "overviews"] {
+        let Ok(entries) = fs::read_dir(game.join(dir)) else {
+            continue;
+        };
+        for entry in entries.filter_map(Result::ok) {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if entry.path().is_file() && stem(&name).eq_ignore_ascii_case(map) {
+                files.push(format!("{}/{}", dir, name));
             }
         }
     }
+    files
+}
 
-    if deleted {
+// The map's own files and those its install manifest says the package added,
+// then the rest of what the BSP references in the mod folder. Only the first
+// set may be deleted; stock files are in neither. Without a manifest the
+// references are all there is to go on, so they count as the map's own.
+fn map_files(
+    instance: &ServerInstance,
+    map: &str,
+) -> Result<(BTreeSet<String>, BTreeSet<String>), String> {
+    let game = instance.game_path()?;
+    let game_prefix = format!("{}/", instance.server_config.game);
+    let mut owned: BTreeSet<String> = own_files(&game, map).into_iter().collect();
+
+    let manifest = read_manifest(&instance.id, map);
+    let has_manifest = manifest.is_some();
+    if let Some(manifest) = manifest {
+        owned.extend(manifest.files);
+    }
+    let mut referenced = BTreeSet::new();
+    match resolve_dependencies(instance, map) {
+        Ok(deps) => referenced.extend(deps.assets.into_iter().filter_map(|a| {
+            a.found
+                .as_deref()
+                .and_then(|f| f.strip_prefix(&game_prefix))
+                .map(str::to_string)
+        })),
+        Err(e) => println!("⚠️ Dependências de {} não lidas: {}", map, e),
+    }
+
+    let on_disk = |f: &String| game.join(f).is_file() && !is_stock(f, None);
+    owned.retain(on_disk);
+    referenced.retain(|f| on_disk(f) && !owned.iter().any(|o| o.eq_ignore_ascii_case(f)));
+    if !has_manifest {
+        owned.append(&mut referenced);
+    }
+    Ok((owned, referenced))
+}
+
+// Everything the other installed maps reference, by asset_key.
+fn shared_assets(instance: &ServerInstance, map: &str, others: &[String]) -> HashSet<String> {
+    let mut shared = HashSet::new();
+    for other in others.iter().filter(|m| !m.eq_ignore_ascii_case(map)) {
+        if let Some(manifest) = read_manifest(&instance.id, other) {
+            shared.extend(manifest.files.iter().map(|f| asset_key(f)));
+        }
+        if let Ok(assets) = referenced_assets(instance, other) {
+            shared.extend(assets.keys().map(|f| asset_key(f)));
+        }
+    }
+    shared
+}
+
+// Moves every file or none: a failed move puts back the ones already moved.
+fn move_to_trash(game: &Path, files: &[String], trash: &Path) -> Result<(), String> {
+    let mut moved = vec![];
+    for file in files {
+        if let Err(e) = move_file(&game.join(file), &trash.join(file)) {
+            for file in moved.iter().rev() {
+                let _ = move_file(&trash.join(file), &game.join(file));
+            }
+            return Err(format!("Erro ao mover {} para a lixeira: {}", file, e));
+        }
+        moved.push(file);
+    }
+    Ok(())
+}
+
+pub fn delete_map(instance: &ServerInstance, map: &str) -> Result<MapDeletion, String> {
+    validate_map_name(map)?;
+    let game = instance.game_path()?;
+    let maps_path = game.join("maps");
+    let manifest = manifest_path(&instance.id, map);
+    let others = installed_maps(&maps_path);
+
+    let (files, not_owned) = map_files(instance, map)?;
+    if files.is_empty() && !manifest.exists() {
+        return Err(format!("Mapa '{}' não encontrado.", map));
+    }
+
+    let shared = shared_assets(instance, map, &others);
+    let (mut kept, trashed): (Vec<String>, Vec<String>) = files.into_iter().partition(|f| {
+        // Another map's own files, e.g. a package that shipped two maps.
+        let owned_by_other = owning_map(f).is_some_and(|o| {
+            !o.eq_ignore_ascii_case(map) && others.iter().any(|m| m.eq_ignore_ascii_case(o))
+        });
+        owned_by_other || shared.contains(&asset_key(f))
+    });
+    kept.extend(not_owned);
+
+    let trash = TRASH_DIR.join(&instance.id).join(format!(
+        "{}-{}",
+        map,
+        Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    move_to_trash(&game, &trashed, &trash)?;
+    if manifest.exists() {
+        let _ = move_file(&manifest, &trash.join("manifest.json"));
+    }
+
+    println!(
+        "🗑️ Mapa '{}' removido: {} arquivo(s) na lixeira, {} mantido(s)",
+        map,
+        trashed.len(),
+        kept.len()
+    );
+    Ok(MapDeletion {
+        map: map.to_string(),
+        trash_path: (!trashed.is_empty()).then(|| trash.to_string_lossy().into_owned()),
+        trashed,
+        kept,
+    })
+}
+
+// Reads every other installed BSP to find shared files, so it runs off the main thread.
+#[tauri::command]
+pub async fn delete_map_files(
+    map_name: String,
+    instance_id: Option<String>,
+) -> Result<MapDeletion, String> {
+    let instance_id = resolve_instance_id(instance_id);
+    let instance = get_instance(Some(&instance_id))?;
+    let map = map_name.clone();
+    let deletion = tauri::async_runtime::spawn_blocking(move || delete_map(&instance, &map))
+        .await
+        .map_err(|e| e.to_string())??;
+
+    if !deletion.trashed.is_empty() {
         tauri::async_runtime::spawn(async move {
-            emit_event(WebhookEvent::MapDeleted(map_name)).await;
+            emit_instance_event(instance_id, WebhookEvent::MapDeleted(map_name)).await;
         });
     }
 
-    Ok(())
+    Ok(deletion)
 }
 
 #[tauri::command]
-pub fn notify_map_added(map_name: String) -> Result<(), String> {
+pub fn notify_map_added(map_name: String, instance_id: Option<String>) -> Result<(), String> {
+    let instance_id = resolve_instance_id(instance_id);
     tauri::async_runtime::spawn(async move {
-        emit_event(WebhookEvent::MapAdded(map_name)).await;
+        emit_instance_event(instance_id, WebhookEvent::MapAdded(map_name)).await;
     });
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::bsp::tests::bsp_bytes;
+    use crate::commands::hlds::ServerConfig;
+    use crate::commands::map_install::{MapManifest, MAP_MANIFESTS_DIR};
+
+    struct Fixture {
+        dir: PathBuf,
+        instance: ServerInstance,
+    }
+
+    impl Fixture {
+        fn new() -> Self {
+            let dir = std::env::temp_dir().join(format!("canalhas-maps-{}", uuid::Uuid::new_v4()));
+            let hlds = dir.join("hlds");
+            fs::create_dir_all(hlds.join("cstrike/maps")).unwrap();
+            let instance = ServerInstance {
+                id: format!("test-{}", uuid::Uuid::new_v4()),
+                name: "Teste".into(),
+                hlds_path: Some(hlds.to_string_lossy().into_owned()),
+                server_config: ServerConfig::default(),
+                admin_backend: Default::default(),
+            };
+            Self { dir, instance }
+        }
+
+        fn game(&self) -> PathBuf {
+            self.instance.game_path().unwrap()
+        }
+
+        fn file(&self, path: &str) {
+            let path = self.game().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, path_bytes(&path)).unwrap();
+        }
+
+        // A map whose entities point at the given models.
+        fn map(&self, map: &str, models: &[&str]) {
+            let mut entities =
+                "{\n\"classname\" \"worldspawn\"\n\"wad\" \"cs_dust.wad\"\n}\n".to_string();
+            for model in models {
+                entities.push_str(&format!(
+                    "{{\n\"classname\" \"cycler\"\n\"model\" \"{}\"\n}}\n",
+                    model
+                ));
+            }
+            fs::write(
+                self.game().join(format!("maps/{}.bsp", map)),
+                bsp_bytes(&entities),
+            )
+            .unwrap();
+        }
+
+        fn exists(&self, path: &str) -> bool {
+            self.game().join(path).is_file()
+        }
+    }
+
+    impl Drop for Fixture {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.dir);
+            let _ = fs::remove_dir_all(TRASH_DIR.join(&self.instance.id));
+            let _ = fs::remove_dir_all(MAP_MANIFESTS_DIR.join(&self.instance.id));
+        }
+    }
+
+    fn path_bytes(path: &Path) -> Vec<u8> {
+        path.to_string_lossy().into_owned().into_bytes()
+    }
+
+    #[test]
+    fn deleting_de_dust_leaves_de_dust2_alone() {
+        let fixture = Fixture::new();
+        fixture.map("de_dust", &[]);
+        fixture.map("de_dust2", &[]);
+        for file in [
+            "maps/de_dust.txt",
+            "maps/de_dust2.res",
+            "maps/de_dust2.txt",
+            "overviews/de_dust.bmp",
+            "overviews/de_dust2.bmp",
+            "cs_dust.wad",
+        ] {
+            fixture.file(file);
+        }
+
+        let deletion = delete_map(&fixture.instance, "de_dust").unwrap();
+        assert_eq!(
+            deletion.trashed,
+            [
+                "maps/de_dust.bsp",
+                "maps/de_dust.txt",
+                "overviews/de_dust.bmp"
+            ]
+        );
+        assert!(deletion.kept.is_empty(), "{:?}", deletion.kept);
+        for file in [
+            "maps/de_dust2.bsp",
+            "maps/de_dust2.res",
+            "maps/de_dust2.txt",
+            "overviews/de_dust2.bmp",
+            "cs_dust.wad",
+        ] {
+            assert!(fixture.exists(file), "{}", file);
+        }
+
+        let trash = PathBuf::from(deletion.trash_path.unwrap());
+        assert!(trash.join("maps/de_dust.bsp").is_file());
+        assert!(delete_map(&fixture.instance, "de_dust").is_err());
+    }
+
+    #[test]
+    fn files_another_map_uses_are_kept() {
+        let fixture = Fixture::new();
+        fixture.map("de_a", &["models/shared.mdl", "models/de_a/tree.mdl"]);
+        fixture.map("de_b", &["models/shared.mdl"]);
+        fixture.file("models/shared.mdl");
+        fixture.file("models/de_a/tree.mdl");
+
+        let deletion = delete_map(&fixture.instance, "de_a").unwrap();
+        assert_eq!(deletion.trashed, ["maps/de_a.bsp", "models/de_a/tree.mdl"]);
+        assert_eq!(deletion.kept, ["models/shared.mdl"]);
+        assert!(fixture.exists("models/shared.mdl"));
+        assert!(!fixture.exists("models/de_a/tree.mdl"));
+    }
+
+    #[test]
+    fn manifest_decides_what_the_map_owns() {
+        let fixture = Fixture::new();
+        fixture.map("de_m", &["models/de_m/tree.mdl", "models/old.mdl"]);
+        for file in [
+            "models/de_m/tree.mdl",
+            "models/old.mdl",
+            "sound/de_m/wind.wav",
+            "de_m.wad",
+        ] {
+            fixture.file(file);
+        }
+
+        let manifest = MapManifest {
+            map: "de_m".into(),
+            archive: "de_m.zip".into(),
+            installed_at: "2024-01-01 00:00:00".into(),
+            files: vec![
+                "maps/de_m.bsp".into(),
+                "models/de_m/tree.mdl".into(),
+                "sound/de_m/wind.wav".into(),
+                "de_m.wad".into(),
+            ],
+            overwritten: vec![],
+        };
+        let path = manifest_path(&fixture.instance.id, "de_m");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, serde_json::to_string(&manifest).unwrap()).unwrap();
+
+        let deletion = delete_map(&fixture.instance, "de_m").unwrap();
+        assert_eq!(
+            deletion.trashed,
+            [
+                "de_m.wad",
+                "maps/de_m.bsp",
+                "models/de_m/tree.mdl",
+                "sound/de_m/wind.wav"
+            ]
+        );
+        // Referenced, but already on disk before the package.
+        assert_eq!(deletion.kept, ["models/old.mdl"]);
+        assert!(fixture.exists("models/old.mdl"));
+        assert!(!path.exists());
+        let trash = PathBuf::from(deletion.trash_path.unwrap());
+        assert!(trash.join("manifest.json").is_file());
+        assert!(trash.join("sound/de_m/wind.wav").is_file());
+    }
+
+    #[test]
+    fn failed_move_puts_files_back() {
+        let fixture = Fixture::new();
+        let game = fixture.game();
+        fixture.file("maps/de_r.bsp");
+        fixture.file("sound/de_r/wind.wav");
+
+        // A file where the trash needs a folder makes the second move fail.
+        let trash = fixture.dir.join("trash");
+        fs::create_dir_all(&trash).unwrap();
+        fs::write(trash.join("sound"), "").unwrap();
+
+        let files = vec![
+            "maps/de_r.bsp".to_string(),
+            "sound/de_r/wind.wav".to_string(),
+        ];
+        let err = move_to_trash(&game, &files, &trash).unwrap_err();
+        assert!(err.contains("sound/de_r/wind.wav"), "{}", err);
+
+        assert!(fixture.exists("maps/de_r.bsp"));
+        assert!(fixture.exists("sound/de_r/wind.wav"));
+        assert!(!trash.join("maps/de_r.bsp").exists());
+        assert_eq!(
+            fs::read(game.join("maps/de_r.bsp")).unwrap(),
+            path_bytes(&game.join("maps/de_r.bsp"))
+        );
+    }
+}
@@ -1,9 +1,32 @@
+pub mod admin_backend;
+pub mod amxx_flags;
+pub mod attach;
+pub mod bans;
+pub mod bsp;
+pub mod chat;
+pub mod console;
 pub mod event;
 pub mod greet;
 pub mod hlds;
+pub mod instances;
+pub mod log_parser;
+pub mod map_deps;
+pub mod map_install;
 pub mod maps;
+pub mod pidfile;
+pub mod players;
+pub mod ports;
+pub mod query;
+pub mod rcon;
+pub mod rotation;
+pub mod shutdown;
+pub mod sink;
+pub mod stats;
+pub mod status;
+pub mod supervisor;
 pub mod system;
 pub mod users;
+pub mod users_ini;
 pub mod webhook;
 
 pub use greet::*;
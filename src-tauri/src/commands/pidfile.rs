@@ -0,0 +1,123 @@
+use std::{fs, path::PathBuf};
+
+use chrono::{DateTime, Local};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+pub static PID_DIR: Lazy<PathBuf> = Lazy::new(|| {
+    let mut path = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
+    path.push("canalhas-manager/pids");
+    path
+});
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RunRecord {
+    pub pid: u32,
+    pub args: Vec<String>,
+    pub started_at: DateTime<Local>,
+    // Kernel start time of the process, so a reused PID is not mistaken for our server.
+    #[serde(default)]
+    pub proc_start: Option<u64>,
+}
+
+impl RunRecord {
+    pub fn is_alive(&self) -> bool {
+        if !is_alive(self.pid) {
+            return false;
+        }
+        match (self.proc_start, process_start_time(self.pid)) {
+            (Some(recorded), Some(current)) => recorded == current,
+            _ => true,
+        }
+    }
+}
+
+fn pid_file(instance_id: &str) -> PathBuf {
+    PID_DIR.join(format!("{}.json", instance_id))
+}
+
+pub fn write_run_record(instance_id: &str, pid: u32, args: &[String]) {
+    let record = RunRecord {
+        pid,
+        args: args.to_vec(),
+        started_at: Local::now(),
+        proc_start: process_start_time(pid),
+    };
+
+    let result = fs::create_dir_all(&*PID_DIR).and_then(|_| {
+        let json = serde_json::to_string_pretty(&record)?;
+        fs::write(pid_file(instance_id), json)
+    });
+
+    if let Err(e) = result {
+        println!(
+            "⚠️ Não foi possível gravar o PID do HLDS [{}]: {}",
+            instance_id, e
+        );
+    }
+}
+
+pub fn read_run_record(instance_id: &str) -> Option<RunRecord> {
+    let contents = fs::read_to_string(pid_file(instance_id)).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+pub fn remove_pid_file(instance_id: &str) {
+    let _ = fs::remove_file(pid_file(instance_id));
+}
+
+// PIDs of servers the manager launched that are still running. A PID file
+// left behind by a crash may name a PID that now belongs to something else.
+pub fn managed_pids() -> Vec<u32> {
+    let Ok(entries) = fs::read_dir(&*PID_DIR) else {
+        return vec![];
+    };
+
+    entries
+        .filter_map(Result::ok)
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|e| fs::read_to_string(e.path()).ok())
+        .filter_map(|json| serde_json::from_str::<RunRecord>(&json).ok())
+        .filter(RunRecord::is_alive)
+        .map(|record| record.pid)
+        .collect()
+}
+
+// Fields after the ")" that closes the command name: state is the first, starttime the 20th.
+#[cfg(target_os = "linux")]
+fn stat_fields(pid: u32) -> Option<Vec<String>> {
+    let stat = fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    let (_, rest) = stat.rsplit_once(')')?;
+    Some(rest.split_whitespace().map(String::from).collect())
+}
+
+#[cfg(target_os = "linux")]
+pub fn process_start_time(pid: u32) -> Option<u64> {
+    stat_fields(pid)?.get(19)?.parse().ok()
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn process_start_time(_pid: u32) -> Option<u64> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+pub fn is_alive(pid: u32) -> bool {
+    stat_fields(pid).is_some_and(|fields| fields.first().is_some_and(|state| state != "Z"))
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+pub fn is_alive(pid: u32) -> bool {
+    std::process::Command::new("kill")
+        .args(["-0", &pid.to_string()])
+        .output()
+        .is_ok_and(|o| o.status.success())
+}
+
+#[cfg(windows)]
+pub fn is_alive(pid: u32) -> bool {
+    std::process::Command::new("tasklist")
+        .args(["/FI", &format!("PID eq {}", pid), "/FO", "CSV", "/NH"])
+        .output()
+        .is_ok_and(|o| String::from_utf8_lossy(&o.stdout).contains(&format!("\"{}\"", pid)))
+}
@@ -0,0 +1,591 @@
+use std::{collections::HashMap, fs, path::PathBuf, sync::Mutex};
+
+use chrono::Local;
+use once_cell::sync::Lazy;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+use crate::commands::log_parser::{GameEvent, LogEntry, LogPlayer};
+use crate::commands::stats::player_key;
+
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+pub static PLAYERS_DB_PATH: Lazy<PathBuf> = Lazy::new(|| {
+    let mut path = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
+    path.push("canalhas-manager/players.db");
+    path
+});
+
+static PLAYERS_DB: Lazy<Mutex<Option<Connection>>> = Lazy::new(|| Mutex::new(None));
+
+// Addresses of players still waiting for Steam validation, by (instance, userid).
+static PENDING_ADDRESSES: Lazy<Mutex<HashMap<(String, i32), String>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS players (
+    player_id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    first_seen TEXT NOT NULL,
+    last_seen TEXT NOT NULL,
+    playtime_secs INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE TABLE IF NOT EXISTS player_names (
+    player_id TEXT NOT NULL REFERENCES players (player_id),
+    name TEXT NOT NULL,
+    first_seen TEXT NOT NULL,
+    last_seen TEXT NOT NULL,
+    PRIMARY KEY (player_id, name)
+);
+
+CREATE TABLE IF NOT EXISTS player_ips (
+    player_id TEXT NOT NULL REFERENCES players (player_id),
+    ip TEXT NOT NULL,
+    first_seen TEXT NOT NULL,
+    last_seen TEXT NOT NULL,
+    PRIMARY KEY (player_id, ip)
+);
+
+CREATE TABLE IF NOT EXISTS sessions (
+    id INTEGER PRIMARY KEY,
+    instance TEXT NOT NULL,
+    player_id TEXT NOT NULL REFERENCES players (player_id),
+    name TEXT NOT NULL,
+    ip TEXT,
+    connected_at TEXT NOT NULL,
+    disconnected_at TEXT
+);
+
+CREATE INDEX IF NOT EXISTS player_ips_ip ON player_ips (ip);
+CREATE INDEX IF NOT EXISTS player_names_name ON player_names (name);
+CREATE INDEX IF NOT EXISTS sessions_open ON sessions (instance, player_id, disconnected_at);
+";
+
+fn with_players_db<T>(f: impl FnOnce(&Connection) -> rusqlite::Result<T>) -> Result<T, String> {
+    let mut guard = PLAYERS_DB.lock().unwrap();
+
+    if guard.is_none() {
+        if let Some(parent) = PLAYERS_DB_PATH.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let conn = Connection::open(&*PLAYERS_DB_PATH).map_err(|e| e.to_string())?;
+        conn.execute_batch(SCHEMA).map_err(|e| e.to_string())?;
+        *guard = Some(conn);
+    }
+
+    f(guard.as_ref().unwrap()).map_err(|e| e.to_string())
+}
+
+fn is_tracked(player: &LogPlayer) -> bool {
+    !matches!(player.steam_id.as_str(), "BOT" | "HLTV")
+}
+
+// "1.2.3.4:27005" -> "1.2.3.4"; "loopback" stays as is.
+fn strip_port(address: &str) -> &str {
+    address.rsplit_once(':').map_or(address, |(ip, _)| ip)
+}
+
+fn seen(conn: &Connection, id: &str, name: &str, at: &str) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT INTO players (player_id, name, first_seen, last_seen) VALUES (?1, ?2, ?3, ?3)
+         ON CONFLICT (player_id) DO UPDATE SET name = ?2, last_seen = max(last_seen, ?3)",
+        params![id, name, at],
+    )?;
+    conn.execute(
+        "INSERT INTO player_names (player_id, name, first_seen, last_seen) VALUES (?1, ?2, ?3, ?3)
+         ON CONFLICT (player_id, name) DO UPDATE SET last_seen = max(last_seen, ?3)",
+        params![id, name, at],
+    )?;
+    Ok(())
+}
+
+fn close_session(conn: &Connection, instance_id: &str, id: &str, at: &str) -> rusqlite::Result<()> {
+    let open: Option<(i64, String)> = conn
+        .query_row(
+            "SELECT id, connected_at FROM sessions
+             WHERE instance = ?1 AND player_id = ?2 AND disconnected_at IS NULL
+             ORDER BY connected_at DESC LIMIT 1",
+            params![instance_id, id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?;
+
+    let Some((session, connected_at)) = open else {
+        return Ok(());
+    };
+    conn.execute(
+        "UPDATE sessions SET disconnected_at = max(?2, connected_at) WHERE id = ?1",
+        params![session, at],
+    )?;
+    conn.execute(
+        "UPDATE players SET playtime_secs = playtime_secs
+            + max(0, strftime('%s', ?2) - strftime('%s', ?3))
+         WHERE player_id = ?1",
+        params![id, at, connected_at],
+    )?;
+    Ok(())
+}
+
+fn open_session(
+    conn: &Connection,
+    instance_id: &str,
+    id: &str,
+    player: &LogPlayer,
+    ip: Option<&str>,
+    at: &str,
+) -> rusqlite::Result<()> {
+    if let Some(ip) = ip {
+        conn.execute(
+            "INSERT INTO player_ips (player_id, ip, first_seen, last_seen) VALUES (?1, ?2, ?3, ?3)
+             ON CONFLICT (player_id, ip) DO UPDATE SET last_seen = max(last_seen, ?3)",
+            params![id, ip, at],
+        )?;
+    }
+    // A reconnect without a disconnect line (e.g. a crash) ends the previous session.
+    close_session(conn, instance_id, id, at)?;
+    conn.execute(
+        "INSERT INTO sessions (instance, player_id, name, ip, connected_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![instance_id, id, player.name, ip, at],
+    )?;
+    Ok(())
+}
+
+fn has_open_session(conn: &Connection, instance_id: &str, id: &str) -> rusqlite::Result<bool> {
+    conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM sessions
+         WHERE instance = ?1 AND player_id = ?2 AND disconnected_at IS NULL)",
+        params![instance_id, id],
+        |row| row.get(0),
+    )
+}
+
+// Called for every parsed line of a server's output.
+pub fn record_player_event(instance_id: &str, entry: &LogEntry) {
+    if let Err(e) = with_players_db(|conn| record(conn, instance_id, entry)) {
+        println!("❌ Erro ao registrar jogador: {}", e);
+    }
+}
+
+fn record(conn: &Connection, instance_id: &str, entry: &LogEntry) -> rusqlite::Result<()> {
+    let at = entry
+        .timestamp
+        .unwrap_or_else(|| Local::now().naive_local())
+        .format(TIMESTAMP_FORMAT)
+        .to_string();
+
+    match &entry.event {
+        // Steam validates after the connect line, so a pending player is only
+        // recorded once the real ID shows up when entering the game.
+        GameEvent::Connected { player, address } if player.steam_id == "STEAM_ID_PENDING" => {
+            PENDING_ADDRESSES.lock().unwrap().insert(
+                (instance_id.to_string(), player.user_id),
+                strip_port(address).to_string(),
+            );
+            Ok(())
+        }
+        GameEvent::Connected { player, address } if is_tracked(player) => {
+            let id = player_key(player);
+            seen(conn, &id, &player.name, &at)?;
+            open_session(
+                conn,
+                instance_id,
+                &id,
+                player,
+                Some(strip_port(address)),
+                &at,
+            )
+        }
+        GameEvent::EnteredGame { player } if is_tracked(player) => {
+            let id = player_key(player);
+            seen(conn, &id, &player.name, &at)?;
+            let pending = PENDING_ADDRESSES
+                .lock()
+                .unwrap()
+                .remove(&(instance_id.to_string(), player.user_id));
+            if pending.is_some() || !has_open_session(conn, instance_id, &id)? {
+                open_session(conn, instance_id, &id, player, pending.as_deref(), &at)?;
+            }
+            Ok(())
+        }
+        GameEvent::Disconnected { player } if player.steam_id == "STEAM_ID_PENDING" => {
+            PENDING_ADDRESSES
+                .lock()
+                .unwrap()
+                .remove(&(instance_id.to_string(), player.user_id));
+            Ok(())
+        }
+        GameEvent::Disconnected { player } if is_tracked(player) => {
+            let id = player_key(player);
+            seen(conn, &id, &player.name, &at)?;
+            close_session(conn, instance_id, &id, &at)
+        }
+        GameEvent::ChangedName { player, new_name } if is_tracked(player) => {
+            let id = player_key(player);
+            seen(conn, &id, &player.name, &at)?;
+            seen(conn, &id, new_name, &at)
+        }
+        GameEvent::JoinedTeam { player, .. } | GameEvent::Say { player, .. }
+            if is_tracked(player) =>
+        {
+            seen(conn, &player_key(player), &player.name, &at)
+        }
+        _ => Ok(()),
+    }
+}
+
+// A fresh server process has nobody connected; sessions left open by a crash
+// or a stop end at the player's last sighting.
+pub fn close_open_sessions(instance_id: &str) {
+    PENDING_ADDRESSES
+        .lock()
+        .unwrap()
+        .retain(|(instance, _), _| instance != instance_id);
+
+    if let Err(e) = with_players_db(|conn| close_sessions(conn, instance_id)) {
+        println!("❌ Erro ao encerrar sessões de jogadores: {}", e);
+    }
+}
+
+fn close_sessions(conn: &Connection, instance_id: &str) -> rusqlite::Result<()> {
+    let open: Vec<String> = conn
+        .prepare(
+            "SELECT DISTINCT player_id FROM sessions
+             WHERE instance = ?1 AND disconnected_at IS NULL",
+        )?
+        .query_map([instance_id], |row| row.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+
+    for id in open {
+        let last_seen: String = conn.query_row(
+            "SELECT last_seen FROM players WHERE player_id = ?1",
+            [&id],
+            |row| row.get(0),
+        )?;
+        close_session(conn, instance_id, &id, &last_seen)?;
+    }
+    Ok(())
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct PlayerSummary {
+    pub player_id: String,
+    pub name: String,
+    pub first_seen: String,
+    pub last_seen: String,
+    pub playtime_secs: i64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct SeenValue {
+    pub value: String,
+    pub first_seen: String,
+    pub last_seen: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct PlayerSession {
+    pub instance: String,
+    pub name: String,
+    pub ip: Option<String>,
+    pub connected_at: String,
+    pub disconnected_at: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct PlayerProfile {
+    #[serde(flatten)]
+    pub summary: PlayerSummary,
+    pub aliases: Vec<SeenValue>,
+    pub ips: Vec<SeenValue>,
+    pub sessions: Vec<PlayerSession>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+pub struct PlayerQuery {
+    // Matches SteamIDs and any name the player has used.
+    pub text: Option<String>,
+    // Exact IP, or a prefix ending in "." such as "192.168.0.".
+    pub ip: Option<String>,
+    pub limit: Option<u32>,
+}
+
+fn summary_from_row(row: &rusqlite::Row) -> rusqlite::Result<PlayerSummary> {
+    Ok(PlayerSummary {
+        player_id: row.get(0)?,
+        name: row.get(1)?,
+        first_seen: row.get(2)?,
+        last_seen: row.get(3)?,
+        playtime_secs: row.get(4)?,
+    })
+}
+
+fn seen_from_row(row: &rusqlite::Row) -> rusqlite::Result<SeenValue> {
+    Ok(SeenValue {
+        value: row.get(0)?,
+        first_seen: row.get(1)?,
+        last_seen: row.get(2)?,
+    })
+}
+
+pub fn search(query: &PlayerQuery) -> Result<Vec<PlayerSummary>, String> {
+    let text = query
+        .text
+        .as_deref()
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(|t| format!("%{}%", t));
+    let ip = query
+        .ip
+        .as_deref()
+        .map(str::trim)
+        .filter(|ip| !ip.is_empty())
+        .map(|ip| match ip.ends_with('.') {
+            true => format!("{}%", ip),
+            false => ip.to_string(),
+        });
+    let limit = query.limit.unwrap_or(50);
+
+    with_players_db(|conn| {
+        conn.prepare(
+            "SELECT p.player_id, p.name, p.first_seen, p.last_seen, p.playtime_secs
+             FROM players p
+             WHERE (?1 IS NULL OR p.player_id LIKE ?1 OR EXISTS (
+                    SELECT 1 FROM player_names n WHERE n.player_id = p.player_id AND n.name LIKE ?1))
+               AND (?2 IS NULL OR EXISTS (
+                    SELECT 1 FROM player_ips i WHERE i.player_id = p.player_id AND i.ip LIKE ?2))
+             ORDER BY p.last_seen DESC
+             LIMIT ?3",
+        )?
+        .query_map(params![text, ip, limit], summary_from_row)?
+        .collect()
+    })
+}
+
+pub fn profile(player_id: &str) -> Result<PlayerProfile, String> {
+    with_players_db(|conn| {
+        let summary = conn
+            .query_row(
+                "SELECT player_id, name, first_seen, last_seen, playtime_secs
+                 FROM players WHERE player_id = ?1",
+                [player_id],
+                summary_from_row,
+            )
+            .optional()?;
+        let Some(summary) = summary else {
+            return Ok(None);
+        };
+
+        let aliases = conn
+            .prepare(
+                "SELECT name, first_seen, last_seen FROM player_names
+                 WHERE player_id = ?1 ORDER BY last_seen DESC",
+            )?
+            .query_map([player_id], seen_from_row)?
+            .collect::<rusqlite::Result<_>>()?;
+
+        let ips = conn
+            .prepare(
+                "SELECT ip, first_seen, last_seen FROM player_ips
+                 WHERE player_id = ?1 ORDER BY last_seen DESC",
+            )?
+            .query_map([player_id], seen_from_row)?
+            .collect::<rusqlite::Result<_>>()?;
+
+        let sessions = conn
+            .prepare(
+                "SELECT instance, name, ip, connected_at, disconnected_at FROM sessions
+                 WHERE player_id = ?1 ORDER BY connected_at DESC LIMIT 50",
+            )?
+            .query_map([player_id], |row| {
+                Ok(PlayerSession {
+                    instance: row.get(0)?,
+                    name: row.get(1)?,
+                    ip: row.get(2)?,
+                    connected_at: row.get(3)?,
+                    disconnected_at: row.get(4)?,
+                })
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+
+        Ok(Some(PlayerProfile {
+            summary,
+            aliases,
+            ips,
+            sessions,
+        }))
+    })?
+    .ok_or_else(|| format!("Jogador '{}' não encontrado.", player_id))
+}
+
+#[tauri::command]
+pub async fn search_players(query: Option<PlayerQuery>) -> Result<Vec<PlayerSummary>, String> {
+    let query = query.unwrap_or_default();
+    tauri::async_runtime::spawn_blocking(move || search(&query))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub async fn get_player_profile(player_id: String) -> Result<PlayerProfile, String> {
+    tauri::async_runtime::spawn_blocking(move || profile(&player_id))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::log_parser::parse_log_line;
+
+    const FULANO: &str = "STEAM_0:1:12345";
+
+    fn setup() -> (Connection, String) {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(SCHEMA).unwrap();
+        // PENDING_ADDRESSES is shared between tests.
+        (conn, uuid::Uuid::new_v4().to_string())
+    }
+
+    fn log(conn: &Connection, instance_id: &str, time: &str, body: &str) {
+        let line = format!("L 08/13/2024 - {}: {}", time, body);
+        record(conn, instance_id, &parse_log_line(&line).unwrap()).unwrap();
+    }
+
+    fn sessions(conn: &Connection) -> Vec<(String, Option<String>, String, Option<String>)> {
+        conn.prepare("SELECT instance, ip, connected_at, disconnected_at FROM sessions ORDER BY id")
+            .unwrap()
+            .query_map([], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+            })
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap()
+    }
+
+    fn playtime(conn: &Connection, id: &str) -> i64 {
+        conn.query_row(
+            "SELECT playtime_secs FROM players WHERE player_id = ?1",
+            [id],
+            |row| row.get(0),
+        )
+        .unwrap()
+    }
+
+    fn at(time: &str) -> String {
+        format!("2024-08-13 {}", time)
+    }
+
+    #[test]
+    fn pending_player_gets_address_on_entering() {
+        let (conn, instance) = setup();
+        log(
+            &conn,
+            &instance,
+            "21:40:01",
+            r#""Fulano<2><STEAM_ID_PENDING><>" connected, address "192.168.0.10:27005""#,
+        );
+        assert!(sessions(&conn).is_empty());
+
+        log(
+            &conn,
+            &instance,
+            "21:40:05",
+            r#""Fulano<2><STEAM_0:1:12345><>" entered the game"#,
+        );
+        log(
+            &conn,
+            &instance,
+            "21:50:05",
+            r#""Fulano<2><STEAM_0:1:12345><CT>" disconnected"#,
+        );
+
+        assert_eq!(
+            sessions(&conn),
+            [(
+                instance,
+                Some("192.168.0.10".to_string()),
+                at("21:40:05"),
+                Some(at("21:50:05"))
+            )]
+        );
+        assert_eq!(playtime(&conn, FULANO), 600);
+        let ip: String = conn
+            .query_row(
+                "SELECT ip FROM player_ips WHERE player_id = ?1",
+                [FULANO],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(ip, "192.168.0.10");
+    }
+
+    #[test]
+    fn entering_keeps_the_session_from_connect() {
+        let (conn, instance) = setup();
+        log(
+            &conn,
+            &instance,
+            "21:40:01",
+            r#""Fulano<2><STEAM_0:1:12345><>" connected, address "10.0.0.2:27005""#,
+        );
+        log(
+            &conn,
+            &instance,
+            "21:40:05",
+            r#""Fulano<2><STEAM_0:1:12345><>" entered the game"#,
+        );
+
+        assert_eq!(
+            sessions(&conn),
+            [(instance, Some("10.0.0.2".to_string()), at("21:40:01"), None)]
+        );
+    }
+
+    #[test]
+    fn reconnect_closes_the_previous_session() {
+        let (conn, instance) = setup();
+        let connect = r#""Fulano<2><STEAM_0:1:12345><>" connected, address "10.0.0.2:27005""#;
+        log(&conn, &instance, "21:40:00", connect);
+        // No disconnect line, e.g. the client crashed.
+        log(&conn, &instance, "21:45:00", connect);
+
+        let sessions = sessions(&conn);
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].3, Some(at("21:45:00")));
+        assert_eq!(sessions[1].2, at("21:45:00"));
+        assert_eq!(sessions[1].3, None);
+        assert_eq!(playtime(&conn, FULANO), 300);
+    }
+
+    #[test]
+    fn restart_closes_sessions_at_last_sighting() {
+        let (conn, instance) = setup();
+        log(
+            &conn,
+            &instance,
+            "21:40:00",
+            r#""Fulano<2><STEAM_0:1:12345><>" connected, address "10.0.0.2:27005""#,
+        );
+        log(
+            &conn,
+            &instance,
+            "21:42:30",
+            r#""Fulano<2><STEAM_0:1:12345><CT>" say "gg""#,
+        );
+        log(
+            &conn,
+            "outro",
+            "21:41:00",
+            r#""Ciclano<3><STEAM_0:0:54321><>" connected, address "10.0.0.3:27005""#,
+        );
+
+        close_sessions(&conn, &instance).unwrap();
+
+        let sessions = sessions(&conn);
+        assert_eq!(sessions[0].3, Some(at("21:42:30")));
+        assert_eq!(sessions[1].3, None);
+        assert_eq!(playtime(&conn, FULANO), 150);
+    }
+}
@@ -0,0 +1,416 @@
+use std::{process::Command, thread, time::Duration};
+
+use serde::Serialize;
+
+use crate::commands::instances::get_instance;
+use crate::commands::pidfile::managed_pids;
+
+const RELEASE_TIMEOUT: Duration = Duration::from_secs(5);
+const RELEASE_POLL: Duration = Duration::from_millis(250);
+
+#[derive(Serialize, Debug, Clone)]
+pub struct PortOwner {
+    // None when the socket belongs to a process we are not allowed to inspect.
+    pub pid: Option<u32>,
+    pub name: Option<String>,
+    pub command: Option<String>,
+    pub managed: bool,
+}
+
+impl PortOwner {
+    fn label(&self) -> String {
+        match (self.pid, &self.name) {
+            (Some(pid), Some(name)) => format!("{} (PID {})", name, pid),
+            (Some(pid), None) => format!("PID {}", pid),
+            _ => "processo desconhecido".into(),
+        }
+    }
+}
+
+// Where start times aren't available a PID file can outlive its process and
+// the PID be reused, so a tracked PID also has to look like HLDS.
+fn is_hlds(command: Option<&str>) -> bool {
+    command.is_some_and(|c| c.to_lowercase().contains("hlds"))
+}
+
+fn parse_port(port: &str) -> Result<u16, String> {
+    port.trim()
+        .parse()
+        .map_err(|_| format!("Porta inválida: '{}'.", port))
+}
+
+// Only processes launched by the manager (tracked by PID file) are stopped
+// without asking; anything else must be in `confirmed`, the PIDs the user was
+// shown and agreed to kill. A process that grabbed the port afterwards is not.
+pub fn release_port(port: &str, confirmed: &[u32]) -> Result<(), String> {
+    let port = parse_port(port)?;
+    let owners = find_port_owners(port);
+    if owners.is_empty() {
+        return Ok(());
+    }
+
+    if let Some(owner) = owners.iter().find(|o| o.pid.is_none()) {
+        return Err(format!(
+            "A porta {} está em uso por um {} e não pode ser liberada.",
+            port,
+            owner.label()
+        ));
+    }
+
+    let unconfirmed: Vec<String> = owners
+        .iter()
+        .filter(|o| !o.managed && !o.pid.is_some_and(|pid| confirmed.contains(&pid)))
+        .map(PortOwner::label)
+        .collect();
+    if !unconfirmed.is_empty() {
+        return Err(format!(
+            "A porta {} está em uso por {}. Confirme para encerrar.",
+            port,
+            unconfirmed.join(", ")
+        ));
+    }
+
+    for owner in &owners {
+        if let Some(pid) = owner.pid {
+            println!("🔪 Liberando porta {}: encerrando {}", port, owner.label());
+            terminate_pid(pid, owner.managed);
+        }
+    }
+
+    let started = std::time::Instant::now();
+    while started.elapsed() < RELEASE_TIMEOUT {
+        if find_port_owners(port).is_empty() {
+            return Ok(());
+        }
+        thread::sleep(RELEASE_POLL);
+    }
+
+    Err(format!("A porta {} continua ocupada.", port))
+}
+
+#[cfg(target_os = "linux")]
+pub fn find_port_owners(port: u16) -> Vec<PortOwner> {
+    use std::collections::{BTreeMap, HashSet};
+    use std::fs;
+
+    let inodes: HashSet<String> = ["/proc/net/udp", "/proc/net/udp6"]
+        .iter()
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .flat_map(|table| socket_inodes(&table, port))
+        .collect();
+    if inodes.is_empty() {
+        return vec![];
+    }
+
+    let managed = managed_pids();
+    let mut found = HashSet::new();
+    // A socket can be shared, e.g. by hlds_run and hlds_linux or after a fork,
+    // so every process holding one is reported, each once.
+    let mut owners = BTreeMap::new();
+
+    for entry in fs::read_dir("/proc").into_iter().flatten().flatten() {
+        let Some(pid) = entry
+            .file_name()
+            .to_str()
+            .and_then(|n| n.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        let Ok(fds) = fs::read_dir(entry.path().join("fd")) else {
+            continue;
+        };
+
+        for fd in fds.flatten() {
+            let Ok(target) = fs::read_link(fd.path()) else {
+                continue;
+            };
+            let target = target.to_string_lossy();
+            let Some(inode) = target
+                .strip_prefix("socket:[")
+                .and_then(|t| t.strip_suffix(']'))
+            else {
+                continue;
+            };
+
+            if inodes.contains(inode) {
+                found.insert(inode.to_string());
+                owners
+                    .entry(pid)
+                    .or_insert_with(|| linux_owner(pid, &managed));
+            }
+        }
+    }
+
+    let mut owners: Vec<PortOwner> = owners.into_values().collect();
+    // Sockets we could not map to a process belong to another user.
+    if found.len() < inodes.len() {
+        owners.push(PortOwner {
+            pid: None,
+            name: None,
+            command: None,
+            managed: false,
+        });
+    }
+    owners
+}
+
+// /proc/net/udp: "sl local_address rem_address st ... uid timeout inode ..."
+#[cfg(target_os = "linux")]
+fn socket_inodes(table: &str, port: u16) -> Vec<String> {
+    table
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let local_port = fields.get(1)?.rsplit(':').next()?;
+            let inode = fields.get(9)?;
+            (u16::from_str_radix(local_port, 16).ok()? == port && *inode != "0")
+                .then(|| inode.to_string())
+        })
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+fn linux_owner(pid: u32, managed: &[u32]) -> PortOwner {
+    use std::fs;
+
+    let name = fs::read_to_string(format!("/proc/{}/comm", pid))
+        .ok()
+        .map(|n| n.trim().to_string());
+    let command = fs::read(format!("/proc/{}/cmdline", pid)).ok().map(|c| {
+        String::from_utf8_lossy(&c)
+            .split('\0')
+            .filter(|a| !a.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    });
+
+    // HLDS is spawned in its own process group, so hlds_linux shares the
+    // group id of the hlds_run PID we recorded. The name check guards against
+    // a stale PID file whose PID was reused.
+    let pgid = fs::read_to_string(format!("/proc/{}/stat", pid))
+        .ok()
+        .and_then(|stat| {
+            let (_, rest) = stat.rsplit_once(')')?;
+            rest.split_whitespace().nth(2)?.parse::<u32>().ok()
+        });
+    let tracked = managed.contains(&pid) || pgid.is_some_and(|g| managed.contains(&g));
+
+    PortOwner {
+        pid: Some(pid),
+        managed: tracked && is_hlds(command.as_deref()),
+        name,
+        command,
+    }
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+pub fn find_port_owners(port: u16) -> Vec<PortOwner> {
+    let managed = managed_pids();
+    let output = Command::new("lsof")
+        .args(["-n", "-P", "-t", &format!("-iUDP:{}", port)])
+        .output();
+
+    let Ok(output) = output else {
+        return vec![];
+    };
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|pid| pid.trim().parse::<u32>().ok())
+        .map(|pid| {
+            let ps = |field: &str| {
+                Command::new("ps")
+                    .args(["-o", field, "-p", &pid.to_string()])
+                    .output()
+                    .ok()
+                    .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+                    .filter(|out| !out.is_empty())
+            };
+            // hlds_run is a script, so its name is the shell; the arguments aren't.
+            let command = ps("command=");
+            PortOwner {
+                pid: Some(pid),
+                managed: managed.contains(&pid) && is_hlds(command.as_deref()),
+                name: ps("comm="),
+                command,
+            }
+        })
+        .collect()
+}
+
+#[cfg(windows)]
+pub fn find_port_owners(port: u16) -> Vec<PortOwner> {
+    let managed = managed_pids();
+    let Ok(output) = Command::new("netstat").args(["-ano", "-p", "UDP"]).output() else {
+        return vec![];
+    };
+
+    netstat_pids(&String::from_utf8_lossy(&output.stdout), port)
+        .into_iter()
+        .map(|pid| {
+            let name = Command::new("tasklist")
+                .args(["/FI", &format!("PID eq {}", pid), "/FO", "CSV", "/NH"])
+                .output()
+                .ok()
+                .and_then(|o| {
+                    let out = String::from_utf8_lossy(&o.stdout).to_string();
+                    out.split(',')
+                        .next()
+                        .map(|n| n.trim_matches('"').to_string())
+                })
+                .filter(|n| !n.is_empty());
+            PortOwner {
+                pid: Some(pid),
+                managed: managed.contains(&pid) && is_hlds(name.as_deref()),
+                command: name.clone(),
+                name,
+            }
+        })
+        .collect()
+}
+
+// "  UDP    0.0.0.0:27015    *:*    1234"
+#[cfg(any(windows, test))]
+fn netstat_pids(output: &str, port: u16) -> Vec<u32> {
+    let mut pids: Vec<u32> = output
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.first() != Some(&"UDP") {
+                return None;
+            }
+            let local_port = fields.get(1)?.rsplit_once(':')?.1;
+            (local_port.parse::<u16>().ok()? == port).then_some(())?;
+            fields.last()?.parse().ok()
+        })
+        .collect();
+    pids.sort_unstable();
+    pids.dedup();
+    pids
+}
+
+fn terminate_pid(pid: u32, managed: bool) {
+    #[cfg(unix)]
+    {
+        // Our own servers are group leaders; signal the whole group so
+        // hlds_run does not respawn hlds_linux.
+        let target = if managed {
+            managed_group(pid)
+        } else {
+            pid.to_string()
+        };
+
+        let _ = Command::new("kill").args(["-TERM", "--", &target]).output();
+        if !wait_exit(pid) {
+            let _ = Command::new("kill").args(["-KILL", "--", &target]).output();
+        }
+    }
+
+    #[cfg(windows)]
+    {
+        let _ = managed;
+        let _ = Command::new("taskkill")
+            .args(["/F", "/T", "/PID", &pid.to_string()])
+            .output();
+    }
+}
+
+#[cfg(unix)]
+fn managed_group(pid: u32) -> String {
+    let managed = managed_pids();
+    let group = Command::new("ps")
+        .args(["-o", "pgid=", "-p", &pid.to_string()])
+        .output()
+        .ok()
+        .and_then(|o| {
+            String::from_utf8_lossy(&o.stdout)
+                .trim()
+                .parse::<u32>()
+                .ok()
+        })
+        .filter(|g| managed.contains(g));
+
+    match group {
+        Some(group) => format!("-{}", group),
+        None => pid.to_string(),
+    }
+}
+
+#[cfg(unix)]
+fn wait_exit(pid: u32) -> bool {
+    let started = std::time::Instant::now();
+    while started.elapsed() < RELEASE_TIMEOUT {
+        let alive = Command::new("kill")
+            .args(["-0", &pid.to_string()])
+            .output()
+            .is_ok_and(|o| o.status.success());
+        if !alive {
+            return true;
+        }
+        thread::sleep(RELEASE_POLL);
+    }
+    false
+}
+
+#[tauri::command]
+pub fn get_port_owners(instance_id: Option<String>) -> Result<Vec<PortOwner>, String> {
+    let port = parse_port(&get_instance(instance_id.as_deref())?.server_config.port)?;
+    Ok(find_port_owners(port))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Captured from a server running HLDS on 27015 (0x6987) next to other UDP services.
+    #[cfg(target_os = "linux")]
+    const PROC_NET_UDP: &str = "   sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode ref pointer drops
+  134: 00000000:6987 00000000:0000 07 00000000:00000000 00:00000000 00000000  1000        0 482113 2 0000000000000000 0
+  213: 00000000:697D 00000000:0000 07 00000000:00000000 00:00000000 00000000  1000        0 482115 2 0000000000000000 0
+  835: 3500007F:0035 00000000:0000 07 00000000:00000000 00:00000000 00000000   101        0 17731 2 0000000000000000 0
+ 1002: 0A00A8C0:D61C 08080808:6987 01 00000000:00000000 00:00000000 00000000  1000        0 499120 2 0000000000000000 0
+ 1121: 0100007F:6987 00000000:0000 07 00000000:00000000 00:00000000 00000000     0        0 0 2 0000000000000000 0
+";
+
+    #[cfg(target_os = "linux")]
+    const PROC_NET_UDP6: &str = "  sl  local_address                         remote_address                        st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode ref pointer drops
+  134: 00000000000000000000000000000000:6987 00000000000000000000000000000000:0000 07 00000000:00000000 00:00000000 00000000  1000        0 482114 2 0000000000000000 0
+ 1353: 00000000000000000000000000000000:14E9 00000000000000000000000000000000:0000 07 00000000:00000000 00:00000000 00000000   108        0 19220 2 0000000000000000 0
+";
+
+    const NETSTAT: &str = "
+Active Connections
+
+  Proto  Local Address          Foreign Address        State           PID
+  UDP    0.0.0.0:500            *:*                                    4624
+  UDP    0.0.0.0:2701           *:*                                    7744
+  UDP    0.0.0.0:27005          *:*                                    5120
+  UDP    0.0.0.0:27015          *:*                                    5120
+  UDP    127.0.0.1:27015        *:*                                    6388
+  UDP    192.168.0.10:51234     192.168.0.20:27015                     8012
+  UDP    [::]:27015             *:*                                    5120
+  UDP    [fe80::1c2a:9d3b:4f6e:12a%12]:1900  *:*                       3316
+  TCP    0.0.0.0:27015          0.0.0.0:0              LISTENING       9100
+";
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn proc_net_udp_matches_the_local_port_only() {
+        assert_eq!(socket_inodes(PROC_NET_UDP, 27015), ["482113"]);
+        assert_eq!(socket_inodes(PROC_NET_UDP, 27005), ["482115"]);
+        assert_eq!(socket_inodes(PROC_NET_UDP6, 27015), ["482114"]);
+        assert!(socket_inodes(PROC_NET_UDP, 27016).is_empty());
+        assert!(socket_inodes(PROC_NET_UDP6, 27005).is_empty());
+        assert!(socket_inodes("", 27015).is_empty());
+    }
+
+    #[test]
+    fn netstat_matches_udp_on_the_local_port_only() {
+        assert_eq!(netstat_pids(NETSTAT, 27015), [5120, 6388]);
+        assert_eq!(netstat_pids(NETSTAT, 27005), [5120]);
+        assert_eq!(netstat_pids(NETSTAT, 1900), [3316]);
+        assert!(netstat_pids(NETSTAT, 270).is_empty());
+        assert_eq!(netstat_pids(NETSTAT, 51234), [8012]);
+    }
+}
@@ -0,0 +1,600 @@
+use std::{collections::BTreeMap, net::UdpSocket, time::Duration};
+
+use serde::Serialize;
+
+use crate::commands::instances::get_instance;
+use crate::commands::rcon::{connect_udp, PACKET_HEADER, SPLIT_HEADER};
+
+const QUERY_TIMEOUT: Duration = Duration::from_secs(2);
+
+const A2S_INFO: u8 = 0x54;
+const A2S_PLAYER: u8 = 0x55;
+const A2S_RULES: u8 = 0x56;
+
+const S2C_CHALLENGE: u8 = 0x41;
+const S2A_INFO_SOURCE: u8 = 0x49;
+const S2A_INFO_GOLDSRC: u8 = 0x6D;
+const S2A_PLAYER: u8 = 0x44;
+const S2A_RULES: u8 = 0x45;
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct ServerInfo {
+    pub name: String,
+    pub map: String,
+    pub folder: String,
+    pub game: String,
+    pub players: u8,
+    pub max_players: u8,
+    pub bots: u8,
+    pub dedicated: bool,
+    pub os: String,
+    pub password: bool,
+    pub vac: bool,
+    pub version: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct PlayerInfo {
+    pub name: String,
+    pub score: i32,
+    pub duration: f32,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ServerStatus {
+    pub name: String,
+    pub map: String,
+    pub game: String,
+    pub player_count: u8,
+    pub max_players: u8,
+    pub bots: u8,
+    pub password: bool,
+    pub vac: bool,
+    pub players: Vec<PlayerInfo>,
+    pub rules: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Copy)]
+enum SplitFormat {
+    GoldSrc,
+    Source,
+}
+
+pub struct QueryClient {
+    socket: UdpSocket,
+}
+
+impl QueryClient {
+    pub fn connect(address: &str) -> Result<Self, String> {
+        Ok(Self {
+            socket: connect_udp(address, QUERY_TIMEOUT)?,
+        })
+    }
+
+    pub fn info(&self) -> Result<ServerInfo, String> {
+        let mut request = vec![A2S_INFO];
+        request.extend_from_slice(b"Source Engine Query\0");
+
+        let mut response = self.request(&request)?;
+        if response.first() == Some(&S2C_CHALLENGE) {
+            request.extend_from_slice(challenge_from(&response)?);
+            response = self.request(&request)?;
+        }
+
+        match response.first() {
+            Some(&S2A_INFO_SOURCE) => parse_source_info(&response[1..]),
+            Some(&S2A_INFO_GOLDSRC) => parse_goldsrc_info(&response[1..]),
+            _ => Err("Resposta A2S_INFO inesperada.".into()),
+        }
+    }
+
+    pub fn players(&self) -> Result<Vec<PlayerInfo>, String> {
+        let response = self.challenged_request(A2S_PLAYER)?;
+        if response.first() != Some(&S2A_PLAYER) {
+            return Err("Resposta A2S_PLAYER inesperada.".into());
+        }
+
+        let mut reader = PacketReader::new(&response[1..]);
+        let count = reader.u8()?;
+        let mut players = Vec::with_capacity(count as usize);
+
+        for _ in 0..count {
+            if reader.is_empty() {
+                break;
+            }
+            let _index = reader.u8()?;
+            players.push(PlayerInfo {
+                name: reader.string()?,
+                score: reader.i32()?,
+                duration: reader.f32()?,
+            });
+        }
+
+        Ok(players)
+    }
+
+    pub fn rules(&self) -> Result<BTreeMap<String, String>, String> {
+        let response = self.challenged_request(A2S_RULES)?;
+        if response.first() != Some(&S2A_RULES) {
+            return Err("Resposta A2S_RULES inesperada.".into());
+        }
+
+        let mut reader = PacketReader::new(&response[1..]);
+        let count = reader.i16()?;
+        let mut rules = BTreeMap::new();
+
+        for _ in 0..count {
+            if reader.is_empty() {
+                break;
+            }
+            let key = reader.string()?;
+            let value = reader.string()?;
+            rules.insert(key, value);
+        }
+
+        Ok(rules)
+    }
+
+    fn challenged_request(&self, kind: u8) -> Result<Vec<u8>, String> {
+        let mut request = vec![kind];
+        request.extend_from_slice(&[0xFF; 4]);
+
+        let response = self.request(&request)?;
+        if response.first() != Some(&S2C_CHALLENGE) {
+            return Ok(response);
+        }
+
+        let mut request = vec![kind];
+        request.extend_from_slice(challenge_from(&response)?);
+        self.request(&request)
+    }
+
+    fn request(&self, body: &[u8]) -> Result<Vec<u8>, String> {
+        let mut packet = PACKET_HEADER.to_vec();
+        packet.extend_from_slice(body);
+        self.socket
+            .send(&packet)
+            .map_err(|e| format!("Erro ao enviar consulta: {}", e))?;
+
+        self.recv_payload()
+    }
+
+    fn recv_payload(&self) -> Result<Vec<u8>, String> {
+        let mut split_packets: Vec<Vec<u8>> = vec![];
+        let mut format: Option<SplitFormat> = None;
+        let mut buf = [0u8; 4096];
+
+        loop {
+            let len = self
+                .socket
+                .recv(&mut buf)
+                .map_err(|e| format!("Servidor não respondeu à consulta: {}", e))?;
+            let packet = &buf[..len];
+
+            if packet.starts_with(&PACKET_HEADER) {
+                return Ok(packet[4..].to_vec());
+            }
+
+            if !packet.starts_with(&SPLIT_HEADER) {
+                continue;
+            }
+
+            format = format.or_else(|| detect_split_format(packet));
+            split_packets.push(packet.to_vec());
+
+            if let Some(format) = format {
+                if let Some(payload) = reassemble(&split_packets, format)? {
+                    return Ok(payload);
+                }
+            }
+        }
+    }
+}
+
+// Only the first fragment carries an inner packet header, which tells the two layouts apart.
+fn detect_split_format(packet: &[u8]) -> Option<SplitFormat> {
+    if packet.len() > 13 && packet[8] >> 4 == 0 && packet[9..13] == PACKET_HEADER {
+        Some(SplitFormat::GoldSrc)
+    } else if packet.len() > 16 && packet[9] == 0 && packet[12..16] == PACKET_HEADER {
+        Some(SplitFormat::Source)
+    } else {
+        None
+    }
+}
+
+fn reassemble(packets: &[Vec<u8>], format: SplitFormat) -> Result<Option<Vec<u8>>, String> {
+    let mut fragments: BTreeMap<u8, &[u8]> = BTreeMap::new();
+    let mut total = 0u8;
+
+    for packet in packets {
+        let (index, count, body) = match format {
+            SplitFormat::GoldSrc => {
+                if packet.len() < 9 {
+                    continue;
+                }
+                (packet[8] >> 4, packet[8] & 0x0F, &packet[9..])
+            }
+            SplitFormat::Source => {
+                if packet.len() < 12 {
+                    continue;
+                }
+                if packet[7] & 0x80 != 0 {
+                    return Err("Pacotes comprimidos não são suportados.".into());
+                }
+                (packet[9], packet[8], &packet[12..])
+            }
+        };
+        total = count;
+        fragments.insert(index, body);
+    }
+
+    if total == 0 || fragments.len() < total as usize {
+        return Ok(None);
+    }
+
+    let joined: Vec<u8> = fragments.into_values().flatten().copied().collect();
+    Ok(Some(
+        joined
+            .strip_prefix(&PACKET_HEADER)
+            .unwrap_or(&joined)
+            .to_vec(),
+    ))
+}
+
+fn challenge_from(response: &[u8]) -> Result<&[u8], String> {
+    response
+        .get(1..5)
+        .ok_or_else(|| "Challenge de consulta inválido.".to_string())
+}
+
+fn parse_source_info(data: &[u8]) -> Result<ServerInfo, String> {
+    let mut reader = PacketReader::new(data);
+    let _protocol = reader.u8()?;
+    let name = reader.string()?;
+    let map = reader.string()?;
+    let folder = reader.string()?;
+    let game = reader.string()?;
+    let _app_id = reader.i16()?;
+    let players = reader.u8()?;
+    let max_players = reader.u8()?;
+    let bots = reader.u8()?;
+    let server_type = reader.u8()?;
+    let environment = reader.u8()?;
+    let password = reader.u8()? == 1;
+    let vac = reader.u8()? == 1;
+    let version = reader.string().ok();
+
+    Ok(ServerInfo {
+        name,
+        map,
+        folder,
+        game,
+        players,
+        max_players,
+        bots,
+        dedicated: server_type == b'd',
+        os: environment_name(environment),
+        password,
+        vac,
+        version,
+    })
+}
+
+fn parse_goldsrc_info(data: &[u8]) -> Result<ServerInfo, String> {
+    let mut reader = PacketReader::new(data);
+    let _address = reader.string()?;
+    let name = reader.string()?;
+    let map = reader.string()?;
+    let folder = reader.string()?;
+    let game = reader.string()?;
+    let players = reader.u8()?;
+    let max_players = reader.u8()?;
+    let _protocol = reader.u8()?;
+    let server_type = reader.u8()?;
+    let environment = reader.u8()?;
+    let password = reader.u8()? == 1;
+
+    if reader.u8()? == 1 {
+        let _link = reader.string()?;
+        let _download_link = reader.string()?;
+        let _null = reader.u8()?;
+        let _version = reader.i32()?;
+        let _size = reader.i32()?;
+        let _type = reader.u8()?;
+        let _dll = reader.u8()?;
+    }
+
+    let vac = reader.u8()? == 1;
+    let bots = reader.u8()?;
+
+    Ok(ServerInfo {
+        name,
+        map,
+        folder,
+        game,
+        players,
+        max_players,
+        bots,
+        dedicated: server_type.eq_ignore_ascii_case(&b'd'),
+        os: environment_name(environment),
+        password,
+        vac,
+        version: None,
+    })
+}
+
+fn environment_name(environment: u8) -> String {
+    match environment.to_ascii_lowercase() {
+        b'l' => "linux",
+        b'w' => "windows",
+        b'm' | b'o' => "mac",
+        _ => "unknown",
+    }
+    .into()
+}
+
+struct PacketReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> PacketReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let end = self.pos + len;
+        let bytes = self
+            .data
+            .get(self.pos..end)
+            .ok_or_else(|| "Resposta de consulta truncada.".to_string())?;
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn i16(&mut self) -> Result<i16, String> {
+        let bytes = self.take(2)?;
+        Ok(i16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn i32(&mut self) -> Result<i32, String> {
+        let bytes = self.take(4)?;
+        Ok(i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn f32(&mut self) -> Result<f32, String> {
+        let bytes = self.take(4)?;
+        Ok(f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        let rest = &self.data[self.pos.min(self.data.len())..];
+        let len = rest
+            .iter()
+            .position(|&b| b == 0)
+            .ok_or_else(|| "Resposta de consulta truncada.".to_string())?;
+        let value = String::from_utf8_lossy(&rest[..len]).into_owned();
+        self.pos += len + 1;
+        Ok(value)
+    }
+}
+
+pub fn query_server_status(address: &str) -> Result<ServerStatus, String> {
+    let client = QueryClient::connect(address)?;
+    let info = client.info()?;
+    let players = client.players()?;
+    let rules = client.rules().unwrap_or_default();
+
+    Ok(ServerStatus {
+        name: info.name,
+        map: info.map,
+        game: info.game,
+        player_count: info.players,
+        max_players: info.max_players,
+        bots: info.bots,
+        password: info.password,
+        vac: info.vac,
+        players,
+        rules,
+    })
+}
+
+#[tauri::command]
+pub async fn get_server_status(instance_id: Option<String>) -> Result<ServerStatus, String> {
+    let config = get_instance(instance_id.as_deref())?.server_config;
+
+    tauri::async_runtime::spawn_blocking(move || query_server_status(&config.address()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    const CHALLENGE: [u8; 4] = [0x0A, 0x0B, 0x0C, 0x0D];
+
+    #[derive(Clone, Copy)]
+    enum Layout {
+        Single,
+        GoldSrc,
+        Source,
+    }
+
+    fn source_info() -> Vec<u8> {
+        let mut body = vec![S2A_INFO_SOURCE, 48];
+        body.extend_from_slice(b"Canalhas\0de_dust2\0cstrike\0Counter-Strike\0");
+        body.extend_from_slice(&10i16.to_le_bytes());
+        body.extend_from_slice(&[5, 32, 1, b'd', b'l', 0, 1]);
+        body.extend_from_slice(b"1.1.2.7\0");
+        body
+    }
+
+    fn goldsrc_info() -> Vec<u8> {
+        let mut body = vec![S2A_INFO_GOLDSRC];
+        body.extend_from_slice(b"127.0.0.1:27015\0Canalhas\0de_inferno\0cstrike\0Counter-Strike\0");
+        body.extend_from_slice(&[5, 32, 47, b'D', b'W', 1, 0, 0, 2]);
+        body
+    }
+
+    fn players() -> Vec<u8> {
+        let mut body = vec![S2A_PLAYER, 2];
+        for (index, name, score, duration) in
+            [(0u8, "Fulano", 12i32, 61.5f32), (1, "Ciclano", -1, 5.25)]
+        {
+            body.push(index);
+            body.extend_from_slice(name.as_bytes());
+            body.push(0);
+            body.extend_from_slice(&score.to_le_bytes());
+            body.extend_from_slice(&duration.to_le_bytes());
+        }
+        body
+    }
+
+    fn rules() -> Vec<u8> {
+        let mut body = vec![S2A_RULES];
+        body.extend_from_slice(&3i16.to_le_bytes());
+        for (key, value) in [
+            ("mp_timelimit", "30"),
+            ("mp_friendlyfire", "0"),
+            ("sv_gravity", "800"),
+        ] {
+            body.extend_from_slice(format!("{}\0{}\0", key, value).as_bytes());
+        }
+        body
+    }
+
+    // Splits a response into three fragments and returns them last-to-first.
+    fn fragments(body: &[u8], layout: Layout) -> Vec<Vec<u8>> {
+        let mut payload = PACKET_HEADER.to_vec();
+        payload.extend_from_slice(body);
+        if let Layout::Single = layout {
+            return vec![payload];
+        }
+
+        let chunks: Vec<&[u8]> = payload.chunks(payload.len() / 3 + 1).collect();
+        let total = chunks.len() as u8;
+        let mut packets: Vec<Vec<u8>> = chunks
+            .iter()
+            .enumerate()
+            .map(|(index, chunk)| {
+                let mut packet = SPLIT_HEADER.to_vec();
+                packet.extend_from_slice(&77i32.to_le_bytes());
+                match layout {
+                    Layout::GoldSrc => packet.push((index as u8) << 4 | total),
+                    _ => {
+                        packet.extend_from_slice(&[total, index as u8]);
+                        packet.extend_from_slice(&1248u16.to_le_bytes());
+                    }
+                }
+                packet.extend_from_slice(chunk);
+                packet
+            })
+            .collect();
+        packets.reverse();
+        packets
+    }
+
+    // In-process A2S server that demands a challenge before every answer.
+    fn spawn_server(layout: Layout) -> String {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        socket
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        let address = socket.local_addr().unwrap().to_string();
+
+        thread::spawn(move || {
+            let mut buf = [0u8; 1400];
+            while let Ok((len, peer)) = socket.recv_from(&mut buf) {
+                let request = &buf[4..len];
+                let challenged = request.ends_with(&CHALLENGE);
+
+                let packets = match (request[0], challenged) {
+                    (_, false) => {
+                        let mut packet = PACKET_HEADER.to_vec();
+                        packet.push(S2C_CHALLENGE);
+                        packet.extend_from_slice(&CHALLENGE);
+                        vec![packet]
+                    }
+                    (A2S_INFO, true) => match layout {
+                        Layout::GoldSrc => fragments(&goldsrc_info(), layout),
+                        _ => fragments(&source_info(), layout),
+                    },
+                    (A2S_PLAYER, true) => fragments(&players(), layout),
+                    (A2S_RULES, true) => fragments(&rules(), layout),
+                    _ => continue,
+                };
+
+                for packet in packets {
+                    socket.send_to(&packet, peer).unwrap();
+                }
+            }
+        });
+
+        address
+    }
+
+    fn assert_players_and_rules(status: &ServerStatus) {
+        assert_eq!(status.players.len(), 2);
+        assert_eq!(status.players[0].name, "Fulano");
+        assert_eq!(status.players[0].score, 12);
+        assert_eq!(status.players[0].duration, 61.5);
+        assert_eq!(status.players[1].name, "Ciclano");
+        assert_eq!(status.players[1].score, -1);
+        assert_eq!(status.players[1].duration, 5.25);
+
+        assert_eq!(status.rules.len(), 3);
+        assert_eq!(status.rules["mp_timelimit"], "30");
+        assert_eq!(status.rules["mp_friendlyfire"], "0");
+        assert_eq!(status.rules["sv_gravity"], "800");
+    }
+
+    #[test]
+    fn queries_with_challenge() {
+        let status = query_server_status(&spawn_server(Layout::Single)).unwrap();
+
+        assert_eq!(status.name, "Canalhas");
+        assert_eq!(status.map, "de_dust2");
+        assert_eq!(status.game, "Counter-Strike");
+        assert_eq!(status.player_count, 5);
+        assert_eq!(status.max_players, 32);
+        assert_eq!(status.bots, 1);
+        assert!(!status.password);
+        assert!(status.vac);
+        assert_players_and_rules(&status);
+    }
+
+    #[test]
+    fn reassembles_goldsrc_split_packets() {
+        let address = spawn_server(Layout::GoldSrc);
+        let info = QueryClient::connect(&address).unwrap().info().unwrap();
+        assert_eq!(info.map, "de_inferno");
+        assert_eq!(info.os, "windows");
+        assert!(info.dedicated);
+        assert!(info.password);
+        assert_eq!(info.bots, 2);
+
+        assert_players_and_rules(&query_server_status(&address).unwrap());
+    }
+
+    #[test]
+    fn reassembles_source_split_packets() {
+        let address = spawn_server(Layout::Source);
+        let info = QueryClient::connect(&address).unwrap().info().unwrap();
+        assert_eq!(info.map, "de_dust2");
+        assert_eq!(info.os, "linux");
+        assert_eq!(info.version.as_deref(), Some("1.1.2.7"));
+
+        assert_players_and_rules(&query_server_status(&address).unwrap());
+    }
+}
@@ -0,0 +1,308 @@
+use std::{
+    collections::BTreeMap,
+    io::ErrorKind,
+    net::{ToSocketAddrs, UdpSocket},
+    time::Duration,
+};
+
+use crate::commands::hlds::ServerConfig;
+use crate::commands::instances::get_instance;
+
+pub const PACKET_HEADER: [u8; 4] = [0xFF, 0xFF, 0xFF, 0xFF];
+pub const SPLIT_HEADER: [u8; 4] = [0xFE, 0xFF, 0xFF, 0xFF];
+
+const RCON_TIMEOUT: Duration = Duration::from_secs(3);
+const RCON_TRAILING_TIMEOUT: Duration = Duration::from_millis(250);
+
+pub struct RconClient {
+    socket: UdpSocket,
+    password: String,
+    challenge: Option<String>,
+}
+
+impl RconClient {
+    pub fn connect(address: &str, password: &str) -> Result<Self, String> {
+        Ok(Self {
+            socket: connect_udp(address, RCON_TIMEOUT)?,
+            password: password.to_string(),
+            challenge: None,
+        })
+    }
+
+    pub fn execute(&mut self, command: &str) -> Result<String, String> {
+        if self.password.is_empty() {
+            return Err("Senha RCON não configurada.".into());
+        }
+
+        let response = self.send_rcon(command)?;
+        if response.starts_with("Bad challenge") {
+            self.challenge = None;
+            return self.send_rcon(command).and_then(check_rcon_response);
+        }
+
+        check_rcon_response(response)
+    }
+
+    fn send_rcon(&mut self, command: &str) -> Result<String, String> {
+        let challenge = match &self.challenge {
+            Some(challenge) => challenge.clone(),
+            None => {
+                let challenge = self.request_challenge()?;
+                self.challenge = Some(challenge.clone());
+                challenge
+            }
+        };
+
+        let mut packet = PACKET_HEADER.to_vec();
+        packet.extend_from_slice(
+            format!(
+                "rcon {} \"{}\" {}\n",
+                challenge,
+                self.password,
+                command.trim()
+            )
+            .as_bytes(),
+        );
+        self.socket
+            .send(&packet)
+            .map_err(|e| format!("Erro ao enviar comando RCON: {}", e))?;
+
+        self.read_response()
+    }
+
+    fn request_challenge(&self) -> Result<String, String> {
+        let mut packet = PACKET_HEADER.to_vec();
+        packet.extend_from_slice(b"challenge rcon\n");
+        self.socket
+            .send(&packet)
+            .map_err(|e| format!("Erro ao solicitar challenge RCON: {}", e))?;
+
+        let payload = self
+            .recv_payload()
+            .map_err(|e| format!("Servidor não respondeu ao challenge RCON: {}", e))?;
+        let text = String::from_utf8_lossy(&payload);
+
+        text.trim_end_matches('\0')
+            .trim()
+            .strip_prefix("challenge rcon ")
+            .map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty() && c.chars().all(|ch| ch.is_ascii_digit()))
+            .ok_or_else(|| format!("Resposta de challenge RCON inválida: {}", text.trim()))
+    }
+
+    fn read_response(&self) -> Result<String, String> {
+        let mut output = String::new();
+
+        match self.recv_payload() {
+            Ok(payload) => output.push_str(&decode_print(&payload)),
+            Err(e) if is_timeout(&e) => return Ok(output),
+            Err(e) => return Err(format!("Erro ao ler resposta RCON: {}", e)),
+        }
+
+        // Long outputs arrive as several print packets, so keep reading briefly.
+        self.socket
+            .set_read_timeout(Some(RCON_TRAILING_TIMEOUT))
+            .map_err(|e| e.to_string())?;
+
+        let result = loop {
+            match self.recv_payload() {
+                Ok(payload) => output.push_str(&decode_print(&payload)),
+                Err(e) if is_timeout(&e) => break Ok(output),
+                Err(e) => break Err(format!("Erro ao ler resposta RCON: {}", e)),
+            }
+        };
+
+        self.socket
+            .set_read_timeout(Some(RCON_TIMEOUT))
+            .map_err(|e| e.to_string())?;
+
+        result
+    }
+
+    fn recv_payload(&self) -> Result<Vec<u8>, std::io::Error> {
+        let mut fragments: BTreeMap<u8, Vec<u8>> = BTreeMap::new();
+        let mut buf = [0u8; 4096];
+
+        loop {
+            let len = self.socket.recv(&mut buf)?;
+            let packet = &buf[..len];
+
+            if packet.starts_with(&PACKET_HEADER) {
+                return Ok(packet[4..].to_vec());
+            }
+
+            // GoldSrc split packet: header, request id, packed byte (index << 4 | total).
+            if packet.starts_with(&SPLIT_HEADER) && packet.len() > 9 {
+                let total = packet[8] & 0x0F;
+                let index = packet[8] >> 4;
+                fragments.insert(index, packet[9..].to_vec());
+
+                if total > 0 && fragments.len() == total as usize {
+                    let joined: Vec<u8> = fragments.into_values().flatten().collect();
+                    return Ok(joined
+                        .strip_prefix(&PACKET_HEADER)
+                        .unwrap_or(&joined)
+                        .to_vec());
+                }
+            }
+        }
+    }
+}
+
+pub fn connect_udp(address: &str, timeout: Duration) -> Result<UdpSocket, String> {
+    let addr = address
+        .to_socket_addrs()
+        .map_err(|e| format!("Endereço inválido '{}': {}", address, e))?
+        .next()
+        .ok_or_else(|| format!("Endereço inválido '{}'", address))?;
+
+    let bind = if addr.is_ipv4() {
+        "0.0.0.0:0"
+    } else {
+        "[::]:0"
+    };
+    let socket = UdpSocket::bind(bind).map_err(|e| e.to_string())?;
+    socket.connect(addr).map_err(|e| e.to_string())?;
+    socket
+        .set_read_timeout(Some(timeout))
+        .map_err(|e| e.to_string())?;
+
+    Ok(socket)
+}
+
+fn decode_print(payload: &[u8]) -> String {
+    let body = payload.strip_prefix(b"l").unwrap_or(payload);
+    String::from_utf8_lossy(body)
+        .trim_end_matches('\0')
+        .to_string()
+}
+
+fn check_rcon_response(response: String) -> Result<String, String> {
+    if response.starts_with("Bad rcon_password") {
+        Err("Senha RCON incorreta.".into())
+    } else if response.starts_with("Bad challenge") {
+        Err("Challenge RCON recusado pelo servidor.".into())
+    } else if response.starts_with("You have been banned") {
+        Err("Endereço banido do RCON pelo servidor.".into())
+    } else {
+        Ok(response)
+    }
+}
+
+pub fn is_timeout(err: &std::io::Error) -> bool {
+    matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut)
+}
+
+pub fn rcon_execute(config: &ServerConfig, command: &str) -> Result<String, String> {
+    RconClient::connect(&config.address(), &config.rcon_password)?.execute(command)
+}
+
+#[tauri::command]
+pub async fn send_rcon_command(
+    command: String,
+    instance_id: Option<String>,
+) -> Result<String, String> {
+    let config = get_instance(instance_id.as_deref())?.server_config;
+
+    tauri::async_runtime::spawn_blocking(move || rcon_execute(&config, &command))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    const CHALLENGE: &str = "1234567890";
+
+    // Minimal HLDS stand-in: answers the challenge and echoes commands as print packets.
+    fn spawn_server(password: &'static str) -> String {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        socket
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        let address = socket.local_addr().unwrap().to_string();
+
+        thread::spawn(move || {
+            let mut buf = [0u8; 4096];
+            while let Ok((len, peer)) = socket.recv_from(&mut buf) {
+                let text = String::from_utf8_lossy(&buf[4..len]).to_string();
+                let replies: Vec<String> = if text == "challenge rcon\n" {
+                    vec![format!("challenge rcon {}\n", CHALLENGE)]
+                } else if let Some(rest) = text.strip_prefix(&format!("rcon {} ", CHALLENGE)) {
+                    let (pass, command) = rest[1..].split_once("\" ").unwrap();
+                    if pass != password {
+                        vec!["lBad rcon_password.\n".into()]
+                    } else if command.trim() == "status" {
+                        vec![
+                            "lhostname: Canalhas\n".into(),
+                            "lmap     : de_dust2\n".into(),
+                        ]
+                    } else {
+                        vec![format!("l{} ok\n", command.trim())]
+                    }
+                } else {
+                    vec!["lBad challenge.\n".into()]
+                };
+
+                for reply in replies {
+                    let mut packet = PACKET_HEADER.to_vec();
+                    packet.extend_from_slice(reply.as_bytes());
+                    socket.send_to(&packet, peer).unwrap();
+                }
+            }
+        });
+
+        address
+    }
+
+    #[test]
+    fn executes_command_with_challenge() {
+        let address = spawn_server("segredo");
+        let mut client = RconClient::connect(&address, "segredo").unwrap();
+
+        assert_eq!(
+            client.execute("changelevel de_inferno").unwrap(),
+            "changelevel de_inferno ok\n"
+        );
+        assert_eq!(client.challenge.as_deref(), Some(CHALLENGE));
+    }
+
+    #[test]
+    fn joins_multi_packet_output() {
+        let address = spawn_server("segredo");
+        let mut client = RconClient::connect(&address, "segredo").unwrap();
+
+        assert_eq!(
+            client.execute("status").unwrap(),
+            "hostname: Canalhas\nmap     : de_dust2\n"
+        );
+    }
+
+    #[test]
+    fn rejects_bad_password() {
+        let address = spawn_server("segredo");
+        let mut client = RconClient::connect(&address, "errada").unwrap();
+
+        assert_eq!(
+            client.execute("status").unwrap_err(),
+            "Senha RCON incorreta."
+        );
+    }
+
+    #[test]
+    fn times_out_without_server() {
+        // Bound but silent socket, so the challenge request never gets an answer.
+        let silent = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let mut client =
+            RconClient::connect(&silent.local_addr().unwrap().to_string(), "segredo").unwrap();
+
+        let err = client.execute("status").unwrap_err();
+        assert!(
+            err.starts_with("Servidor não respondeu ao challenge RCON"),
+            "{}",
+            err
+        );
+    }
+}
@@ -0,0 +1,627 @@
+use std::{
+    fs::{self, File},
+    io::{ErrorKind, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::commands::console;
+use crate::commands::hlds::{HldsState, InstanceRuntime};
+use crate::commands::instances::{get_instance, resolve_instance_id, ServerInstance};
+use crate::commands::map_deps::validate_map_name;
+use crate::commands::maps::installed_maps;
+
+const MAPCYCLE: &str = "mapcycle.txt";
+// The game only rereads the mapcycle when `mapcyclefile` changes, so a live
+// push alternates between mapcycle.txt and this copy of it.
+const MAPCYCLE_LIVE: &str = "mapcycle_live.txt";
+const CVAR_TIMEOUT: Duration = Duration::from_secs(2);
+const ACTION_TIMEOUT: Duration = Duration::from_millis(500);
+const MAX_ROTATION: u32 = 1000;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RotationList {
+    Mapcycle,
+    // AMXX configs/maps.ini, read by mapchooser for the end-of-map vote.
+    MapsIni,
+}
+
+impl FromStr for RotationList {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "mapcycle" => Ok(Self::Mapcycle),
+            "maps-ini" | "maps_ini" => Ok(Self::MapsIni),
+            _ => Err(format!(
+                "Lista inválida: '{}'. Use mapcycle ou maps-ini.",
+                value
+            )),
+        }
+    }
+}
+
+impl RotationList {
+    pub fn path(&self, instance: &ServerInstance) -> Result<PathBuf, String> {
+        let game = instance.game_path()?;
+        Ok(match self {
+            Self::Mapcycle => game.join(MAPCYCLE),
+            Self::MapsIni => game.join("addons/amxmodx/configs/maps.ini"),
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RotationEntry {
+    pub map: String,
+    // Rest of the line, kept as is: the vote description in maps.ini or the
+    // "\minplayers\8\" block in mapcycle.txt.
+    #[serde(default)]
+    pub extra: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct Rotation {
+    pub list: RotationList,
+    pub path: String,
+    pub entries: Vec<RotationEntry>,
+    // Entries without a BSP in maps/; the server skips or stalls on them.
+    pub missing: Vec<String>,
+    pub pushed: bool,
+}
+
+fn parse_line(raw: &str) -> Option<RotationEntry> {
+    let line = raw.split("//").next().unwrap_or("").trim();
+    if line.is_empty() || line.starts_with(';') {
+        return None;
+    }
+    let (map, extra) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let extra = extra.trim();
+    let entry = RotationEntry {
+        map: map.trim_matches('"').to_string(),
+        extra: (!extra.is_empty()).then(|| extra.to_string()),
+    };
+    (!entry.map.is_empty()).then_some(entry)
+}
+
+pub fn parse_rotation(text: &str) -> Vec<RotationEntry> {
+    text.lines().filter_map(parse_line).collect()
+}
+
+fn validate_entry(entry: &RotationEntry) -> Result<(), String> {
+    validate_map_name(&entry.map)?;
+    if entry.map.contains(|c: char| c.is_whitespace() || c == '"') {
+        return Err(format!("Nome de mapa inválido: '{}'.", entry.map));
+    }
+    if entry
+        .extra
+        .as_deref()
+        .map(str::trim)
+        .is_some_and(|e| e.contains(['\n', '\r']))
+    {
+        return Err(format!("Linha inválida para '{}'.", entry.map));
+    }
+    Ok(())
+}
+
+fn entry_line(entry: &RotationEntry) -> String {
+    match entry
+        .extra
+        .as_deref()
+        .map(str::trim)
+        .filter(|e| !e.is_empty())
+    {
+        Some(extra) => format!("{} {}", entry.map, extra),
+        None => entry.map.clone(),
+    }
+}
+
+fn same_entry(a: &RotationEntry, b: &RotationEntry) -> bool {
+    let extra = |e: &RotationEntry| e.extra.as_deref().map(str::trim).unwrap_or("").to_string();
+    a.map.eq_ignore_ascii_case(&b.map) && extra(a) == extra(b)
+}
+
+// Comments, blank lines and headers stay where they are; the new entries fill
+// the lines that held entries, in order, and any extra ones go after the last.
+// An unchanged entry keeps its original line, inline comment included.
+fn render_rotation(previous: &str, entries: &[RotationEntry]) -> String {
+    let newline = if previous.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let lines: Vec<(&str, Option<RotationEntry>)> = previous
+        .lines()
+        .map(|raw| {
+            let raw = raw.strip_suffix('\r').unwrap_or(raw);
+            (raw, parse_line(raw))
+        })
+        .collect();
+
+    let mut originals: Vec<(&str, &RotationEntry)> = lines
+        .iter()
+        .filter_map(|(raw, entry)| Some((*raw, entry.as_ref()?)))
+        .collect();
+    let mut render =
+        |entry: &RotationEntry| match originals.iter().position(|(_, e)| same_entry(e, entry)) {
+            Some(i) => originals.remove(i).0.to_string(),
+            None => entry_line(entry),
+        };
+
+    let last_entry = lines.iter().rposition(|(_, entry)| entry.is_some());
+    let mut pending = entries.iter();
+    let mut out = vec![];
+    for (i, (raw, entry)) in lines.iter().enumerate() {
+        match entry {
+            None => out.push(raw.to_string()),
+            Some(_) => out.extend(pending.next().map(&mut render)),
+        }
+        if Some(i) == last_entry {
+            out.extend(pending.by_ref().map(&mut render));
+        }
+    }
+    out.extend(pending.map(&mut render));
+
+    let mut content = out.join(newline);
+    content.push_str(newline);
+    content
+}
+
+fn check(
+    instance: &ServerInstance,
+    list: RotationList,
+    entries: Vec<RotationEntry>,
+) -> Result<Rotation, String> {
+    let installed = installed_maps(&instance.game_path()?.join("maps"));
+    let mut missing: Vec<String> = vec![];
+    for entry in &entries {
+        if !installed.iter().any(|m| m.eq_ignore_ascii_case(&entry.map))
+            && !missing.contains(&entry.map)
+        {
+            missing.push(entry.map.clone());
+        }
+    }
+
+    Ok(Rotation {
+        list,
+        path: list.path(instance)?.to_string_lossy().into_owned(),
+        entries,
+        missing,
+        pushed: false,
+    })
+}
+
+pub fn read_rotation(instance: &ServerInstance, list: RotationList) -> Result<Rotation, String> {
+    let entries = fs::read_to_string(list.path(instance)?)
+        .map(|text| parse_rotation(&text))
+        .unwrap_or_default();
+    check(instance, list, entries)
+}
+
+fn write_file(path: &Path, content: &str) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let tmp = path.with_extension("tmp");
+    let mut file = File::create(&tmp).map_err(|e| e.to_string())?;
+    file.write_all(content.as_bytes())
+        .map_err(|e| e.to_string())?;
+    file.sync_all().map_err(|e| e.to_string())?;
+    fs::rename(&tmp, path).map_err(|e| e.to_string())
+}
+
+pub fn write_rotation(
+    instance: &ServerInstance,
+    list: RotationList,
+    entries: Vec<RotationEntry>,
+) -> Result<Rotation, String> {
+    for entry in &entries {
+        validate_entry(entry)?;
+    }
+
+    let rotation = check(instance, list, entries)?;
+    let path = Path::new(&rotation.path);
+    let previous = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(format!("Erro ao ler {}: {}", path.display(), e)),
+    };
+    write_file(path, &render_rotation(&previous, &rotation.entries))?;
+    if !rotation.missing.is_empty() {
+        println!(
+            "⚠️ {} referencia mapas não instalados: {}",
+            rotation.path,
+            rotation.missing.join(", ")
+        );
+    }
+    Ok(rotation)
+}
+
+// Builds entries for a new map order, keeping each map's extra from `previous`.
+pub fn reorder(maps: &[String], previous: &[RotationEntry]) -> Vec<RotationEntry> {
+    maps.iter()
+        .map(|map| RotationEntry {
+            map: map.clone(),
+            extra: previous
+                .iter()
+                .find(|e| e.map.eq_ignore_ascii_case(map))
+                .and_then(|e| e.extra.clone()),
+        })
+        .collect()
+}
+
+// `"mapcyclefile" is "mapcycle.txt"`
+fn cvar_value(output: &str, cvar: &str) -> Option<String> {
+    let prefix = format!("\"{}\" is \"", cvar);
+    output.lines().find_map(|line| {
+        let rest = line.trim().strip_prefix(&prefix)?;
+        Some(rest.split('"').next().unwrap_or("").to_string())
+    })
+}
+
+// Points the running server at the new mapcycle, taking effect from the next
+// map change. Returns false when the server is not running.
+pub fn push_mapcycle(runtime: &InstanceRuntime, instance: &ServerInstance) -> Result<bool, String> {
+    let marker = "\"mapcyclefile\" is";
+    let Some(output) = console::execute(
+        runtime,
+        &instance.id,
+        "mapcyclefile",
+        |line| line.contains(marker),
+        CVAR_TIMEOUT,
+    )?
+    else {
+        return Ok(false);
+    };
+    let current = cvar_value(&output, "mapcyclefile").unwrap_or_default();
+
+    let game = instance.game_path()?;
+    let target = if current.eq_ignore_ascii_case(MAPCYCLE) {
+        fs::copy(game.join(MAPCYCLE), game.join(MAPCYCLE_LIVE))
+            .map_err(|e| format!("Erro ao copiar o mapcycle: {}", e))?;
+        MAPCYCLE_LIVE
+    } else {
+        MAPCYCLE
+    };
+
+    let command = format!("mapcyclefile {}", target);
+    console::execute(runtime, &instance.id, &command, |_| true, ACTION_TIMEOUT)?;
+    println!("🔁 [{}] {}", instance.id, command);
+    Ok(true)
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct WeightedMap {
+    pub map: String,
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+}
+
+fn default_weight() -> u32 {
+    1
+}
+
+impl FromStr for WeightedMap {
+    type Err = String;
+
+    // "de_dust2:3" or just "de_dust2".
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (map, weight) = match value.rsplit_once(':') {
+            Some((map, weight)) => (
+                map,
+                weight
+                    .parse()
+                    .map_err(|_| format!("Peso inválido em '{}'.", value))?,
+            ),
+            None => (value, 1),
+        };
+        Ok(Self {
+            map: map.to_string(),
+            weight,
+        })
+    }
+}
+
+// splitmix64, enough to shuffle a map list without pulling in a crate.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: Option<u64>) -> Self {
+        Self(seed.unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_nanos() as u64)
+                .unwrap_or(0)
+        }))
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        (z ^ (z >> 31)) % n
+    }
+}
+
+// Each map appears `weight` times, in random order, never twice in a row
+// when the weights allow it. All weights 1 is a plain shuffle.
+pub fn generate_rotation(pool: &[WeightedMap], seed: Option<u64>) -> Result<Vec<String>, String> {
+    let mut left: Vec<(String, u32)> = vec![];
+    for entry in pool.iter().filter(|e| e.weight > 0) {
+        validate_map_name(&entry.map)?;
+        match left
+            .iter_mut()
+            .find(|(m, _)| m.eq_ignore_ascii_case(&entry.map))
+        {
+            Some((_, weight)) => *weight += entry.weight,
+            None => left.push((entry.map.clone(), entry.weight)),
+        }
+    }
+    let total: u32 = left.iter().map(|(_, w)| w).sum();
+    if total == 0 {
+        return Err("Nenhum mapa com peso maior que zero.".into());
+    }
+    if total > MAX_ROTATION {
+        return Err(format!("A rotação passaria de {} mapas.", MAX_ROTATION));
+    }
+
+    let mut rng = Rng::new(seed);
+    let mut rotation: Vec<String> = vec![];
+    for remaining in (1..=total).rev() {
+        let last = rotation.last();
+        let allowed: Vec<usize> = (0..left.len())
+            .filter(|&i| left[i].1 > 0 && Some(&left[i].0) != last)
+            .collect();
+        let allowed = if allowed.is_empty() {
+            (0..left.len()).filter(|&i| left[i].1 > 0).collect()
+        } else {
+            allowed
+        };
+
+        // A map holding more than half of what is left must go now, or it
+        // ends up repeated at the tail.
+        let pick = match allowed.iter().find(|&&i| left[i].1 * 2 > remaining) {
+            Some(&i) => i,
+            None => {
+                let weight: u32 = allowed.iter().map(|&i| left[i].1).sum();
+                let mut roll = rng.below(weight as u64) as u32;
+                *allowed
+                    .iter()
+                    .find(|&&i| {
+                        if roll < left[i].1 {
+                            return true;
+                        }
+                        roll -= left[i].1;
+                        false
+                    })
+                    .unwrap()
+            }
+        };
+
+        left[pick].1 -= 1;
+        rotation.push(left[pick].0.clone());
+    }
+    Ok(rotation)
+}
+
+#[tauri::command]
+pub fn get_rotation(list: RotationList, instance_id: Option<String>) -> Result<Rotation, String> {
+    read_rotation(&get_instance(instance_id.as_deref())?, list)
+}
+
+#[tauri::command]
+pub async fn save_rotation(
+    state: State<'_, HldsState>,
+    list: RotationList,
+    entries: Vec<RotationEntry>,
+    push: Option<bool>,
+    instance_id: Option<String>,
+) -> Result<Rotation, String> {
+    let instance_id = resolve_instance_id(instance_id);
+    let instance = get_instance(Some(&instance_id))?;
+    let runtime = state.runtime(&instance_id);
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut rotation = write_rotation(&instance, list, entries)?;
+        // mapchooser only reads maps.ini on map start; nothing to push.
+        if push.unwrap_or(false) && list == RotationList::Mapcycle {
+            rotation.pushed = push_mapcycle(&runtime, &instance)?;
+        }
+        Ok(rotation)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub fn generate_map_rotation(
+    pool: Vec<WeightedMap>,
+    seed: Option<u64>,
+) -> Result<Vec<String>, String> {
+    generate_rotation(&pool, seed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool(maps: &[(&str, u32)]) -> Vec<WeightedMap> {
+        maps.iter()
+            .map(|(map, weight)| WeightedMap {
+                map: map.to_string(),
+                weight: *weight,
+            })
+            .collect()
+    }
+
+    fn count(rotation: &[String], map: &str) -> usize {
+        rotation.iter().filter(|m| *m == map).count()
+    }
+
+    fn entry(map: &str, extra: Option<&str>) -> RotationEntry {
+        RotationEntry {
+            map: map.to_string(),
+            extra: extra.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn rotation_follows_weights() {
+        let maps = pool(&[("de_dust2", 3), ("de_inferno", 2), ("de_nuke", 1)]);
+        for seed in 0..50 {
+            let rotation = generate_rotation(&maps, Some(seed)).unwrap();
+            assert_eq!(rotation.len(), 6);
+            assert_eq!(count(&rotation, "de_dust2"), 3);
+            assert_eq!(count(&rotation, "de_inferno"), 2);
+            assert_eq!(count(&rotation, "de_nuke"), 1);
+            assert!(rotation.windows(2).all(|w| w[0] != w[1]), "{:?}", rotation);
+        }
+        assert_eq!(
+            generate_rotation(&maps, Some(7)),
+            generate_rotation(&maps, Some(7))
+        );
+    }
+
+    #[test]
+    fn dominant_map_is_spread_out() {
+        // Three of five slots: only dust2 _ dust2 _ dust2 avoids a repeat.
+        let maps = pool(&[("de_dust2", 3), ("de_nuke", 1), ("de_train", 1)]);
+        for seed in 0..50 {
+            let rotation = generate_rotation(&maps, Some(seed)).unwrap();
+            assert_eq!(rotation[0], "de_dust2");
+            assert_eq!(rotation[2], "de_dust2");
+            assert_eq!(rotation[4], "de_dust2");
+        }
+
+        // Too heavy to avoid repeats entirely; they are pushed to the end.
+        let rotation = generate_rotation(&pool(&[("de_dust2", 3), ("de_nuke", 1)]), Some(1));
+        assert_eq!(
+            rotation.unwrap(),
+            ["de_dust2", "de_nuke", "de_dust2", "de_dust2"]
+        );
+    }
+
+    #[test]
+    fn duplicate_maps_add_up() {
+        let maps = pool(&[
+            ("de_dust2", 1),
+            ("DE_DUST2", 1),
+            ("de_nuke", 2),
+            ("cs_office", 0),
+        ]);
+        let rotation = generate_rotation(&maps, Some(3)).unwrap();
+        assert_eq!(rotation.len(), 4);
+        assert_eq!(count(&rotation, "de_dust2"), 2);
+        assert_eq!(count(&rotation, "cs_office"), 0);
+    }
+
+    #[test]
+    fn rotation_size_is_bounded() {
+        assert!(generate_rotation(&pool(&[("de_dust2", 0)]), Some(1)).is_err());
+        assert!(generate_rotation(&pool(&[("../de_dust2", 1)]), Some(1)).is_err());
+        assert_eq!(
+            generate_rotation(&pool(&[("de_dust2", MAX_ROTATION)]), Some(1))
+                .unwrap()
+                .len(),
+            MAX_ROTATION as usize
+        );
+        assert!(generate_rotation(
+            &pool(&[("de_dust2", MAX_ROTATION), ("de_nuke", 1)]),
+            Some(1)
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn parses_lists() {
+        let text = "// mapcycle\n\
+                    de_dust2\n\
+                    \n\
+                    de_inferno \\minplayers\\8\\ // night only\n\
+                    ; disabled\n\
+                    \"de_nuke\" \"Nuke\"\n\
+                    //de_train\n";
+        assert_eq!(
+            parse_rotation(text),
+            [
+                entry("de_dust2", None),
+                entry("de_inferno", Some("\\minplayers\\8\\")),
+                entry("de_nuke", Some("\"Nuke\"")),
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_cvar_values() {
+        let output = "] mapcyclefile\n\"mapcyclefile\" is \"mapcycle_live.txt\"\n";
+        assert_eq!(
+            cvar_value(output, "mapcyclefile").as_deref(),
+            Some("mapcycle_live.txt")
+        );
+        assert_eq!(
+            cvar_value("\"mapcyclefile\" is \"\"", "mapcyclefile").as_deref(),
+            Some("")
+        );
+        assert_eq!(
+            cvar_value("Unknown command: mapcyclefile", "mapcyclefile"),
+            None
+        );
+    }
+
+    #[test]
+    fn rewrite_keeps_comments() {
+        let previous = "// Rotação do servidor\n\
+                        de_dust2 // favorito\n\
+                        de_inferno\n\
+                        \n\
+                        ; mapas de treino\n\
+                        de_nuke\n\
+                        // fim\n";
+        let entries = [
+            entry("de_nuke", None),
+            entry("de_dust2", None),
+            entry("de_train", None),
+            entry("de_aztec", None),
+        ];
+        assert_eq!(
+            render_rotation(previous, &entries),
+            "// Rotação do servidor\n\
+             de_nuke\n\
+             de_dust2 // favorito\n\
+             \n\
+             ; mapas de treino\n\
+             de_train\n\
+             de_aztec\n\
+             // fim\n"
+        );
+
+        assert_eq!(
+            render_rotation(previous, &[entry("de_inferno", Some("\"Inferno\""))]),
+            "// Rotação do servidor\n\
+             de_inferno \"Inferno\"\n\
+             \n\
+             ; mapas de treino\n\
+             // fim\n"
+        );
+    }
+
+    #[test]
+    fn rewrite_keeps_line_endings() {
+        assert_eq!(
+            render_rotation(
+                "// maps\r\nde_dust2\r\n",
+                &[entry("de_dust2", None), entry("de_nuke", None)]
+            ),
+            "// maps\r\nde_dust2\r\nde_nuke\r\n"
+        );
+        assert_eq!(
+            render_rotation("", &[entry("de_dust2", None)]),
+            "de_dust2\n"
+        );
+    }
+}
@@ -0,0 +1,286 @@
+use std::{
+    io::Write,
+    process::{Child, Command},
+    thread,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+const TERMINATE_TIMEOUT: Duration = Duration::from_secs(5);
+const WAIT_POLL: Duration = Duration::from_millis(200);
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct StopOptions {
+    #[serde(default = "default_countdown")]
+    pub countdown: u32,
+    #[serde(default)]
+    pub message: Option<String>,
+    #[serde(default = "default_quit_timeout")]
+    pub quit_timeout: u64,
+}
+
+impl Default for StopOptions {
+    fn default() -> Self {
+        Self {
+            countdown: default_countdown(),
+            message: None,
+            quit_timeout: default_quit_timeout(),
+        }
+    }
+}
+
+fn default_countdown() -> u32 {
+    10
+}
+
+fn default_quit_timeout() -> u64 {
+    15
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum StopMethod {
+    NotRunning,
+    Quit,
+    Terminated,
+    Killed,
+}
+
+impl StopMethod {
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::NotRunning => "o servidor já estava parado",
+            Self::Quit => "encerrado com `quit`",
+            Self::Terminated => "encerrado com SIGTERM",
+            Self::Killed => "encerrado à força (SIGKILL)",
+        }
+    }
+}
+
+// What a graceful shutdown needs from a server: our own child process, or one
+// we reattached to and can only reach through RCON.
+pub trait ShutdownTarget {
+    fn send_console(&mut self, command: &str);
+    fn has_exited(&mut self) -> bool;
+    fn terminate(&mut self);
+    fn force_kill(&mut self);
+}
+
+impl ShutdownTarget for Child {
+    fn send_console(&mut self, command: &str) {
+        if let Some(stdin) = &mut self.stdin {
+            let _ = stdin.write_all(format!("{}\n", command).as_bytes());
+        }
+    }
+
+    fn has_exited(&mut self) -> bool {
+        matches!(self.try_wait(), Ok(Some(_)))
+    }
+
+    fn terminate(&mut self) {
+        terminate_group(self.id());
+    }
+
+    fn force_kill(&mut self) {
+        force_kill(self);
+    }
+}
+
+pub fn graceful_shutdown(target: &mut impl ShutdownTarget, options: &StopOptions) -> StopMethod {
+    let message = sanitize_say(
+        options
+            .message
+            .as_deref()
+            .unwrap_or("Servidor será desligado em"),
+    );
+
+    for remaining in (1..=options.countdown).rev() {
+        if target.has_exited() {
+            return StopMethod::Quit;
+        }
+
+        if remaining == options.countdown || remaining <= 5 || remaining % 10 == 0 {
+            target.send_console(&format!("say \"{} {}s\"", message, remaining));
+        }
+        thread::sleep(Duration::from_secs(1));
+    }
+
+    target.send_console("quit");
+    if wait_timeout(target, Duration::from_secs(options.quit_timeout)) {
+        return StopMethod::Quit;
+    }
+
+    println!("⚠️ HLDS não respondeu ao quit, enviando SIGTERM");
+    target.terminate();
+    if wait_timeout(target, TERMINATE_TIMEOUT) {
+        return StopMethod::Terminated;
+    }
+
+    println!("⚠️ HLDS não respondeu ao SIGTERM, forçando encerramento");
+    target.force_kill();
+    StopMethod::Killed
+}
+
+pub fn force_kill(child: &mut Child) {
+    kill_group(child.id());
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+// HLDS runs in its own process group, so signals reach hlds_linux and not only the hlds_run wrapper.
+pub fn terminate_group(pid: u32) {
+    #[cfg(unix)]
+    {
+        let _ = Command::new("kill")
+            .args(["-TERM", "--", &format!("-{}", pid)])
+            .output();
+    }
+
+    #[cfg(windows)]
+    {
+        let _ = Command::new("taskkill")
+            .args(["/T", "/PID", &pid.to_string()])
+            .output();
+    }
+}
+
+pub fn kill_group(pid: u32) {
+    #[cfg(unix)]
+    {
+        let _ = Command::new("kill")
+            .args(["-KILL", "--", &format!("-{}", pid)])
+            .output();
+    }
+
+    #[cfg(windows)]
+    {
+        let _ = Command::new("taskkill")
+            .args(["/F", "/T", "/PID", &pid.to_string()])
+            .output();
+    }
+}
+
+fn wait_timeout(target: &mut impl ShutdownTarget, timeout: Duration) -> bool {
+    let started = Instant::now();
+    while started.elapsed() < timeout {
+        if target.has_exited() {
+            return true;
+        }
+        thread::sleep(WAIT_POLL);
+    }
+    target.has_exited()
+}
+
+// Quotes and semicolons would let the message break out of the `say` command.
+pub fn sanitize_say(message: &str) -> String {
+    message
+        .chars()
+        .filter(|c| !matches!(c, '"' | ';' | '\n' | '\r'))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Records what it was sent and exits once a given step has happened.
+    struct FakeServer {
+        exits_after: &'static str,
+        log: Vec<String>,
+    }
+
+    impl FakeServer {
+        fn new(exits_after: &'static str) -> Self {
+            Self {
+                exits_after,
+                log: vec![],
+            }
+        }
+    }
+
+    impl ShutdownTarget for FakeServer {
+        fn send_console(&mut self, command: &str) {
+            self.log.push(command.to_string());
+        }
+
+        fn has_exited(&mut self) -> bool {
+            self.log
+                .iter()
+                .any(|step| step.starts_with(self.exits_after))
+        }
+
+        fn terminate(&mut self) {
+            self.log.push("terminate".into());
+        }
+
+        fn force_kill(&mut self) {
+            self.log.push("kill".into());
+        }
+    }
+
+    fn options(countdown: u32) -> StopOptions {
+        StopOptions {
+            countdown,
+            message: None,
+            quit_timeout: 0,
+        }
+    }
+
+    #[test]
+    fn exits_during_the_countdown() {
+        let mut server = FakeServer::new("say");
+        assert_eq!(
+            graceful_shutdown(&mut server, &options(3)),
+            StopMethod::Quit
+        );
+        assert_eq!(server.log, ["say \"Servidor será desligado em 3s\""]);
+    }
+
+    #[test]
+    fn exits_after_quit() {
+        let mut server = FakeServer::new("quit");
+        assert_eq!(
+            graceful_shutdown(&mut server, &options(0)),
+            StopMethod::Quit
+        );
+        assert_eq!(server.log, ["quit"]);
+    }
+
+    #[test]
+    fn needs_terminate() {
+        let mut server = FakeServer::new("terminate");
+        assert_eq!(
+            graceful_shutdown(&mut server, &options(0)),
+            StopMethod::Terminated
+        );
+        assert_eq!(server.log, ["quit", "terminate"]);
+    }
+
+    #[test]
+    fn needs_kill() {
+        let mut server = FakeServer::new("kill");
+        assert_eq!(
+            graceful_shutdown(&mut server, &options(0)),
+            StopMethod::Killed
+        );
+        assert_eq!(server.log, ["quit", "terminate", "kill"]);
+    }
+
+    #[test]
+    fn say_message_cannot_break_out() {
+        assert_eq!(
+            sanitize_say("Reinício \"agora\"; quit\r\nrcon_password x"),
+            "Reinício agora quitrcon_password x"
+        );
+        assert_eq!(sanitize_say("Até já!"), "Até já!");
+
+        let mut server = FakeServer::new("say");
+        let options = StopOptions {
+            message: Some("\"; quit;\"".into()),
+            ..options(1)
+        };
+        graceful_shutdown(&mut server, &options);
+        assert_eq!(server.log[0], "say \" quit 1s\"");
+    }
+}
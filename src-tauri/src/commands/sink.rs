@@ -0,0 +1,21 @@
+use std::sync::Arc;
+
+use tauri::{AppHandle, Emitter};
+
+// Where live server events (log lines, crashes, ...) go: the Tauri window, or
+// the WebSocket clients of the headless daemon.
+pub trait EventSink: Send + Sync {
+    fn emit(&self, event: &str, payload: serde_json::Value);
+}
+
+pub type Sink = Arc<dyn EventSink>;
+
+impl EventSink for AppHandle {
+    fn emit(&self, event: &str, payload: serde_json::Value) {
+        let _ = Emitter::emit(self, event, payload);
+    }
+}
+
+pub fn app_sink(app: AppHandle) -> Sink {
+    Arc::new(app)
+}
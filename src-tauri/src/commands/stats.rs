@@ -0,0 +1,625 @@
+use std::{fs, path::Path, path::PathBuf, sync::Mutex};
+
+use chrono::{Duration, Local, NaiveDate, NaiveDateTime};
+use once_cell::sync::Lazy;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+use crate::commands::instances::get_instance;
+use crate::commands::log_parser::{parse_log_line, GameEvent, LogEntry, LogPlayer};
+
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+pub static STATS_DB_PATH: Lazy<PathBuf> = Lazy::new(|| {
+    let mut path = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
+    path.push("canalhas-manager/stats.db");
+    path
+});
+
+static STATS_DB: Lazy<Mutex<Option<Connection>>> = Lazy::new(|| Mutex::new(None));
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS matches (
+    id INTEGER PRIMARY KEY,
+    instance TEXT NOT NULL,
+    map TEXT NOT NULL,
+    started_at TEXT NOT NULL,
+    UNIQUE (instance, map, started_at)
+);
+
+CREATE TABLE IF NOT EXISTS kills (
+    id INTEGER PRIMARY KEY,
+    instance TEXT NOT NULL,
+    match_id INTEGER REFERENCES matches (id),
+    map TEXT,
+    occurred_at TEXT NOT NULL,
+    killer_id TEXT NOT NULL,
+    killer_name TEXT NOT NULL,
+    killer_team TEXT NOT NULL,
+    victim_id TEXT NOT NULL,
+    victim_name TEXT NOT NULL,
+    victim_team TEXT NOT NULL,
+    weapon TEXT NOT NULL,
+    headshot INTEGER NOT NULL DEFAULT 0,
+    UNIQUE (instance, occurred_at, killer_id, victim_id, weapon)
+);
+
+CREATE TABLE IF NOT EXISTS rounds (
+    id INTEGER PRIMARY KEY,
+    instance TEXT NOT NULL,
+    match_id INTEGER REFERENCES matches (id),
+    map TEXT,
+    ended_at TEXT NOT NULL,
+    winner TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    ct_score INTEGER,
+    t_score INTEGER,
+    UNIQUE (instance, ended_at, reason)
+);
+
+CREATE INDEX IF NOT EXISTS kills_occurred_at ON kills (occurred_at);
+CREATE INDEX IF NOT EXISTS kills_killer ON kills (killer_id);
+CREATE INDEX IF NOT EXISTS kills_victim ON kills (victim_id);
+CREATE INDEX IF NOT EXISTS rounds_ended_at ON rounds (ended_at);
+";
+
+pub fn with_stats_db<T>(f: impl FnOnce(&Connection) -> rusqlite::Result<T>) -> Result<T, String> {
+    let mut guard = STATS_DB.lock().unwrap();
+
+    if guard.is_none() {
+        if let Some(parent) = STATS_DB_PATH.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let conn = Connection::open(&*STATS_DB_PATH).map_err(|e| e.to_string())?;
+        conn.execute_batch(SCHEMA).map_err(|e| e.to_string())?;
+        *guard = Some(conn);
+    }
+
+    f(guard.as_ref().unwrap()).map_err(|e| e.to_string())
+}
+
+pub struct StatsRecorder {
+    instance_id: String,
+    match_id: Option<i64>,
+    map: Option<String>,
+}
+
+impl StatsRecorder {
+    pub fn new(instance_id: &str) -> Self {
+        Self {
+            instance_id: instance_id.to_string(),
+            match_id: None,
+            map: None,
+        }
+    }
+
+    // The map of the last "Started map" line seen.
+    pub fn map(&self) -> Option<&str> {
+        self.map.as_deref()
+    }
+
+    pub fn record_entry(&mut self, entry: &LogEntry) {
+        if let Err(e) = with_stats_db(|conn| self.record(conn, entry).map(|_| ())) {
+            println!("❌ Erro ao gravar estatísticas: {}", e);
+        }
+    }
+
+    pub fn record(&mut self, conn: &Connection, entry: &LogEntry) -> rusqlite::Result<usize> {
+        let at = entry
+            .timestamp
+            .unwrap_or_else(|| Local::now().naive_local())
+            .format(TIMESTAMP_FORMAT)
+            .to_string();
+
+        match &entry.event {
+            GameEvent::MapStarted { map } => {
+                conn.execute(
+                    "INSERT OR IGNORE INTO matches (instance, map, started_at) VALUES (?1, ?2, ?3)",
+                    params![self.instance_id, map, at],
+                )?;
+                self.match_id = conn
+                    .query_row(
+                        "SELECT id FROM matches
+                         WHERE instance = ?1 AND map = ?2 AND started_at = ?3",
+                        params![self.instance_id, map, at],
+                        |row| row.get(0),
+                    )
+                    .optional()?;
+                self.map = Some(map.clone());
+                Ok(0)
+            }
+            GameEvent::Kill {
+                killer,
+                victim,
+                weapon,
+                headshot,
+            } => conn.execute(
+                "INSERT OR IGNORE INTO kills (
+                    instance, match_id, map, occurred_at,
+                    killer_id, killer_name, killer_team,
+                    victim_id, victim_name, victim_team,
+                    weapon, headshot
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+                params![
+                    self.instance_id,
+                    self.match_id,
+                    self.map,
+                    at,
+                    player_key(killer),
+                    killer.name,
+                    killer.team,
+                    player_key(victim),
+                    victim.name,
+                    victim.team,
+                    weapon,
+                    headshot
+                ],
+            ),
+            GameEvent::TeamTriggered {
+                team,
+                action,
+                ct_score: Some(ct_score),
+                t_score: Some(t_score),
+            } => conn.execute(
+                "INSERT OR IGNORE INTO rounds (
+                    instance, match_id, map, ended_at, winner, reason, ct_score, t_score
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    self.instance_id,
+                    self.match_id,
+                    self.map,
+                    at,
+                    team,
+                    action,
+                    ct_score,
+                    t_score
+                ],
+            ),
+            _ => Ok(0),
+        }
+    }
+}
+
+// LAN servers and bots share placeholder IDs, so those players are keyed by name.
+pub fn player_key(player: &LogPlayer) -> String {
+    match player.steam_id.as_str() {
+        "BOT" | "HLTV" | "STEAM_ID_LAN" | "STEAM_ID_PENDING" | "VALVE_ID_LAN" | "" => {
+            format!("name:{}", player.name)
+        }
+        steam_id => steam_id.to_string(),
+    }
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+pub struct StatsFilter {
+    // None covers every instance.
+    pub instance: Option<String>,
+    pub map: Option<String>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub limit: Option<u32>,
+}
+
+impl StatsFilter {
+    fn bounds(&self) -> Result<(Option<String>, Option<String>), String> {
+        let from = self
+            .from
+            .as_deref()
+            .map(|s| parse_bound(s, false))
+            .transpose()?;
+        let to = self
+            .to
+            .as_deref()
+            .map(|s| parse_bound(s, true))
+            .transpose()?;
+        Ok((from, to))
+    }
+
+    fn limit(&self) -> u32 {
+        self.limit.unwrap_or(50)
+    }
+}
+
+// A bare date as the upper bound includes that whole day.
+fn parse_bound(value: &str, upper: bool) -> Result<String, String> {
+    let value = value.trim();
+    if let Ok(datetime) = NaiveDateTime::parse_from_str(value, TIMESTAMP_FORMAT) {
+        return Ok(datetime.format(TIMESTAMP_FORMAT).to_string());
+    }
+
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| format!("Data inválida: '{}'. Use AAAA-MM-DD.", value))?;
+    let date = if upper {
+        date + Duration::days(1)
+    } else {
+        date
+    };
+    Ok(date.format("%Y-%m-%d 00:00:00").to_string())
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct PlayerStats {
+    pub player_id: String,
+    pub name: String,
+    pub kills: u32,
+    pub deaths: u32,
+    // Only kill lines ending in "(headshot)" count; stock CS 1.6 logs never
+    // write it, so this stays 0 unless an AMXX plugin logs headshots.
+    pub headshots: u32,
+    pub teamkills: u32,
+    pub kd: f64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct WeaponStats {
+    pub weapon: String,
+    pub kills: u32,
+    pub headshots: u32,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct PlayerDetail {
+    pub stats: PlayerStats,
+    pub weapons: Vec<WeaponStats>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct MapStats {
+    pub map: String,
+    pub matches: u32,
+    pub rounds: u32,
+    pub ct_wins: u32,
+    pub t_wins: u32,
+    pub kills: u32,
+    pub headshots: u32,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct ImportSummary {
+    pub files: u32,
+    pub lines: u32,
+    pub kills: u32,
+    pub rounds: u32,
+}
+
+const FILTERED_KILLS: &str = "
+    filtered AS (
+        SELECT * FROM kills
+        WHERE (?1 IS NULL OR map = ?1)
+          AND (?2 IS NULL OR occurred_at >= ?2)
+          AND (?3 IS NULL OR occurred_at < ?3)
+          AND (?5 IS NULL OR instance = ?5)
+    )";
+
+const PLAYER_TOTALS: &str = "
+    frags AS (
+        SELECT killer_id AS player_id,
+               COUNT(*) AS kills,
+               SUM(headshot) AS headshots,
+               SUM(killer_team = victim_team AND killer_team != '') AS teamkills
+        FROM filtered
+        WHERE killer_id != victim_id
+        GROUP BY killer_id
+    ),
+    deaths AS (
+        SELECT victim_id AS player_id, COUNT(*) AS deaths
+        FROM filtered
+        GROUP BY victim_id
+    ),
+    names AS (
+        SELECT player_id, name, MAX(occurred_at) AS last_seen FROM (
+            SELECT killer_id AS player_id, killer_name AS name, occurred_at FROM filtered
+            UNION ALL
+            SELECT victim_id, victim_name, occurred_at FROM filtered
+        )
+        GROUP BY player_id
+    ),
+    totals AS (
+        SELECT n.player_id,
+               n.name,
+               COALESCE(f.kills, 0) AS kills,
+               COALESCE(d.deaths, 0) AS deaths,
+               COALESCE(f.headshots, 0) AS headshots,
+               COALESCE(f.teamkills, 0) AS teamkills
+        FROM names n
+        LEFT JOIN frags f ON f.player_id = n.player_id
+        LEFT JOIN deaths d ON d.player_id = n.player_id
+    )";
+
+fn player_stats_from_row(row: &rusqlite::Row) -> rusqlite::Result<PlayerStats> {
+    let kills: u32 = row.get(2)?;
+    let deaths: u32 = row.get(3)?;
+    Ok(PlayerStats {
+        player_id: row.get(0)?,
+        name: row.get(1)?,
+        kills,
+        deaths,
+        headshots: row.get(4)?,
+        teamkills: row.get(5)?,
+        kd: if deaths == 0 {
+            kills as f64
+        } else {
+            kills as f64 / deaths as f64
+        },
+    })
+}
+
+fn player_leaderboard(filter: &StatsFilter) -> Result<Vec<PlayerStats>, String> {
+    let (from, to) = filter.bounds()?;
+    let sql = format!(
+        "WITH {FILTERED_KILLS}, {PLAYER_TOTALS}
+         SELECT player_id, name, kills, deaths, headshots, teamkills
+         FROM totals
+         ORDER BY kills DESC, deaths ASC
+         LIMIT ?4"
+    );
+
+    with_stats_db(|conn| {
+        conn.prepare(&sql)?
+            .query_map(
+                params![filter.map, from, to, filter.limit(), filter.instance],
+                player_stats_from_row,
+            )?
+            .collect()
+    })
+}
+
+fn player_detail(player_id: &str, filter: &StatsFilter) -> Result<Option<PlayerDetail>, String> {
+    let (from, to) = filter.bounds()?;
+    let stats_sql = format!(
+        "WITH {FILTERED_KILLS}, {PLAYER_TOTALS}
+         SELECT player_id, name, kills, deaths, headshots, teamkills
+         FROM totals
+         WHERE player_id = ?4"
+    );
+    let weapons_sql = format!(
+        "WITH {FILTERED_KILLS}
+         SELECT weapon, COUNT(*), SUM(headshot)
+         FROM filtered
+         WHERE killer_id = ?4 AND victim_id != ?4
+         GROUP BY weapon
+         ORDER BY COUNT(*) DESC"
+    );
+
+    with_stats_db(|conn| {
+        let stats = conn
+            .query_row(
+                &stats_sql,
+                params![filter.map, from, to, player_id, filter.instance],
+                player_stats_from_row,
+            )
+            .optional()?;
+
+        let Some(stats) = stats else {
+            return Ok(None);
+        };
+
+        let weapons = conn
+            .prepare(&weapons_sql)?
+            .query_map(
+                params![filter.map, from, to, player_id, filter.instance],
+                |row| {
+                    Ok(WeaponStats {
+                        weapon: row.get(0)?,
+                        kills: row.get(1)?,
+                        headshots: row.get(2)?,
+                    })
+                },
+            )?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(Some(PlayerDetail { stats, weapons }))
+    })
+}
+
+fn map_leaderboard(filter: &StatsFilter) -> Result<Vec<MapStats>, String> {
+    let (from, to) = filter.bounds()?;
+    let sql = "
+        WITH map_matches AS (
+            SELECT map, COUNT(*) AS matches FROM matches
+            WHERE (?1 IS NULL OR map = ?1)
+              AND (?2 IS NULL OR started_at >= ?2)
+              AND (?3 IS NULL OR started_at < ?3)
+              AND (?5 IS NULL OR instance = ?5)
+            GROUP BY map
+        ),
+        map_rounds AS (
+            SELECT map,
+                   COUNT(*) AS rounds,
+                   SUM(winner = 'CT') AS ct_wins,
+                   SUM(winner = 'TERRORIST') AS t_wins
+            FROM rounds
+            WHERE map IS NOT NULL
+              AND (?1 IS NULL OR map = ?1)
+              AND (?2 IS NULL OR ended_at >= ?2)
+              AND (?3 IS NULL OR ended_at < ?3)
+              AND (?5 IS NULL OR instance = ?5)
+            GROUP BY map
+        ),
+        map_kills AS (
+            SELECT map, COUNT(*) AS kills, SUM(headshot) AS headshots FROM kills
+            WHERE map IS NOT NULL
+              AND (?1 IS NULL OR map = ?1)
+              AND (?2 IS NULL OR occurred_at >= ?2)
+              AND (?3 IS NULL OR occurred_at < ?3)
+              AND (?5 IS NULL OR instance = ?5)
+            GROUP BY map
+        ),
+        maps AS (
+            SELECT map FROM map_matches
+            UNION SELECT map FROM map_rounds
+            UNION SELECT map FROM map_kills
+        )
+        SELECT m.map,
+               COALESCE(mm.matches, 0),
+               COALESCE(mr.rounds, 0),
+               COALESCE(mr.ct_wins, 0),
+               COALESCE(mr.t_wins, 0),
+               COALESCE(mk.kills, 0),
+               COALESCE(mk.headshots, 0)
+        FROM maps m
+        LEFT JOIN map_matches mm ON mm.map = m.map
+        LEFT JOIN map_rounds mr ON mr.map = m.map
+        LEFT JOIN map_kills mk ON mk.map = m.map
+        ORDER BY COALESCE(mm.matches, 0) DESC, COALESCE(mr.rounds, 0) DESC
+        LIMIT ?4";
+
+    with_stats_db(|conn| {
+        conn.prepare(sql)?
+            .query_map(
+                params![filter.map, from, to, filter.limit(), filter.instance],
+                |row| {
+                    Ok(MapStats {
+                        map: row.get(0)?,
+                        matches: row.get(1)?,
+                        rounds: row.get(2)?,
+                        ct_wins: row.get(3)?,
+                        t_wins: row.get(4)?,
+                        kills: row.get(5)?,
+                        headshots: row.get(6)?,
+                    })
+                },
+            )?
+            .collect()
+    })
+}
+
+pub fn import_log_dir(instance_id: &str, logs_path: &Path) -> Result<ImportSummary, String> {
+    let mut files: Vec<PathBuf> = fs::read_dir(logs_path)
+        .map_err(|e| format!("Erro ao ler {}: {}", logs_path.display(), e))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "log"))
+        .collect();
+    files.sort();
+
+    let mut summary = ImportSummary::default();
+
+    for file in files {
+        let bytes =
+            fs::read(&file).map_err(|e| format!("Erro ao ler {}: {}", file.display(), e))?;
+        let contents = String::from_utf8_lossy(&bytes);
+        let mut recorder = StatsRecorder::new(instance_id);
+
+        with_stats_db(|conn| {
+            let tx = conn.unchecked_transaction()?;
+            for line in contents.lines() {
+                let Some(entry) = parse_log_line(line) else {
+                    continue;
+                };
+                summary.lines += 1;
+
+                let inserted = recorder.record(&tx, &entry)? as u32;
+                match entry.event {
+                    GameEvent::Kill { .. } => summary.kills += inserted,
+                    GameEvent::TeamTriggered { .. } => summary.rounds += inserted,
+                    _ => {}
+                }
+            }
+            tx.commit()
+        })?;
+
+        summary.files += 1;
+    }
+
+    println!(
+        "📊 Importados {} log(s): {} abates e {} rounds novos",
+        summary.files, summary.kills, summary.rounds
+    );
+
+    Ok(summary)
+}
+
+#[tauri::command]
+pub async fn get_player_leaderboard(
+    filter: Option<StatsFilter>,
+) -> Result<Vec<PlayerStats>, String> {
+    let filter = filter.unwrap_or_default();
+    tauri::async_runtime::spawn_blocking(move || player_leaderboard(&filter))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub async fn get_player_stats(
+    player_id: String,
+    filter: Option<StatsFilter>,
+) -> Result<Option<PlayerDetail>, String> {
+    let filter = filter.unwrap_or_default();
+    tauri::async_runtime::spawn_blocking(move || player_detail(&player_id, &filter))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub async fn get_map_leaderboard(filter: Option<StatsFilter>) -> Result<Vec<MapStats>, String> {
+    let filter = filter.unwrap_or_default();
+    tauri::async_runtime::spawn_blocking(move || map_leaderboard(&filter))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub async fn import_stats_logs(instance_id: Option<String>) -> Result<ImportSummary, String> {
+    let instance = get_instance(instance_id.as_deref())?;
+    let logs_path = instance.game_path()?.join("logs");
+
+    if !logs_path.exists() {
+        return Err("Pasta de logs não encontrada.".to_string());
+    }
+
+    tauri::async_runtime::spawn_blocking(move || import_log_dir(&instance.id, &logs_path))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LINES: [&str; 2] = [
+        r#"L 08/13/2024 - 21:30:00: Started map "de_dust2" (CRC "-1480873540")"#,
+        r#"L 08/13/2024 - 21:45:12: "Fulano<2><STEAM_0:1:12345><CT>" killed "Ciclano<3><STEAM_0:0:54321><TERRORIST>" with "ak47""#,
+    ];
+
+    fn record(conn: &Connection, instance_id: &str) -> usize {
+        let mut recorder = StatsRecorder::new(instance_id);
+        LINES
+            .iter()
+            .map(|line| {
+                recorder
+                    .record(conn, &parse_log_line(line).unwrap())
+                    .unwrap()
+            })
+            .sum()
+    }
+
+    fn kills_by_instance(conn: &Connection) -> Vec<(String, u32)> {
+        conn.prepare("SELECT instance, COUNT(*) FROM kills GROUP BY instance ORDER BY instance")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn same_log_is_kept_apart_per_instance() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(SCHEMA).unwrap();
+
+        assert_eq!(record(&conn, "default"), 1);
+        assert_eq!(record(&conn, "default"), 0);
+        assert_eq!(record(&conn, "scrim"), 1);
+        assert_eq!(
+            kills_by_instance(&conn),
+            [("default".to_string(), 1), ("scrim".to_string(), 1)]
+        );
+
+        let matches: u32 = conn
+            .query_row("SELECT COUNT(DISTINCT match_id) FROM kills", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(matches, 2);
+    }
+}
@@ -0,0 +1,439 @@
+use std::{str::FromStr, time::Duration};
+
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::commands::bans::{ban, BanRecord, NewBan};
+use crate::commands::console;
+use crate::commands::hlds::{HldsState, InstanceRuntime};
+use crate::commands::instances::resolve_instance_id;
+
+const STATUS_TIMEOUT: Duration = Duration::from_secs(3);
+const ACTION_TIMEOUT: Duration = Duration::from_millis(500);
+
+// One row of the `status` table:
+// #  1 "Player" 2 STEAM_0:1:12345   3 12:34   45    0 192.168.0.5:27005
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct StatusPlayer {
+    pub userid: u32,
+    pub name: String,
+    pub unique_id: String,
+    pub frags: i32,
+    pub time: String,
+    pub ping: u32,
+    pub loss: u32,
+    pub address: Option<String>,
+}
+
+impl StatusPlayer {
+    pub fn is_bot(&self) -> bool {
+        matches!(self.unique_id.as_str(), "BOT" | "HLTV")
+    }
+
+    // Placeholder IDs (LAN, pending validation) can't be banned by SteamID.
+    fn steam_id(&self) -> Option<&str> {
+        let id = &self.unique_id;
+        ((id.starts_with("STEAM_") || id.starts_with("VALVE_")) && id.matches(':').count() == 2)
+            .then_some(id.as_str())
+    }
+
+    fn ip(&self) -> Option<&str> {
+        let address = self.address.as_deref()?;
+        let ip = address.split(':').next().unwrap_or(address);
+        ip.parse::<std::net::Ipv4Addr>().is_ok().then_some(ip)
+    }
+}
+
+pub fn parse_status(output: &str) -> Vec<StatusPlayer> {
+    output.lines().filter_map(parse_status_line).collect()
+}
+
+fn parse_status_line(line: &str) -> Option<StatusPlayer> {
+    let rest = line.trim().strip_prefix('#')?;
+    let (slot, rest) = rest.split_once('"')?;
+    slot.trim().parse::<u32>().ok()?;
+
+    // Names may contain spaces and quotes; the last quote closes the name.
+    let (name, rest) = rest.rsplit_once('"')?;
+    let mut tokens = rest.split_whitespace();
+    let userid = tokens.next()?.parse().ok()?;
+    let unique_id = tokens.next()?.to_string();
+
+    // HLTV proxies print extra "hltv:1/128 delay:30" columns instead of frags.
+    let mut tokens = tokens.filter(|t| !t.starts_with("hltv:") && !t.starts_with("delay:"));
+    let frags = match unique_id.as_str() {
+        "HLTV" => 0,
+        _ => tokens.next()?.parse().ok()?,
+    };
+    let time = tokens.next()?.to_string();
+    let ping = tokens.next()?.parse().ok()?;
+    let loss = tokens.next()?.parse().ok()?;
+    let address = tokens.next().map(str::to_string);
+
+    Some(StatusPlayer {
+        userid,
+        name: name.to_string(),
+        unique_id,
+        frags,
+        time,
+        ping,
+        loss,
+        address,
+    })
+}
+
+// `status` ends its table with "N users".
+fn is_status_end(line: &str) -> bool {
+    let mut words = line.split_whitespace();
+    matches!(
+        (words.next(), words.next(), words.next()),
+        (Some(n), Some("users" | "user"), None) if n.parse::<u32>().is_ok()
+    )
+}
+
+pub fn players(runtime: &InstanceRuntime, instance_id: &str) -> Result<Vec<StatusPlayer>, String> {
+    let output = console::execute(
+        runtime,
+        instance_id,
+        "status",
+        is_status_end,
+        STATUS_TIMEOUT,
+    )?
+    .ok_or("O servidor não está em execução.")?;
+    Ok(parse_status(&output))
+}
+
+// Identifies a player by userid plus the uniqueid the caller saw, so an action
+// never lands on someone else who reconnected into the same slot.
+#[derive(Deserialize, Debug, Clone)]
+pub struct PlayerTarget {
+    pub userid: u32,
+    pub unique_id: String,
+}
+
+fn resolve(
+    runtime: &InstanceRuntime,
+    instance_id: &str,
+    target: &PlayerTarget,
+) -> Result<StatusPlayer, String> {
+    players(runtime, instance_id)?
+        .into_iter()
+        .find(|p| p.userid == target.userid && p.unique_id == target.unique_id)
+        .ok_or_else(|| {
+            format!(
+                "O jogador #{} ({}) não está mais no servidor.",
+                target.userid, target.unique_id
+            )
+        })
+}
+
+// Keeps free text from breaking out of the quoted console argument.
+fn quoted(text: &str) -> Result<String, String> {
+    if text.contains(['"', ';', '\n', '\r']) {
+        return Err("O texto não pode conter aspas, ';' ou quebras de linha.".into());
+    }
+    Ok(format!("\"{}\"", text.trim()))
+}
+
+fn run(runtime: &InstanceRuntime, instance_id: &str, command: &str) -> Result<(), String> {
+    console::execute(runtime, instance_id, command, |_| true, ACTION_TIMEOUT)?
+        .ok_or("O servidor não está em execução.")?;
+    println!("👢 [{}] {}", instance_id, command);
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Team {
+    T,
+    Ct,
+    Spec,
+}
+
+impl Team {
+    fn arg(&self) -> &'static str {
+        match self {
+            Self::T => "T",
+            Self::Ct => "CT",
+            Self::Spec => "SPEC",
+        }
+    }
+}
+
+impl FromStr for Team {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "t" | "tr" => Ok(Self::T),
+            "ct" => Ok(Self::Ct),
+            "spec" | "spectator" => Ok(Self::Spec),
+            _ => Err(format!("Time inválido: '{}'. Use t, ct ou spec.", value)),
+        }
+    }
+}
+
+// Pairs a userid with whoever holds it right now, for callers that only know
+// the number (e.g. the CLI).
+pub fn current_target(
+    runtime: &InstanceRuntime,
+    instance_id: &str,
+    userid: u32,
+) -> Result<PlayerTarget, String> {
+    players(runtime, instance_id)?
+        .into_iter()
+        .find(|p| p.userid == userid)
+        .map(|p| PlayerTarget {
+            userid,
+            unique_id: p.unique_id,
+        })
+        .ok_or_else(|| format!("Nenhum jogador com userid #{} no servidor.", userid))
+}
+
+pub fn kick(
+    runtime: &InstanceRuntime,
+    instance_id: &str,
+    target: &PlayerTarget,
+    reason: Option<&str>,
+) -> Result<StatusPlayer, String> {
+    let reason = reason
+        .filter(|r| !r.trim().is_empty())
+        .map(quoted)
+        .transpose()?;
+    let player = resolve(runtime, instance_id, target)?;
+    let command = match reason {
+        Some(reason) => format!("kick #{} {}", player.userid, reason),
+        None => format!("kick #{}", player.userid),
+    };
+    run(runtime, instance_id, &command)?;
+    Ok(player)
+}
+
+// Bans by SteamID when the player has a real one, otherwise by IP.
+pub fn ban_player_target(
+    runtime: &InstanceRuntime,
+    instance_id: &str,
+    target: &PlayerTarget,
+    minutes: u32,
+    reason: Option<String>,
+    banned_by: Option<String>,
+) -> Result<BanRecord, String> {
+    let player = resolve(runtime, instance_id, target)?;
+    if player.is_bot() {
+        return Err("Bots e HLTV não podem ser banidos.".into());
+    }
+
+    let (ban_target, by_ip) = match (player.steam_id(), player.ip()) {
+        (Some(steam_id), _) => (steam_id.to_string(), false),
+        (None, Some(ip)) => (ip.to_string(), true),
+        (None, None) => {
+            return Err(format!(
+                "{} não tem SteamID nem IP que possam ser banidos.",
+                player.name
+            ))
+        }
+    };
+
+    let record = ban(
+        runtime,
+        instance_id,
+        NewBan {
+            target: ban_target,
+            minutes,
+            reason,
+            banned_by,
+        },
+    )?;
+
+    // banid kicks on its own; addip only blocks new connections.
+    if by_ip {
+        run(runtime, instance_id, &format!("kick #{}", player.userid))?;
+    }
+    Ok(record)
+}
+
+// Needs AMX Mod X (amx_slay lives in admincmd.amxx).
+pub fn slay(
+    runtime: &InstanceRuntime,
+    instance_id: &str,
+    target: &PlayerTarget,
+) -> Result<StatusPlayer, String> {
+    let player = resolve(runtime, instance_id, target)?;
+    run(
+        runtime,
+        instance_id,
+        &format!("amx_slay #{}", player.userid),
+    )?;
+    Ok(player)
+}
+
+// The engine has no team switch; this uses amx_team from the amx_super plugin.
+pub fn move_team(
+    runtime: &InstanceRuntime,
+    instance_id: &str,
+    target: &PlayerTarget,
+    team: Team,
+) -> Result<StatusPlayer, String> {
+    let player = resolve(runtime, instance_id, target)?;
+    run(
+        runtime,
+        instance_id,
+        &format!("amx_team #{} {}", player.userid, team.arg()),
+    )?;
+    Ok(player)
+}
+
+#[tauri::command]
+pub async fn list_players(
+    state: State<'_, HldsState>,
+    instance_id: Option<String>,
+) -> Result<Vec<StatusPlayer>, String> {
+    let instance_id = resolve_instance_id(instance_id);
+    let runtime = state.runtime(&instance_id);
+    tauri::async_runtime::spawn_blocking(move || players(&runtime, &instance_id))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub async fn kick_player(
+    state: State<'_, HldsState>,
+    target: PlayerTarget,
+    reason: Option<String>,
+    instance_id: Option<String>,
+) -> Result<StatusPlayer, String> {
+    let instance_id = resolve_instance_id(instance_id);
+    let runtime = state.runtime(&instance_id);
+    tauri::async_runtime::spawn_blocking(move || {
+        kick(&runtime, &instance_id, &target, reason.as_deref())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub async fn ban_player(
+    state: State<'_, HldsState>,
+    target: PlayerTarget,
+    minutes: u32,
+    reason: Option<String>,
+    banned_by: Option<String>,
+    instance_id: Option<String>,
+) -> Result<BanRecord, String> {
+    let instance_id = resolve_instance_id(instance_id);
+    let runtime = state.runtime(&instance_id);
+    tauri::async_runtime::spawn_blocking(move || {
+        ban_player_target(&runtime, &instance_id, &target, minutes, reason, banned_by)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub async fn slay_player(
+    state: State<'_, HldsState>,
+    target: PlayerTarget,
+    instance_id: Option<String>,
+) -> Result<StatusPlayer, String> {
+    let instance_id = resolve_instance_id(instance_id);
+    let runtime = state.runtime(&instance_id);
+    tauri::async_runtime::spawn_blocking(move || slay(&runtime, &instance_id, &target))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub async fn move_player_team(
+    state: State<'_, HldsState>,
+    target: PlayerTarget,
+    team: Team,
+    instance_id: Option<String>,
+) -> Result<StatusPlayer, String> {
+    let instance_id = resolve_instance_id(instance_id);
+    let runtime = state.runtime(&instance_id);
+    tauri::async_runtime::spawn_blocking(move || move_team(&runtime, &instance_id, &target, team))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STATUS: &str = "hostname:  Canalhas
+version :  48/1.1.2.7/Stdio 8684 secure  (10)
+tcp/ip  :  192.168.0.2:27015
+map     :  de_dust2 at: 0 x, 0 y, 0 z
+players :  4 active (32 max)
+
+#      name userid uniqueid frag time ping loss adr
+# 1 \"Fulano\" 1 STEAM_0:1:12345   3 12:34   45    0 192.168.0.5:27005
+# 2 \"Bot Joao\" 2 BOT   0 12:30    0    0
+# 3 \"HLTV Proxy\" 3 HLTV hltv:1/128 delay:30 45:10    0    0 192.168.0.9:27020
+#10 \"o \"mito\" [BR]\" 10 STEAM_0:0:2  -1  1:05:12   80    2 10.0.0.3:27005
+4 users
+";
+
+    #[test]
+    fn parses_status_rows() {
+        let players = parse_status(STATUS);
+        assert_eq!(players.len(), 4);
+
+        assert_eq!(
+            players[0],
+            StatusPlayer {
+                userid: 1,
+                name: "Fulano".into(),
+                unique_id: "STEAM_0:1:12345".into(),
+                frags: 3,
+                time: "12:34".into(),
+                ping: 45,
+                loss: 0,
+                address: Some("192.168.0.5:27005".into()),
+            }
+        );
+        assert_eq!(players[0].steam_id(), Some("STEAM_0:1:12345"));
+        assert_eq!(players[0].ip(), Some("192.168.0.5"));
+        assert!(!players[0].is_bot());
+
+        assert_eq!(players[1].name, "Bot Joao");
+        assert_eq!(players[1].time, "12:30");
+        assert_eq!(players[1].address, None);
+        assert!(players[1].is_bot());
+        assert_eq!(players[1].steam_id(), None);
+
+        assert_eq!(
+            players[2],
+            StatusPlayer {
+                userid: 3,
+                name: "HLTV Proxy".into(),
+                unique_id: "HLTV".into(),
+                frags: 0,
+                time: "45:10".into(),
+                ping: 0,
+                loss: 0,
+                address: Some("192.168.0.9:27020".into()),
+            }
+        );
+        assert!(players[2].is_bot());
+
+        assert_eq!(players[3].userid, 10);
+        assert_eq!(players[3].name, "o \"mito\" [BR]");
+        assert_eq!(players[3].frags, -1);
+        assert_eq!(players[3].time, "1:05:12");
+        assert_eq!(players[3].loss, 2);
+    }
+
+    #[test]
+    fn detects_end_of_status() {
+        assert!(is_status_end("4 users"));
+        assert!(is_status_end("1 user"));
+        assert!(is_status_end("  0 users  "));
+        assert!(!is_status_end("players :  4 active (32 max)"));
+        assert!(!is_status_end("4 users online"));
+        assert!(!is_status_end("users"));
+        assert!(!is_status_end(""));
+
+        assert_eq!(STATUS.lines().filter(|l| is_status_end(l)).count(), 1);
+    }
+}
@@ -0,0 +1,354 @@
+use std::{
+    collections::VecDeque,
+    process::ExitStatus,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use tauri::State;
+
+use crate::commands::event::WebhookEvent;
+use crate::commands::hlds::{spawn_hlds, HldsState, InstanceRuntime};
+use crate::commands::instances::{get_instance, resolve_instance_id};
+use crate::commands::pidfile::remove_pid_file;
+use crate::commands::shutdown::StopMethod;
+use crate::commands::sink::Sink;
+use crate::commands::webhook::emit_instance_event;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+const BACKOFF_BASE: Duration = Duration::from_secs(2);
+const BACKOFF_MAX: Duration = Duration::from_secs(60);
+const MAX_RESTARTS: usize = 5;
+const RESTART_WINDOW: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Serialize, Debug, Clone)]
+pub struct CrashRecord {
+    pub at: DateTime<Local>,
+    pub exit_code: Option<i32>,
+    pub signal: Option<i32>,
+}
+
+impl CrashRecord {
+    fn from_status(status: ExitStatus) -> Self {
+        let exit_code = status.code();
+
+        #[cfg(unix)]
+        let signal = {
+            use std::os::unix::process::ExitStatusExt;
+            // hlds_run is a shell wrapper, so a crash of hlds_linux shows up as 128 + signal.
+            status
+                .signal()
+                .or_else(|| exit_code.filter(|c| *c > 128).map(|c| c - 128))
+        };
+        #[cfg(not(unix))]
+        let signal = None;
+
+        Self {
+            at: Local::now(),
+            exit_code,
+            signal,
+        }
+    }
+
+    // Someone ran `quit` in the console or over RCON: a stop, not a crash.
+    fn is_clean_exit(&self) -> bool {
+        self.exit_code == Some(0) && self.signal.is_none()
+    }
+}
+
+fn backoff(restarts: usize) -> Duration {
+    BACKOFF_BASE
+        .saturating_mul(2u32.saturating_pow(restarts as u32))
+        .min(BACKOFF_MAX)
+}
+
+// Forgets restarts older than the window and returns how long to wait before
+// the next one, or None once the window already holds MAX_RESTARTS.
+fn next_restart(restarts: &mut VecDeque<Instant>, now: Instant) -> Option<Duration> {
+    while restarts
+        .front()
+        .is_some_and(|t| now.saturating_duration_since(*t) > RESTART_WINDOW)
+    {
+        restarts.pop_front();
+    }
+    (restarts.len() < MAX_RESTARTS).then(|| backoff(restarts.len()))
+}
+
+pub fn supervise(
+    sink: Sink,
+    instance_id: String,
+    runtime: Arc<InstanceRuntime>,
+    running_flag: Arc<AtomicBool>,
+) {
+    thread::spawn(move || {
+        let mut restarts: VecDeque<Instant> = VecDeque::new();
+
+        loop {
+            thread::sleep(POLL_INTERVAL);
+
+            // Cleared by stop_process before it starts shutting the server down.
+            if !running_flag.load(Ordering::Relaxed) {
+                return;
+            }
+
+            let status = {
+                let mut guard = runtime.process.lock().unwrap();
+                match guard.as_mut() {
+                    Some((child, _, flag)) if Arc::ptr_eq(flag, &running_flag) => {
+                        match child.try_wait() {
+                            Ok(Some(status)) => status,
+                            _ => continue,
+                        }
+                    }
+                    _ => return,
+                }
+            };
+
+            // A stop requested while we waited for the lock is not a crash.
+            if !running_flag.load(Ordering::Relaxed) {
+                return;
+            }
+
+            let record = CrashRecord::from_status(status);
+
+            if record.is_clean_exit() {
+                stopped(&sink, &instance_id, &runtime, &running_flag);
+                return;
+            }
+
+            println!(
+                "💥 HLDS [{}] caiu (código {:?}, sinal {:?})",
+                instance_id, record.exit_code, record.signal
+            );
+            runtime.crashes.lock().unwrap().push(record.clone());
+            sink.emit(
+                "hlds-crash",
+                serde_json::json!({ "instance": instance_id, "crash": record }),
+            );
+
+            let id = instance_id.clone();
+            tauri::async_runtime::spawn(async move {
+                emit_instance_event(
+                    id,
+                    WebhookEvent::ServerCrashed {
+                        exit_code: record.exit_code,
+                        signal: record.signal,
+                    },
+                )
+                .await;
+            });
+
+            if !restart(&sink, &instance_id, &runtime, &running_flag, &mut restarts) {
+                return;
+            }
+        }
+    });
+}
+
+fn stopped(
+    sink: &Sink,
+    instance_id: &str,
+    runtime: &InstanceRuntime,
+    running_flag: &Arc<AtomicBool>,
+) {
+    let entry = {
+        let mut guard = runtime.process.lock().unwrap();
+        match guard.as_ref() {
+            Some((_, _, flag)) if Arc::ptr_eq(flag, running_flag) => guard.take(),
+            _ => None,
+        }
+    };
+    running_flag.store(false, Ordering::Relaxed);
+    remove_pid_file(instance_id);
+    if let Some((_, handle, _)) = entry {
+        thread::spawn(move || {
+            let _ = handle.join();
+        });
+    }
+
+    println!("🛑 HLDS [{}] encerrado pelo próprio servidor", instance_id);
+    sink.emit(
+        "hlds-stopped",
+        serde_json::json!({ "instance": instance_id, "method": StopMethod::Quit }),
+    );
+
+    let id = instance_id.to_string();
+    tauri::async_runtime::spawn(async move {
+        emit_instance_event(id, WebhookEvent::ServerStopped(StopMethod::Quit)).await;
+    });
+}
+
+fn restart(
+    sink: &Sink,
+    instance_id: &str,
+    runtime: &Arc<InstanceRuntime>,
+    running_flag: &Arc<AtomicBool>,
+    restarts: &mut VecDeque<Instant>,
+) -> bool {
+    loop {
+        let Some(delay) = next_restart(restarts, Instant::now()) else {
+            println!(
+                "🛑 HLDS [{}] reiniciado {} vezes em {} minutos, desistindo",
+                instance_id,
+                restarts.len(),
+                RESTART_WINDOW.as_secs() / 60
+            );
+            running_flag.store(false, Ordering::Relaxed);
+            remove_pid_file(instance_id);
+
+            let count = restarts.len() as u32;
+            let id = instance_id.to_string();
+            tauri::async_runtime::spawn(async move {
+                emit_instance_event(id, WebhookEvent::RestartGaveUp { restarts: count }).await;
+            });
+            return false;
+        };
+
+        println!(
+            "⏳ Reiniciando HLDS [{}] em {}s",
+            instance_id,
+            delay.as_secs()
+        );
+        thread::sleep(delay);
+
+        // Re-read the instance so config edits made after the crash are picked up.
+        let instance = match get_instance(Some(instance_id)) {
+            Ok(instance) => instance,
+            Err(e) => {
+                println!("❌ Falha ao reiniciar HLDS [{}]: {}", instance_id, e);
+                running_flag.store(false, Ordering::Relaxed);
+                remove_pid_file(instance_id);
+                return false;
+            }
+        };
+
+        let mut guard = runtime.process.lock().unwrap();
+        let still_ours = matches!(
+            guard.as_ref(),
+            Some((_, _, flag)) if Arc::ptr_eq(flag, running_flag)
+        );
+        if !still_ours || !running_flag.load(Ordering::Relaxed) {
+            return false;
+        }
+
+        restarts.push_back(Instant::now());
+        let attempt = restarts.len() as u32;
+
+        match spawn_hlds(sink, &instance, runtime.clone()) {
+            Ok((child, handle)) => {
+                if let Some((_, old_handle, _)) =
+                    guard.replace((child, handle, running_flag.clone()))
+                {
+                    thread::spawn(move || {
+                        let _ = old_handle.join();
+                    });
+                }
+
+                println!(
+                    "🔁 HLDS [{}] reiniciado (tentativa {})",
+                    instance_id, attempt
+                );
+                let id = instance_id.to_string();
+                tauri::async_runtime::spawn(async move {
+                    emit_instance_event(id, WebhookEvent::ServerRestarted { attempt }).await;
+                });
+                return true;
+            }
+            Err(e) => println!("❌ Falha ao reiniciar HLDS [{}]: {}", instance_id, e),
+        }
+    }
+}
+
+#[tauri::command]
+pub fn get_crash_history(state: State<HldsState>, instance_id: Option<String>) -> Vec<CrashRecord> {
+    crash_history(&state, &resolve_instance_id(instance_id))
+}
+
+pub fn crash_history(state: &HldsState, instance_id: &str) -> Vec<CrashRecord> {
+    let runtime = state.runtime(instance_id);
+    let crashes = runtime.crashes.lock().unwrap().clone();
+    crashes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINUTE: Duration = Duration::from_secs(60);
+
+    #[cfg(unix)]
+    fn record(raw: i32) -> CrashRecord {
+        use std::os::unix::process::ExitStatusExt;
+        CrashRecord::from_status(ExitStatus::from_raw(raw))
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn exit_status_is_decoded() {
+        // Wait statuses: the exit code sits in the second byte, a signal in the first.
+        let quit = record(0);
+        assert!(quit.is_clean_exit());
+
+        let error = record(1 << 8);
+        assert_eq!((error.exit_code, error.signal), (Some(1), None));
+        assert!(!error.is_clean_exit());
+
+        // hlds_run reporting a segfault of hlds_linux.
+        let wrapped = record(139 << 8);
+        assert_eq!((wrapped.exit_code, wrapped.signal), (Some(139), Some(11)));
+        assert!(!wrapped.is_clean_exit());
+
+        let killed = record(9);
+        assert_eq!((killed.exit_code, killed.signal), (None, Some(9)));
+        assert!(!killed.is_clean_exit());
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_a_minute() {
+        let secs: Vec<u64> = (0..8).map(|n| backoff(n).as_secs()).collect();
+        assert_eq!(secs, [2, 4, 8, 16, 32, 60, 60, 60]);
+        assert_eq!(backoff(usize::MAX), BACKOFF_MAX);
+    }
+
+    #[test]
+    fn sixth_crash_in_the_window_gives_up() {
+        let start = Instant::now();
+        let mut restarts = VecDeque::new();
+        let mut delays = vec![];
+
+        for crash in 0..MAX_RESTARTS as u32 {
+            let now = start + MINUTE * crash;
+            let delay = next_restart(&mut restarts, now).unwrap();
+            delays.push(delay.as_secs());
+            restarts.push_back(now + delay);
+        }
+        assert_eq!(delays, [2, 4, 8, 16, 32]);
+
+        assert_eq!(next_restart(&mut restarts, start + MINUTE * 5), None);
+        assert_eq!(restarts.len(), MAX_RESTARTS);
+    }
+
+    #[test]
+    fn restarts_leave_the_window_after_ten_minutes() {
+        let start = Instant::now();
+        let mut restarts: VecDeque<Instant> = (0..MAX_RESTARTS as u32)
+            .map(|n| start + MINUTE * n)
+            .collect();
+        assert_eq!(next_restart(&mut restarts, start + MINUTE * 9), None);
+
+        // The first restart is now just over ten minutes old.
+        let now = start + RESTART_WINDOW + Duration::from_secs(1);
+        assert_eq!(next_restart(&mut restarts, now), Some(backoff(4)));
+        assert_eq!(restarts.len(), MAX_RESTARTS - 1);
+
+        let later = start + MINUTE * 30;
+        assert_eq!(next_restart(&mut restarts, later), Some(BACKOFF_BASE));
+        assert!(restarts.is_empty());
+    }
+}
@@ -1,13 +1,19 @@
-use std::fs::{self, File};
-use std::io::{BufRead, BufReader, Write};
-use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
 
 use chrono::Local;
 use serde::{Deserialize, Serialize};
 use tauri::State;
 
+use crate::commands::admin_backend::{open_backend, AdminBackend, AdminBackendKind};
+use crate::commands::amxx_flags::validate_entry;
+use crate::commands::console;
 use crate::commands::event::WebhookEvent;
-use crate::commands::webhook::emit_event;
+use crate::commands::hlds::{HldsState, InstanceRuntime};
+use crate::commands::instances::{get_instance, resolve_instance_id, update_instance};
+use crate::commands::webhook::emit_instance_event;
+
+const RELOAD_TIMEOUT: Duration = Duration::from_secs(5);
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AdminEntry {
@@ -18,199 +24,237 @@ pub struct AdminEntry {
     pub flags: String,
 }
 
-pub struct HldsPaths {
-    pub hlds_path: PathBuf,
+// Whether an edit to users.ini is already in effect on the server.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum AdminReload {
+    Live { admins: u32 },
+    NotRunning,
+    Unconfirmed { reason: String },
+}
+
+// Backends do blocking file or database I/O, so keep them off the async runtime.
+async fn with_backend<T: Send + 'static>(
+    instance_id: String,
+    f: impl FnOnce(&mut dyn AdminBackend) -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut backend = open_backend(&get_instance(Some(&instance_id))?)?;
+        f(backend.as_mut())
+    })
+    .await
+    .map_err(|e| e.to_string())?
 }
 
 #[tauri::command]
-pub fn list_admins(state: State<HldsPaths>) -> Result<Vec<AdminEntry>, String> {
-    let path = state
-        .hlds_path
-        .join("cstrike/addons/amxmodx/configs/users.ini");
-    let file = File::open(&path).map_err(|e| e.to_string())?;
-    let reader = BufReader::new(file);
-
-    let mut entries = vec![];
-    let mut current_name: Option<String> = None;
-
-    for line in reader.lines().flatten() {
-        let trimmed = line.trim();
-
-        if trimmed.starts_with("#[NOME:") {
-            if let Some(name) = trimmed
-                .strip_prefix("#[NOME:")
-                .and_then(|s| s.strip_suffix("]"))
-            {
-                current_name = Some(name.trim().to_string());
-            }
-        } else if trimmed.starts_with('"') {
-            let parts: Vec<&str> = trimmed.split_whitespace().collect();
-            if parts.len() >= 4 {
-                let auth = parts[0].trim_matches('"').to_string();
-                let password = parts[1].trim_matches('"').to_string();
-                let access = parts[2].trim_matches('"').to_string();
-                let flags = parts[3].trim_matches('"').to_string();
-
-                entries.push(AdminEntry {
-                    name: current_name.clone(),
-                    auth,
-                    password,
-                    access,
-                    flags,
-                });
-
-                current_name = None;
-            }
-        }
-    }
+pub async fn list_admins(instance_id: Option<String>) -> Result<Vec<AdminEntry>, String> {
+    with_backend(resolve_instance_id(instance_id), |backend| backend.list()).await
+}
 
-    Ok(entries)
+// admin.sma answers with "[AMXX] Loaded 3 admins from file", or "from database"
+// with admin_sql; "admin" when there is exactly one.
+fn loaded_admins(line: &str) -> Option<u32> {
+    let (_, rest) = line.split_once("[AMXX] Loaded ")?;
+    let (count, rest) = rest.split_once(' ')?;
+    let source = rest
+        .strip_prefix("admins from ")
+        .or_else(|| rest.strip_prefix("admin from "))?;
+    if !matches!(source.trim_end(), "file" | "database") {
+        return None;
+    }
+    count.parse().ok()
 }
 
-#[tauri::command]
-pub async fn add_admin(state: State<'_, HldsPaths>, entry: AdminEntry) -> Result<(), String> {
-    let path = state
-        .hlds_path
-        .join("cstrike/addons/amxmodx/configs/users.ini");
-    let mut file = File::options()
-        .append(true)
-        .open(&path)
-        .map_err(|e| e.to_string())?;
-
-    if let Some(name) = &entry.name {
-        writeln!(file, "#[NOME: {}]", name).map_err(|e| e.to_string())?;
+// admin.sma only reads users.ini on map change unless told to reload.
+pub async fn reload_admins(runtime: Arc<InstanceRuntime>, instance_id: String) -> AdminReload {
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        console::execute(
+            &runtime,
+            &instance_id,
+            "amx_reloadadmins",
+            |line| loaded_admins(line).is_some(),
+            RELOAD_TIMEOUT,
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())
+    .and_then(|result| result);
+
+    match result {
+        Ok(None) => AdminReload::NotRunning,
+        Ok(Some(output)) => match output.lines().find_map(loaded_admins) {
+            Some(admins) => {
+                println!("🔄 Admins recarregados ({})", admins);
+                AdminReload::Live { admins }
+            }
+            None => AdminReload::Unconfirmed {
+                reason: "O servidor não confirmou o recarregamento. A mudança vale a partir da próxima troca de mapa.".into(),
+            },
+        },
+        Err(e) => AdminReload::Unconfirmed {
+            reason: format!("Falha ao recarregar os admins: {}", e),
+        },
     }
+}
 
-    writeln!(
-        file,
-        "\"{}\" \"{}\" \"{}\" \"{}\"",
-        entry.auth, entry.password, entry.access, entry.flags
-    )
-    .map_err(|e| e.to_string())?;
+pub async fn add_admin_entry(
+    runtime: Arc<InstanceRuntime>,
+    instance_id: String,
+    entry: AdminEntry,
+) -> Result<AdminReload, String> {
+    let entry = validate_entry(&entry)?;
+    let added = entry.clone();
+    with_backend(instance_id.clone(), move |backend| backend.add(&added)).await?;
+    let reload = reload_admins(runtime, instance_id.clone()).await;
 
     let label = match &entry.name {
         Some(name) => format!("{name} ({})", entry.auth),
         None => entry.auth.clone(),
     };
 
-    emit_event(WebhookEvent::Custom {
-        title: "Novo Administrador Adicionado".into(),
-        message: format!(
-            "📥 O **\"{}\"** foi adicionado à lista de administradores.",
-            label
-        ),
-    })
+    emit_instance_event(
+        instance_id,
+        WebhookEvent::Custom {
+            title: "Novo Administrador Adicionado".into(),
+            message: format!(
+                "📥 O **\"{}\"** foi adicionado à lista de administradores.",
+                label
+            ),
+        },
+    )
     .await;
 
-    Ok(())
+    Ok(reload)
 }
 
-#[tauri::command]
-pub async fn update_admin(state: State<'_, HldsPaths>, updated: AdminEntry) -> Result<(), String> {
-    let path = state
-        .hlds_path
-        .join("cstrike/addons/amxmodx/configs/users.ini");
-    let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
-
-    let mut lines: Vec<&str> = vec![];
-    let mut skip_next = false;
-
-    for line in content.lines() {
-        if skip_next {
-            skip_next = false;
-            continue;
-        }
-
-        if line.trim().starts_with('"') && line.contains(&updated.auth) {
-            if let Some(prev) = lines.last() {
-                if prev.trim().starts_with("#[NOME:") {
-                    lines.pop();
-                }
-            }
-
-            if let Some(name) = &updated.name {
-                lines.push(Box::leak(format!("#[NOME: {}]", name).into_boxed_str()));
-            }
-
-            lines.push(Box::leak(
-                format!(
-                    "\"{}\" \"{}\" \"{}\" \"{}\"",
-                    updated.auth, updated.password, updated.access, updated.flags
-                )
-                .into_boxed_str(),
-            ));
-
-            skip_next = true;
-        } else {
-            lines.push(line);
-        }
-    }
-
-    fs::write(&path, lines.join("\n") + "\n").map_err(|e| e.to_string())?;
+pub async fn update_admin_entry(
+    runtime: Arc<InstanceRuntime>,
+    instance_id: String,
+    updated: AdminEntry,
+) -> Result<AdminReload, String> {
+    let updated = validate_entry(&updated)?;
+    let changed = updated.clone();
+    with_backend(instance_id.clone(), move |backend| backend.update(&changed)).await?;
+    let reload = reload_admins(runtime, instance_id.clone()).await;
 
     let label = match &updated.name {
         Some(name) => format!("{name} ({})", updated.auth),
         None => updated.auth.clone(),
     };
 
-    emit_event(WebhookEvent::Custom {
-        title: "Administrador Atualizado".into(),
-        message: format!("🛠️ O **\"{}\"** teve seus dados atualizados.", label),
-    })
+    emit_instance_event(
+        instance_id,
+        WebhookEvent::Custom {
+            title: "Administrador Atualizado".into(),
+            message: format!("🛠️ O **\"{}\"** teve seus dados atualizados.", label),
+        },
+    )
     .await;
 
-    Ok(())
+    Ok(reload)
 }
 
-#[tauri::command]
-pub async fn remove_admin(state: State<'_, HldsPaths>, auth: String) -> Result<(), String> {
-    let path = state
-        .hlds_path
-        .join("cstrike/addons/amxmodx/configs/users.ini");
-    let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
-
-    let mut lines: Vec<&str> = vec![];
-    let mut skip_next = false;
-    let mut name = None;
-
-    for line in content.lines() {
-        if skip_next {
-            skip_next = false;
-            continue;
-        }
-
-        if line.trim().starts_with('"') && line.contains(&auth) {
-            if let Some(prev) = lines.last() {
-                if prev.trim().starts_with("#[NOME:") {
-                    name = prev
-                        .trim()
-                        .strip_prefix("#[NOME:")
-                        .and_then(|s| s.strip_suffix("]"))
-                        .map(str::to_string);
-                    lines.pop();
-                }
-            }
-            continue;
-        }
-
-        lines.push(line);
-    }
-
-    fs::write(&path, lines.join("\n") + "\n").map_err(|e| e.to_string())?;
+pub async fn remove_admin_entry(
+    runtime: Arc<InstanceRuntime>,
+    instance_id: String,
+    auth: String,
+) -> Result<AdminReload, String> {
+    let removed = auth.clone();
+    let name = with_backend(instance_id.clone(), move |backend| backend.remove(&removed)).await?;
+    let reload = reload_admins(runtime, instance_id.clone()).await;
 
     let label = match name {
         Some(n) => format!("{n} ({})", auth),
         None => auth.clone(),
     };
 
-    emit_event(WebhookEvent::Custom {
-        title: "Administrador Removido".into(),
-        message: format!(
-            "❌ O **\"{}\"** foi removido da lista de administradores.",
-            label
-        ),
-    })
+    emit_instance_event(
+        instance_id,
+        WebhookEvent::Custom {
+            title: "Administrador Removido".into(),
+            message: format!(
+                "❌ O **\"{}\"** foi removido da lista de administradores.",
+                label
+            ),
+        },
+    )
     .await;
 
-    Ok(())
+    Ok(reload)
+}
+
+#[tauri::command]
+pub async fn add_admin(
+    state: State<'_, HldsState>,
+    entry: AdminEntry,
+    instance_id: Option<String>,
+) -> Result<AdminReload, String> {
+    let instance_id = resolve_instance_id(instance_id);
+    add_admin_entry(state.runtime(&instance_id), instance_id, entry).await
+}
+
+#[tauri::command]
+pub async fn update_admin(
+    state: State<'_, HldsState>,
+    updated: AdminEntry,
+    instance_id: Option<String>,
+) -> Result<AdminReload, String> {
+    let instance_id = resolve_instance_id(instance_id);
+    update_admin_entry(state.runtime(&instance_id), instance_id, updated).await
+}
+
+#[tauri::command]
+pub async fn remove_admin(
+    state: State<'_, HldsState>,
+    auth: String,
+    instance_id: Option<String>,
+) -> Result<AdminReload, String> {
+    let instance_id = resolve_instance_id(instance_id);
+    remove_admin_entry(state.runtime(&instance_id), instance_id, auth).await
+}
+
+#[tauri::command]
+pub fn get_admin_backend(instance_id: Option<String>) -> Result<AdminBackendKind, String> {
+    Ok(get_instance(instance_id.as_deref())?.admin_backend)
+}
+
+#[tauri::command]
+pub fn set_admin_backend(
+    backend: AdminBackendKind,
+    instance_id: Option<String>,
+) -> Result<(), String> {
+    update_instance(&resolve_instance_id(instance_id), |instance| {
+        instance.admin_backend = backend
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reload_is_confirmed_by_the_loaded_line() {
+        assert_eq!(loaded_admins("[AMXX] Loaded 3 admins from file"), Some(3));
+        assert_eq!(loaded_admins("[AMXX] Loaded 1 admin from file"), Some(1));
+        assert_eq!(loaded_admins("[AMXX] Loaded 0 admins from file"), Some(0));
+        assert_eq!(
+            loaded_admins("[AMXX] Loaded 12 admins from database"),
+            Some(12)
+        );
+        assert_eq!(loaded_admins("[AMXX] Loaded 3 admins from file\r"), Some(3));
+    }
+
+    #[test]
+    fn other_amxx_lines_are_ignored() {
+        for line in [
+            "[AMXX] Admin Base 1.9.0.5294 by AMXX Dev Team",
+            "[AMXX] Plugin \"admin.amxx\" failed to load: Module/Library \"mysql\" required",
+            "[AMXX] SQL error: can't connect: 'Can't connect to MySQL server on '10.0.0.1' (111)'",
+            "[AMXX] Loaded 1.9 admins from file",
+            "[AMXX] Loaded 3 maps from file",
+            "Loaded 3 admins from file",
+        ] {
+            assert_eq!(loaded_admins(line), None, "{}", line);
+        }
+    }
 }
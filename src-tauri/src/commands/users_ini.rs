@@ -0,0 +1,437 @@
+use std::{
+    fs::{self, File},
+    io::Write,
+    path::Path,
+};
+
+use crate::commands::users::AdminEntry;
+
+const NAME_PREFIX: &str = "#[NOME:";
+
+// One line of users.ini. Lines we do not touch keep their original text, so
+// comments, spacing and unknown syntax survive a rewrite untouched.
+#[derive(Debug, Clone, PartialEq)]
+enum Line {
+    Name {
+        name: String,
+        raw: Option<String>,
+    },
+    Entry {
+        entry: IniEntry,
+        raw: Option<String>,
+    },
+    Other(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct IniEntry {
+    auth: String,
+    password: String,
+    access: String,
+    flags: String,
+    // Anything after the fourth field, usually an inline comment.
+    trailing: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct UsersIni {
+    lines: Vec<Line>,
+    crlf: bool,
+    final_newline: bool,
+}
+
+impl UsersIni {
+    pub fn parse(text: &str) -> Self {
+        let crlf = text.contains("\r\n");
+        let final_newline = text.is_empty() || text.ends_with('\n');
+
+        let lines = text
+            .lines()
+            .map(|raw| {
+                let raw = raw.strip_suffix('\r').unwrap_or(raw);
+                parse_line(raw).unwrap_or_else(|| Line::Other(raw.to_string()))
+            })
+            .collect();
+
+        Self {
+            lines,
+            crlf,
+            final_newline,
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        fs::read_to_string(path)
+            .map(|text| Self::parse(&text))
+            .map_err(|e| format!("Erro ao ler {}: {}", path.display(), e))
+    }
+
+    pub fn render(&self) -> String {
+        let newline = if self.crlf { "\r\n" } else { "\n" };
+        let mut out = self
+            .lines
+            .iter()
+            .map(render_line)
+            .collect::<Vec<_>>()
+            .join(newline);
+
+        if self.final_newline && !self.lines.is_empty() {
+            out.push_str(newline);
+        }
+        out
+    }
+
+    // Write to a sibling temp file and rename it over the original, so a crash
+    // mid-write never leaves AMXX with a truncated users.ini.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let tmp = path.with_extension("ini.tmp");
+
+        let result = File::create(&tmp)
+            .and_then(|mut file| {
+                file.write_all(self.render().as_bytes())?;
+                file.sync_all()
+            })
+            .and_then(|_| fs::rename(&tmp, path));
+
+        result.map_err(|e| {
+            let _ = fs::remove_file(&tmp);
+            format!("Erro ao gravar {}: {}", path.display(), e)
+        })
+    }
+
+    pub fn entries(&self) -> Vec<AdminEntry> {
+        self.lines
+            .iter()
+            .enumerate()
+            .filter_map(|(i, line)| match line {
+                Line::Entry { entry, .. } => Some(AdminEntry {
+                    name: self.name_for(i),
+                    auth: entry.auth.clone(),
+                    password: entry.password.clone(),
+                    access: entry.access.clone(),
+                    flags: entry.flags.clone(),
+                }),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn find(&self, auth: &str) -> Option<usize> {
+        self.lines
+            .iter()
+            .position(|line| matches!(line, Line::Entry { entry, .. } if entry.auth == auth))
+    }
+
+    // `#[NOME: ...]` annotates the entry right below it.
+    fn name_for(&self, index: usize) -> Option<String> {
+        match index.checked_sub(1).and_then(|i| self.lines.get(i)) {
+            Some(Line::Name { name, .. }) => Some(name.clone()),
+            _ => None,
+        }
+    }
+
+    fn has_name_line(&self, index: usize) -> bool {
+        self.name_for(index).is_some()
+    }
+
+    pub fn add(&mut self, admin: &AdminEntry) -> Result<(), String> {
+        validate(admin)?;
+        if self.find(&admin.auth).is_some() {
+            return Err(format!(
+                "Já existe um administrador com o auth '{}'.",
+                admin.auth
+            ));
+        }
+
+        if let Some(name) = &admin.name {
+            self.lines.push(Line::Name {
+                name: name.clone(),
+                raw: None,
+            });
+        }
+        self.lines.push(Line::Entry {
+            entry: IniEntry::from_admin(admin, String::new()),
+            raw: None,
+        });
+        Ok(())
+    }
+
+    pub fn update(&mut self, admin: &AdminEntry) -> Result<(), String> {
+        validate(admin)?;
+        let index = self
+            .find(&admin.auth)
+            .ok_or_else(|| not_found(&admin.auth))?;
+
+        let trailing = match &self.lines[index] {
+            Line::Entry { entry, .. } => entry.trailing.clone(),
+            _ => String::new(),
+        };
+        let unchanged_entry = matches!(
+            &self.lines[index],
+            Line::Entry { entry, .. } if *entry == IniEntry::from_admin(admin, trailing.clone())
+        );
+        if !unchanged_entry {
+            self.lines[index] = Line::Entry {
+                entry: IniEntry::from_admin(admin, trailing),
+                raw: None,
+            };
+        }
+
+        let had_name = self.has_name_line(index);
+        match (&admin.name, had_name) {
+            (Some(name), true) => {
+                if self.name_for(index).as_ref() != Some(name) {
+                    self.lines[index - 1] = Line::Name {
+                        name: name.clone(),
+                        raw: None,
+                    };
+                }
+            }
+            (Some(name), false) => self.lines.insert(
+                index,
+                Line::Name {
+                    name: name.clone(),
+                    raw: None,
+                },
+            ),
+            (None, true) => {
+                self.lines.remove(index - 1);
+            }
+            (None, false) => {}
+        }
+        Ok(())
+    }
+
+    // Returns the `#[NOME: ...]` of the removed entry, if it had one.
+    pub fn remove(&mut self, auth: &str) -> Result<Option<String>, String> {
+        let index = self.find(auth).ok_or_else(|| not_found(auth))?;
+        let name = self.name_for(index);
+
+        self.lines.remove(index);
+        if name.is_some() {
+            self.lines.remove(index - 1);
+        }
+        Ok(name)
+    }
+}
+
+impl IniEntry {
+    fn from_admin(admin: &AdminEntry, trailing: String) -> Self {
+        Self {
+            auth: admin.auth.clone(),
+            password: admin.password.clone(),
+            access: admin.access.clone(),
+            flags: admin.flags.clone(),
+            trailing,
+        }
+    }
+}
+
+fn not_found(auth: &str) -> String {
+    format!("Administrador '{}' não encontrado.", auth)
+}
+
+// users.ini has no escaping, so a quote or line break would corrupt the file.
+fn validate(admin: &AdminEntry) -> Result<(), String> {
+    if admin.auth.trim().is_empty() {
+        return Err("O auth do administrador não pode ser vazio.".into());
+    }
+
+    let fields = [
+        ("auth", admin.auth.as_str()),
+        ("senha", admin.password.as_str()),
+        ("acesso", admin.access.as_str()),
+        ("flags", admin.flags.as_str()),
+        ("nome", admin.name.as_deref().unwrap_or("")),
+    ];
+    for (label, value) in fields {
+        if value.contains(['"', '\n', '\r']) {
+            return Err(format!(
+                "O campo {} não pode conter aspas ou quebras de linha.",
+                label
+            ));
+        }
+    }
+
+    if admin.name.as_deref().is_some_and(|n| n.contains(']')) {
+        return Err("O nome não pode conter ']'.".into());
+    }
+    Ok(())
+}
+
+fn parse_line(raw: &str) -> Option<Line> {
+    let trimmed = raw.trim();
+
+    if let Some(name) = trimmed
+        .strip_prefix(NAME_PREFIX)
+        .and_then(|s| s.strip_suffix(']'))
+    {
+        return Some(Line::Name {
+            name: name.trim().to_string(),
+            raw: Some(raw.to_string()),
+        });
+    }
+
+    if trimmed.is_empty() || trimmed.starts_with([';', '#']) || trimmed.starts_with("//") {
+        return None;
+    }
+
+    let (fields, trailing) = tokenize(trimmed, 4);
+    let [auth, password, access, flags]: [String; 4] = fields.try_into().ok()?;
+
+    Some(Line::Entry {
+        entry: IniEntry {
+            auth,
+            password,
+            access,
+            flags,
+            trailing: trailing.to_string(),
+        },
+        raw: Some(raw.to_string()),
+    })
+}
+
+// Same rules as AMXX's parse(): a token is either "quoted" or runs until whitespace.
+fn tokenize(line: &str, max: usize) -> (Vec<String>, &str) {
+    let mut tokens = vec![];
+    let mut rest = line;
+
+    while tokens.len() < max {
+        rest = rest.trim_start();
+        if rest.is_empty() || rest.starts_with(';') || rest.starts_with("//") {
+            break;
+        }
+
+        if let Some(inner) = rest.strip_prefix('"') {
+            let end = inner.find('"').unwrap_or(inner.len());
+            tokens.push(inner[..end].to_string());
+            rest = inner.get(end + 1..).unwrap_or("");
+        } else {
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            tokens.push(rest[..end].to_string());
+            rest = &rest[end..];
+        }
+    }
+
+    (tokens, rest.trim())
+}
+
+fn render_line(line: &Line) -> String {
+    match line {
+        Line::Other(raw)
+        | Line::Name { raw: Some(raw), .. }
+        | Line::Entry { raw: Some(raw), .. } => raw.clone(),
+        Line::Name { name, raw: None } => format!("{} {}]", NAME_PREFIX, name),
+        Line::Entry { entry, raw: None } => {
+            let mut out = format!(
+                "\"{}\" \"{}\" \"{}\" \"{}\"",
+                entry.auth, entry.password, entry.access, entry.flags
+            );
+            if !entry.trailing.is_empty() {
+                out.push(' ');
+                out.push_str(&entry.trailing);
+            }
+            out
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn admin(auth: &str, name: Option<&str>) -> AdminEntry {
+        AdminEntry {
+            name: name.map(str::to_string),
+            auth: auth.into(),
+            password: "".into(),
+            access: "abcdefghijklmnopqrstu".into(),
+            flags: "ce".into(),
+        }
+    }
+
+    #[test]
+    fn round_trips_untouched_files() {
+        for text in [
+            "",
+            "; Users configuration\n\n; comentário solto\n\"STEAM_0:1:1\" \"\" \"abcdefghijklmnopqrstu\" \"ce\"\n",
+            "; Windows\r\n\r\n\"STEAM_0:1:1\" \"\" \"abc\" \"ce\" ; dono\r\n",
+            "\"STEAM_0:1:1\" \"\" \"abc\" \"ce\"",
+            "#[NOME: Fulano]\n\"STEAM_0:1:1\" \"\" \"abc\" \"ce\"\n#[NOME:Ciclano ]\n   \"STEAM_0:1:12\"   \"\"  \"z\"  \"ce\"\n",
+            "\"Fulano\" \"senha com espaço\" \"abc\" \"a\"\n\"127.0.0.1\" \"\" \"l\" \"de\"\n// fim\n",
+        ] {
+            assert_eq!(UsersIni::parse(text).render(), text);
+        }
+    }
+
+    #[test]
+    fn parses_quoted_passwords_and_names() {
+        let ini = UsersIni::parse(
+            "#[NOME: Fulano]\n\"Fulano\" \"senha com espaço\" \"abc\" \"a\" ; inline\n",
+        );
+        let entries = ini.entries();
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].name.as_deref(), Some("Fulano"));
+        assert_eq!(entries[0].password, "senha com espaço");
+        assert_eq!(entries[0].access, "abc");
+        assert_eq!(entries[0].flags, "a");
+    }
+
+    #[test]
+    fn edits_only_the_exact_auth() {
+        let text = "; admins\r\n#[NOME: Curto]\r\n\"STEAM_0:1:1\" \"\" \"abc\" \"ce\"\r\n\"STEAM_0:1:12\" \"\" \"z\" \"ce\" ; manter\r\n";
+
+        let mut ini = UsersIni::parse(text);
+        ini.update(&admin("STEAM_0:1:1", Some("Novo"))).unwrap();
+        assert_eq!(
+            ini.render(),
+            "; admins\r\n#[NOME: Novo]\r\n\"STEAM_0:1:1\" \"\" \"abcdefghijklmnopqrstu\" \"ce\"\r\n\"STEAM_0:1:12\" \"\" \"z\" \"ce\" ; manter\r\n"
+        );
+
+        let mut ini = UsersIni::parse(text);
+        assert_eq!(ini.remove("STEAM_0:1:1").unwrap().as_deref(), Some("Curto"));
+        assert_eq!(
+            ini.render(),
+            "; admins\r\n\"STEAM_0:1:12\" \"\" \"z\" \"ce\" ; manter\r\n"
+        );
+
+        assert!(ini.remove("STEAM_0:1:1").is_err());
+        assert!(ini.add(&admin("STEAM_0:1:12", None)).is_err());
+    }
+
+    #[test]
+    fn rejects_fields_that_would_corrupt_the_file() {
+        let mut ini = UsersIni::default();
+        assert!(ini.add(&admin("STEAM_0:1:1\"", None)).is_err());
+        assert!(ini.add(&admin("STEAM_0:1:1", Some("a]b"))).is_err());
+        assert!(ini.add(&admin(" ", None)).is_err());
+        assert!(ini.entries().is_empty());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn save_replaces_file_atomically() {
+        use std::os::unix::fs::MetadataExt;
+
+        let dir = std::env::temp_dir().join(format!("canalhas-users-ini-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("users.ini");
+        fs::write(&path, "; antigo\n").unwrap();
+        let before = fs::metadata(&path).unwrap().ino();
+
+        let mut ini = UsersIni::load(&path).unwrap();
+        ini.add(&admin("STEAM_0:1:1", Some("Fulano"))).unwrap();
+        ini.save(&path).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "; antigo\n#[NOME: Fulano]\n\"STEAM_0:1:1\" \"\" \"abcdefghijklmnopqrstu\" \"ce\"\n"
+        );
+        // A rename swaps in a new inode instead of truncating the old file in place.
+        assert_ne!(fs::metadata(&path).unwrap().ino(), before);
+        assert!(!path.with_extension("ini.tmp").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -6,17 +6,32 @@ use std::{
     fs::{self, File},
     io::{Read, Write},
     path::PathBuf,
-    sync::Mutex,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant, SystemTime},
 };
 
 use chrono::Utc;
 
 use crate::commands::event::WebhookEvent;
+use crate::commands::instances::get_instance;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Webhook {
     pub url: String,
     pub events: Vec<String>,
+    // Empty means the webhook receives events from every instance.
+    #[serde(default)]
+    pub instances: Vec<String>,
+}
+
+impl Webhook {
+    fn wants(&self, instance: &str, event_name: &str) -> bool {
+        (self.instances.is_empty() || self.instances.iter().any(|i| i == instance))
+            && self.events.iter().any(|e| e == event_name)
+    }
 }
 
 pub static WEBHOOKS_PATH: Lazy<PathBuf> = Lazy::new(|| {
@@ -27,12 +42,27 @@ pub static WEBHOOKS_PATH: Lazy<PathBuf> = Lazy::new(|| {
 
 pub static CLIENT: Lazy<Client> = Lazy::new(Client::new);
 pub static WEBHOOK_MUTEX: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+static PENDING: AtomicUsize = AtomicUsize::new(0);
+
+// Every log line asks whether anyone subscribed to its event, so keep the parsed
+// list in memory. It is keyed by the file's mtime to notice edits made by other
+// processes (the CLI or the daemon) and dropped on every local write.
+type CachedWebhooks = Option<(SystemTime, Vec<Webhook>)>;
+static WEBHOOKS_CACHE: Lazy<Mutex<CachedWebhooks>> = Lazy::new(|| Mutex::new(None));
 
 #[tauri::command]
-pub fn add_webhook(url: String, events: Vec<String>) -> Result<(), String> {
+pub fn add_webhook(
+    url: String,
+    events: Vec<String>,
+    instances: Option<Vec<String>>,
+) -> Result<(), String> {
     let _lock = WEBHOOK_MUTEX.lock().unwrap();
     let mut hooks = read_webhooks();
-    hooks.push(Webhook { url, events });
+    hooks.push(Webhook {
+        url,
+        events,
+        instances: instances.unwrap_or_default(),
+    });
     write_webhooks(&hooks)
 }
 
@@ -51,15 +81,39 @@ pub fn list_webhooks() -> Vec<Webhook> {
     read_webhooks()
 }
 
-pub async fn emit_event(event: WebhookEvent) {
+pub fn has_subscribers(instance: &str, event_name: &str) -> bool {
+    read_webhooks()
+        .iter()
+        .any(|h| h.wants(instance, event_name))
+}
+
+// Lets short-lived processes (the CLI) wait for webhooks still being delivered.
+pub async fn wait_for_pending(timeout: Duration) {
+    let started = Instant::now();
+    while PENDING.load(Ordering::SeqCst) > 0 && started.elapsed() < timeout {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+}
+
+pub async fn emit_instance_event(instance_id: String, event: WebhookEvent) {
+    PENDING.fetch_add(1, Ordering::SeqCst);
+    deliver(instance_id, event).await;
+    PENDING.fetch_sub(1, Ordering::SeqCst);
+}
+
+async fn deliver(instance_id: String, event: WebhookEvent) {
     let hooks = read_webhooks();
     let event_name = event.name().to_string();
 
     let matching_hooks: Vec<_> = hooks
         .into_iter()
-        .filter(|h| h.events.contains(&event_name))
+        .filter(|h| h.wants(&instance_id, &event_name))
         .collect();
 
+    if matching_hooks.is_empty() {
+        return;
+    }
+
     println!(
         "📡 Emitindo evento '{}' para {} webhook(s)",
         event_name,
@@ -68,20 +122,34 @@ pub async fn emit_event(event: WebhookEvent) {
 
     let color = match &event {
         WebhookEvent::ServerStarted => 0x57F287,
-        WebhookEvent::ServerStopped => 0xED4245,
+        WebhookEvent::ServerStopped(_) => 0xED4245,
         WebhookEvent::ErrorOccurred => 0xFF0000,
+        WebhookEvent::MissingAsset { .. } => 0xE67E22,
+        WebhookEvent::ServerCrashed { .. } => 0xFF0000,
+        WebhookEvent::ServerRestarted { .. } => 0xFEE75C,
+        WebhookEvent::RestartGaveUp { .. } => 0x992D22,
         WebhookEvent::MapAdded(_) => 0x00B0F4,
         WebhookEvent::MapDeleted(_) => 0xFAA61A,
         WebhookEvent::MapChanged(_) => 0x5865F2,
+        WebhookEvent::PlayerBanned(_) => 0xED4245,
+        WebhookEvent::BanRemoved(_) | WebhookEvent::BanExpired(_) => 0x57F287,
+        WebhookEvent::ChatFlagged(_) => 0xFEE75C,
         WebhookEvent::Custom { .. } => 0xfb7f0c,
+        WebhookEvent::Game(_) => 0x99AAB5,
     };
 
+    let server = get_instance(Some(&instance_id))
+        .map(|i| i.name)
+        .unwrap_or_else(|_| instance_id.clone());
+    let fields = serde_json::json!([{ "name": "Servidor", "value": server, "inline": true }]);
+
     let payload = serde_json::json!({
         "username": "Canalhas Manager",
         "embeds": [{
             "title": event.title(),
             "description": event.description(),
             "color": color,
+            "fields": fields,
             "author": {
                 "name": "Canalhas Manager",
                 "icon_url": "https://i.imgur.com/fKL31aD.jpg"
@@ -115,10 +183,23 @@ pub async fn emit_event(event: WebhookEvent) {
 }
 
 fn read_webhooks() -> Vec<Webhook> {
-    if !WEBHOOKS_PATH.exists() {
+    let Ok(modified) = fs::metadata(&*WEBHOOKS_PATH).and_then(|m| m.modified()) else {
         return vec![];
+    };
+
+    let mut cache = WEBHOOKS_CACHE.lock().unwrap();
+    if let Some((cached_at, hooks)) = cache.as_ref() {
+        if *cached_at == modified {
+            return hooks.clone();
+        }
     }
 
+    let hooks = load_webhooks();
+    *cache = Some((modified, hooks.clone()));
+    hooks
+}
+
+fn load_webhooks() -> Vec<Webhook> {
     let mut contents = String::new();
     if File::open(&*WEBHOOKS_PATH)
         .and_then(|mut f| f.read_to_string(&mut contents))
@@ -137,7 +218,11 @@ fn write_webhooks(hooks: &[Webhook]) -> Result<(), String> {
         fs::create_dir_all(parent).map_err(|e| e.to_string())?;
     }
 
-    File::create(&*WEBHOOKS_PATH)
+    let result = File::create(&*WEBHOOKS_PATH)
         .and_then(|mut f| f.write_all(json.as_bytes()))
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string());
+
+    // Only after the write, so a read that raced it can't keep a partial file cached.
+    *WEBHOOKS_CACHE.lock().unwrap() = None;
+    result
 }
@@ -1,34 +1,54 @@
-mod commands;
+pub mod commands;
 
-use std::path::PathBuf;
+use tauri::Manager;
 
 use commands::{
+    amxx_flags::{get_admin_flags, validate_admin_flags},
+    attach::reattach_instances,
+    bans::{add_ban, list_bans, remove_ban, start_ban_sweeper},
+    chat::{list_chat_filters, save_chat_filters, search_chat},
     greet::greet,
     hlds::{
-        get_hlds_path, get_server_config, is_hlds_running, save_hlds_path, save_server_config,
-        send_command_to_hlds, start_hlds_server, stop_hlds_server, HldsState,
+        get_hlds_path, get_hlds_status, get_server_config, is_hlds_running, save_hlds_path,
+        save_server_config, send_command_to_hlds, start_hlds_server, stop_hlds_server, HldsState,
     },
-    maps::{delete_map_files, list_maps},
+    instances::{add_instance, list_instances, remove_instance, rename_instance},
+    map_deps::{check_map_dependencies, generate_map_res},
+    map_install::{install_map_package, preview_map_package},
+    maps::{delete_map_files, list_maps, notify_map_added},
+    players::{get_player_profile, search_players},
+    ports::get_port_owners,
+    query::get_server_status,
+    rcon::send_rcon_command,
+    rotation::{generate_map_rotation, get_rotation, save_rotation},
+    sink::app_sink,
+    stats::{get_map_leaderboard, get_player_leaderboard, get_player_stats, import_stats_logs},
+    status::{ban_player, kick_player, list_players, move_player_team, slay_player},
+    supervisor::get_crash_history,
     system::get_system_stats,
-    users::{add_admin, list_admins, remove_admin, update_admin, HldsPaths},
+    users::{
+        add_admin, get_admin_backend, list_admins, remove_admin, set_admin_backend, update_admin,
+    },
     webhook::{add_webhook, list_webhooks, remove_webhook},
 };
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
-        .manage(HldsState {
-            process: std::sync::Arc::new(std::sync::Mutex::new(None)),
-        })
-        .manage(HldsPaths {
-            hlds_path: get_hlds_path()
-                .unwrap_or(None)
-                .map(PathBuf::from)
-                .unwrap_or_else(|| PathBuf::from(".")),
+        .manage(HldsState::default())
+        .setup(|app| {
+            reattach_instances(&app.state::<HldsState>(), &app_sink(app.handle().clone()));
+            let handle = app.handle().clone();
+            start_ban_sweeper(move |id| handle.state::<HldsState>().runtime(id));
+            Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             greet,
             get_system_stats,
+            list_instances,
+            add_instance,
+            rename_instance,
+            remove_instance,
             get_hlds_path,
             save_hlds_path,
             start_hlds_server,
@@ -36,8 +56,17 @@ pub fn run() {
             save_server_config,
             get_server_config,
             send_command_to_hlds,
+            send_rcon_command,
             is_hlds_running,
+            get_hlds_status,
+            get_port_owners,
+            get_crash_history,
+            get_server_status,
             list_maps,
+            get_player_leaderboard,
+            get_player_stats,
+            get_map_leaderboard,
+            import_stats_logs,
             delete_map_files,
             add_webhook,
             remove_webhook,
@@ -46,6 +75,31 @@ pub fn run() {
             add_admin,
             update_admin,
             remove_admin,
+            get_admin_flags,
+            validate_admin_flags,
+            get_admin_backend,
+            set_admin_backend,
+            list_bans,
+            add_ban,
+            remove_ban,
+            search_players,
+            get_player_profile,
+            list_players,
+            kick_player,
+            ban_player,
+            slay_player,
+            move_player_team,
+            search_chat,
+            list_chat_filters,
+            save_chat_filters,
+            check_map_dependencies,
+            generate_map_res,
+            preview_map_package,
+            install_map_package,
+            notify_map_added,
+            get_rotation,
+            save_rotation,
+            generate_map_rotation,
         ])
         .plugin(tauri_plugin_app::init())
         .plugin(tauri_plugin_dialog::init())
@@ -22,23 +22,33 @@ import {
 import { Button } from "./components/ui/button";
 import { Input } from "./components/ui/input";
 import AdminManager from "./pages/admin";
+import BansPage from "./pages/bans";
+import ChatPage from "./pages/chat";
+import RotationPage from "./pages/rotation";
 import { cn } from "./lib/utils";
+import { useInstance } from "./components/instance-provider";
 
 function App() {
   const [command, setCommand] = useState("");
   const [consoleLines, setConsoleLines] = useState<string[]>([]);
   const [hldsPath, setHldsPath] = useState<string | null>(null);
+  const { instanceId } = useInstance();
 
   const scrollRef = useRef<HTMLDivElement>(null);
 
   useEffect(() => {
     let lastLine = "";
-    const unlisten = listen<string>("hlds-log", (event) => {
-      if (event.payload !== lastLine) {
-        setConsoleLines((prev) => [...prev, event.payload]);
-        lastLine = event.payload;
+    const unlisten = listen<{ instance: string; line: string }>(
+      "hlds-log",
+      (event) => {
+        const { instance, line } = event.payload;
+        const formatted = instance === "default" ? line : `[${instance}] ${line}`;
+        if (formatted !== lastLine) {
+          setConsoleLines((prev) => [...prev, formatted]);
+          lastLine = formatted;
+        }
       }
-    });
+    );
     return () => {
       unlisten.then((f) => f());
     };
@@ -46,11 +56,13 @@ function App() {
 
   useEffect(() => {
     const checkPath = async () => {
-      const saved = await invoke<string | null>("get_hlds_path");
-      if (saved) setHldsPath(saved);
+      const saved = await invoke<string | null>("get_hlds_path", {
+        instanceId,
+      });
+      setHldsPath(saved);
     };
     checkPath();
-  }, []);
+  }, [instanceId]);
 
   useEffect(() => {
     if (scrollRef.current) {
@@ -65,7 +77,7 @@ function App() {
       title: "Selecione a pasta do HLDS",
     });
     if (typeof selected === "string") {
-      await invoke("save_hlds_path", { path: selected });
+      await invoke("save_hlds_path", { path: selected, instanceId });
       setHldsPath(selected);
     }
   };
@@ -75,7 +87,7 @@ function App() {
     const timestamp = new Date().toLocaleTimeString();
     setConsoleLines((lines) => [...lines, `[${timestamp}] > ${command}`]);
     try {
-      await invoke("send_command_to_hlds", { command });
+      await invoke("send_command_to_hlds", { command, instanceId });
     } catch (err) {
       console.error("Erro ao enviar comando:", err);
     }
@@ -150,13 +162,19 @@ function App() {
                 </div>
               </div>
 
-              <div className="w-1/2 space-y-4 p-4 overflow-auto h-full">
+              <div
+                key={instanceId}
+                className="w-1/2 space-y-4 p-4 overflow-auto h-full"
+              >
                 <Routes>
                   <Route path="/" element={<Console />} />
                   <Route path="/maps" element={<MapsPage />} />
                   <Route path="/webhooks" element={<WebhooksPage />} />
                   <Route path="/settings" element={<SettingsPage />} />
                   <Route path="/administradores" element={<AdminManager />} />
+                  <Route path="/banimentos" element={<BansPage />} />
+                  <Route path="/chat" element={<ChatPage />} />
+                  <Route path="/rotacao" element={<RotationPage />} />
                 </Routes>
               </div>
             </div>
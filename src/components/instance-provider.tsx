@@ -0,0 +1,64 @@
+"use client";
+
+import {
+  createContext,
+  useContext,
+  useEffect,
+  useState,
+  type ReactNode,
+} from "react";
+import { invoke } from "@tauri-apps/api/core";
+
+export type Instance = {
+  id: string;
+  name: string;
+};
+
+type InstanceContextValue = {
+  instanceId: string;
+  instances: Instance[];
+  setInstanceId: (id: string) => void;
+};
+
+const STORAGE_KEY = "canalhas-instance";
+
+const InstanceContext = createContext<InstanceContextValue>({
+  instanceId: "default",
+  instances: [],
+  setInstanceId: () => {},
+});
+
+// The server every page manages; remembered between sessions.
+export function InstanceProvider({ children }: { children: ReactNode }) {
+  const [instanceId, setInstanceIdState] = useState(
+    () => localStorage.getItem(STORAGE_KEY) ?? "default"
+  );
+  const [instances, setInstances] = useState<Instance[]>([]);
+
+  const setInstanceId = (id: string) => {
+    localStorage.setItem(STORAGE_KEY, id);
+    setInstanceIdState(id);
+  };
+
+  useEffect(() => {
+    invoke<Instance[]>("list_instances")
+      .then((list) => {
+        setInstances(list);
+        // The remembered instance may have been removed meanwhile.
+        if (list.length > 0 && !list.some((i) => i.id === instanceId)) {
+          setInstanceId(list[0].id);
+        }
+      })
+      .catch(() => setInstances([]));
+  }, []);
+
+  return (
+    <InstanceContext.Provider value={{ instanceId, instances, setInstanceId }}>
+      {children}
+    </InstanceContext.Provider>
+  );
+}
+
+export function useInstance() {
+  return useContext(InstanceContext);
+}
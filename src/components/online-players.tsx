@@ -0,0 +1,193 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { MoreVertical, RefreshCw, Users } from "lucide-react";
+import { toast } from "sonner";
+
+import { Card, CardContent } from "@/components/ui/card";
+import { Button } from "@/components/ui/button";
+import { Badge } from "@/components/ui/badge";
+import { useInstance } from "@/components/instance-provider";
+import {
+  DropdownMenu,
+  DropdownMenuContent,
+  DropdownMenuItem,
+  DropdownMenuTrigger,
+} from "@/components/ui/dropdown-menu";
+
+interface StatusPlayer {
+  userid: number;
+  name: string;
+  unique_id: string;
+  frags: number;
+  time: string;
+  ping: number;
+  loss: number;
+  address?: string;
+}
+
+type Team = "t" | "ct" | "spec";
+
+// `status` output lands in the console pane, so refresh on roster changes
+// instead of polling.
+const ROSTER_EVENTS = [
+  "connected",
+  "entered_game",
+  "disconnected",
+  "changed_name",
+  "joined_team",
+  "map_started",
+];
+
+export default function OnlinePlayers() {
+  const [players, setPlayers] = useState<StatusPlayer[]>([]);
+  const [offline, setOffline] = useState(false);
+  const { instanceId } = useInstance();
+
+  const loadPlayers = async () => {
+    try {
+      setPlayers(
+        await invoke<StatusPlayer[]>("list_players", { instanceId })
+      );
+      setOffline(false);
+    } catch {
+      setPlayers([]);
+      setOffline(true);
+    }
+  };
+
+  useEffect(() => {
+    loadPlayers();
+    const unlisten = listen<{ instance: string; event: { type: string } }>(
+      "hlds-event",
+      (event) => {
+        const { instance, event: game } = event.payload;
+        if (instance === instanceId && ROSTER_EVENTS.includes(game.type)) {
+          loadPlayers();
+        }
+      }
+    );
+    return () => {
+      unlisten.then((f) => f());
+    };
+  }, []);
+
+  const act = async (
+    command: string,
+    player: StatusPlayer,
+    args: Record<string, unknown>,
+    success: string
+  ) => {
+    try {
+      await invoke(command, {
+        instanceId,
+        target: { userid: player.userid, unique_id: player.unique_id },
+        ...args,
+      });
+      toast.success(success);
+    } catch (err) {
+      toast.error(String(err));
+    }
+    loadPlayers();
+  };
+
+  const kick = (player: StatusPlayer) => {
+    const reason = prompt(`Motivo do kick de ${player.name}:`);
+    if (reason === null) return;
+    act("kick_player", player, { reason }, `${player.name} expulso.`);
+  };
+
+  const ban = (player: StatusPlayer) => {
+    const minutes = prompt("Duração em minutos (0 = permanente):", "0");
+    if (minutes === null) return;
+    const reason = prompt("Motivo do banimento:") ?? "";
+    act(
+      "ban_player",
+      player,
+      { minutes: Number(minutes) || 0, reason: reason || null },
+      `${player.name} banido.`
+    );
+  };
+
+  const move = (player: StatusPlayer, team: Team) =>
+    act(
+      "move_player_team",
+      player,
+      { team },
+      `${player.name} movido para ${team.toUpperCase()}.`
+    );
+
+  return (
+    <Card>
+      <CardContent className="p-4 space-y-2 text-sm">
+        <div className="flex items-center justify-between font-semibold">
+          <div className="flex items-center gap-2">
+            <Users className="h-4 w-4" /> Jogadores online ({players.length})
+          </div>
+          <Button variant="ghost" size="icon" onClick={loadPlayers}>
+            <RefreshCw className="h-4 w-4" />
+          </Button>
+        </div>
+
+        {offline && (
+          <p className="text-muted-foreground">Servidor parado.</p>
+        )}
+
+        {players.map((player) => (
+          <div
+            key={player.userid}
+            className="flex items-center justify-between gap-2"
+          >
+            <div>
+              <div className="font-semibold">
+                #{player.userid} {player.name}
+              </div>
+              <div className="text-xs text-muted-foreground font-mono">
+                {player.unique_id}
+                {player.address && ` · ${player.address}`}
+              </div>
+            </div>
+            <div className="flex items-center gap-2">
+              <Badge variant="outline">{player.frags} frags</Badge>
+              <Badge variant="secondary">{player.ping} ms</Badge>
+              <span className="text-xs text-muted-foreground">
+                {player.time}
+              </span>
+              <DropdownMenu>
+                <DropdownMenuTrigger asChild>
+                  <Button variant="ghost" size="icon">
+                    <MoreVertical className="w-4 h-4" />
+                  </Button>
+                </DropdownMenuTrigger>
+                <DropdownMenuContent align="end">
+                  <DropdownMenuItem onClick={() => kick(player)}>
+                    Expulsar
+                  </DropdownMenuItem>
+                  <DropdownMenuItem onClick={() => ban(player)}>
+                    Banir
+                  </DropdownMenuItem>
+                  <DropdownMenuItem
+                    onClick={() =>
+                      act("slay_player", player, {}, `${player.name} morto.`)
+                    }
+                  >
+                    Matar (slay)
+                  </DropdownMenuItem>
+                  <DropdownMenuItem onClick={() => move(player, "t")}>
+                    Mover para T
+                  </DropdownMenuItem>
+                  <DropdownMenuItem onClick={() => move(player, "ct")}>
+                    Mover para CT
+                  </DropdownMenuItem>
+                  <DropdownMenuItem onClick={() => move(player, "spec")}>
+                    Mover para espectador
+                  </DropdownMenuItem>
+                </DropdownMenuContent>
+              </DropdownMenu>
+            </div>
+          </div>
+        ))}
+      </CardContent>
+    </Card>
+  );
+}
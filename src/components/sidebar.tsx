@@ -2,9 +2,12 @@
 "use client";
 
 import {
+  Ban,
   FileCog,
   LayoutDashboard,
+  ListOrdered,
   Map as MapIcon,
+  MessageSquare,
   Power,
   ServerCog,
   Users,
@@ -17,31 +20,118 @@ import {
   TooltipProvider,
   TooltipTrigger,
 } from "@/components/ui/tooltip";
+import {
+  DropdownMenu,
+  DropdownMenuContent,
+  DropdownMenuLabel,
+  DropdownMenuRadioGroup,
+  DropdownMenuRadioItem,
+  DropdownMenuSeparator,
+  DropdownMenuTrigger,
+} from "@/components/ui/dropdown-menu";
+import { useInstance } from "@/components/instance-provider";
 import { useTheme } from "next-themes";
 import { invoke } from "@tauri-apps/api/core";
-import { useState } from "react";
+import { ask } from "@tauri-apps/plugin-dialog";
+import { useEffect, useState } from "react";
 import { cn } from "@/lib/utils";
 import { useNavigate, useLocation } from "react-router-dom";
+import { toast } from "sonner";
+
+type PortOwner = {
+  pid: number | null;
+  name: string | null;
+  command: string | null;
+  managed: boolean;
+};
+
+type HldsStatus = {
+  running: boolean;
+  attached: boolean;
+  pid: number | null;
+  started_at: string | null;
+};
+
+type StopMethod = "not_running" | "quit" | "terminated" | "killed";
+
+const stopMessages: Record<StopMethod, string> = {
+  not_running: "O servidor já estava parado.",
+  quit: "Servidor encerrado com quit.",
+  terminated: "Servidor não respondeu ao quit e foi encerrado com SIGTERM.",
+  killed: "Servidor não respondeu e foi encerrado à força.",
+};
 
 export function Sidebar() {
   const [serverRunning, setServerRunning] = useState(false);
   const [loading, setLoading] = useState(false);
+  const { instanceId, instances, setInstanceId } = useInstance();
 
   const navigate = useNavigate();
   const location = useLocation();
+  const instanceName =
+    instances.find((i) => i.id === instanceId)?.name ?? instanceId;
+
+  // The manager may have been restarted while the server kept running.
+  useEffect(() => {
+    invoke<HldsStatus>("get_hlds_status", { instanceId }).then((status) => {
+      setServerRunning(status.running);
+      if (status.attached) {
+        toast.info(
+          `Servidor reanexado (PID ${status.pid}): comandos via RCON e saída pelo arquivo de log.`
+        );
+      }
+    });
+  }, [instanceId]);
+
+  // Processes we did not launch are only killed after the user confirms.
+  const startServer = async () => {
+    const owners = await invoke<PortOwner[]>("get_port_owners", {
+      instanceId,
+    });
+    const foreign = owners.filter((o) => !o.managed && o.pid !== null);
+
+    if (foreign.length > 0) {
+      const list = foreign
+        .map((o) => `• ${o.name ?? "?"} (PID ${o.pid})\n  ${o.command ?? ""}`)
+        .join("\n");
+      const confirmed = await ask(
+        `A porta do servidor está em uso por:\n\n${list}\n\nDeseja encerrar esses processos?`,
+        { title: "Porta em uso", kind: "warning" }
+      );
+      if (!confirmed) {
+        throw new Error("Inicialização cancelada: porta em uso.");
+      }
+    }
+
+    // Only the PIDs shown in the dialog may be killed; anything that grabs the
+    // port in the meantime makes the start fail instead.
+    await invoke("start_hlds_server", {
+      instanceId,
+      confirmedPids: foreign.map((o) => o.pid),
+    });
+  };
 
   const toggleServer = async () => {
     setLoading(true);
     try {
       if (serverRunning) {
-        await invoke("stop_hlds_server");
+        const method = await invoke<StopMethod>("stop_hlds_server", {
+          instanceId,
+          options: { countdown: 10 },
+        });
         setServerRunning(false);
+        if (method === "quit" || method === "not_running") {
+          toast.success(stopMessages[method]);
+        } else {
+          toast.warning(stopMessages[method]);
+        }
       } else {
-        await invoke("start_hlds_server");
+        await startServer();
         setServerRunning(true);
       }
     } catch (err) {
       console.error("Erro ao alternar servidor:", err);
+      toast.error(String(err));
     } finally {
       setLoading(false);
     }
@@ -51,6 +141,35 @@ export function Sidebar() {
     <TooltipProvider>
       <div className="w-20 bg-muted border-r border-border flex flex-col justify-between items-center py-4">
         <div className="space-y-4 flex flex-col items-center">
+          <DropdownMenu>
+            <Tooltip>
+              <TooltipTrigger asChild>
+                <DropdownMenuTrigger asChild>
+                  <Button variant="outline" size="icon">
+                    <ServerCog className="w-5 h-5" />
+                  </Button>
+                </DropdownMenuTrigger>
+              </TooltipTrigger>
+              <TooltipContent side="right">
+                Servidor: {instanceName}
+              </TooltipContent>
+            </Tooltip>
+            <DropdownMenuContent side="right" align="start">
+              <DropdownMenuLabel>Servidor gerenciado</DropdownMenuLabel>
+              <DropdownMenuSeparator />
+              <DropdownMenuRadioGroup
+                value={instanceId}
+                onValueChange={setInstanceId}
+              >
+                {instances.map((instance) => (
+                  <DropdownMenuRadioItem key={instance.id} value={instance.id}>
+                    {instance.name}
+                  </DropdownMenuRadioItem>
+                ))}
+              </DropdownMenuRadioGroup>
+            </DropdownMenuContent>
+          </DropdownMenu>
+
           <Tooltip>
             <TooltipTrigger asChild>
               <Button
@@ -123,6 +242,45 @@ export function Sidebar() {
             </TooltipTrigger>
             <TooltipContent side="right">Administradores</TooltipContent>
           </Tooltip>
+
+          <Tooltip>
+            <TooltipTrigger asChild>
+              <Button
+                variant={location.pathname === "/banimentos" ? "default" : "ghost"}
+                size="icon"
+                onClick={() => navigate("/banimentos")}
+              >
+                <Ban className="w-5 h-5" />
+              </Button>
+            </TooltipTrigger>
+            <TooltipContent side="right">Banimentos</TooltipContent>
+          </Tooltip>
+
+          <Tooltip>
+            <TooltipTrigger asChild>
+              <Button
+                variant={location.pathname === "/chat" ? "default" : "ghost"}
+                size="icon"
+                onClick={() => navigate("/chat")}
+              >
+                <MessageSquare className="w-5 h-5" />
+              </Button>
+            </TooltipTrigger>
+            <TooltipContent side="right">Chat</TooltipContent>
+          </Tooltip>
+
+          <Tooltip>
+            <TooltipTrigger asChild>
+              <Button
+                variant={location.pathname === "/rotacao" ? "default" : "ghost"}
+                size="icon"
+                onClick={() => navigate("/rotacao")}
+              >
+                <ListOrdered className="w-5 h-5" />
+              </Button>
+            </TooltipTrigger>
+            <TooltipContent side="right">Rotação de Mapas</TooltipContent>
+          </Tooltip>
         </div>
 
         <div className="space-y-4 flex flex-col items-center">
@@ -4,9 +4,12 @@ import { createRoot } from "react-dom/client";
 import { StrictMode } from "react";
 
 import App from "./App";
+import { InstanceProvider } from "./components/instance-provider";
 
 createRoot(document.getElementById("root")!).render(
   <StrictMode>
-    <App />
+    <InstanceProvider>
+      <App />
+    </InstanceProvider>
   </StrictMode>
 );
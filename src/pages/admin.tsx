@@ -14,6 +14,8 @@ import {
   DropdownMenuItem,
   DropdownMenuTrigger,
 } from "@/components/ui/dropdown-menu";
+import { toast } from "sonner";
+import { useInstance } from "@/components/instance-provider";
 
 interface AdminEntry {
   name?: string;
@@ -23,6 +25,51 @@ interface AdminEntry {
   flags: string;
 }
 
+interface FlagInfo {
+  flag: string;
+  name: string;
+  description: string;
+}
+
+interface AccessPreset {
+  id: string;
+  name: string;
+  access: string;
+  flags: string;
+}
+
+type AdminReload =
+  | { status: "live"; admins: number }
+  | { status: "not_running" }
+  | { status: "unconfirmed"; reason: string };
+
+type AdminBackend = "users_ini" | "sql";
+
+interface PlayerSummary {
+  player_id: string;
+  name: string;
+  first_seen: string;
+  last_seen: string;
+  playtime_secs: number;
+}
+
+interface SeenValue {
+  value: string;
+  first_seen: string;
+  last_seen: string;
+}
+
+interface PlayerProfile extends PlayerSummary {
+  aliases: SeenValue[];
+  ips: SeenValue[];
+}
+
+interface AdminFlagCatalog {
+  access: FlagInfo[];
+  account: FlagInfo[];
+  presets: AccessPreset[];
+}
+
 export default function AdminManager() {
   const [admins, setAdmins] = useState<AdminEntry[]>([]);
   const [editing, setEditing] = useState<Record<string, AdminEntry>>({});
@@ -35,19 +82,60 @@ export default function AdminManager() {
   });
   const [search, setSearch] = useState("");
   const [filterFlag, setFilterFlag] = useState("");
+  const [backend, setBackend] = useState<AdminBackend>("users_ini");
+  const [catalog, setCatalog] = useState<AdminFlagCatalog>({
+    access: [],
+    account: [],
+    presets: [],
+  });
+  const [playerQuery, setPlayerQuery] = useState({ text: "", ip: "" });
+  const [players, setPlayers] = useState<PlayerSummary[]>([]);
+  const [profile, setProfile] = useState<PlayerProfile | null>(null);
+  const { instanceId } = useInstance();
 
   const loadAdmins = async () => {
-    const data = await invoke<AdminEntry[]>("list_admins");
-    setAdmins(data);
+    try {
+      setAdmins(await invoke<AdminEntry[]>("list_admins", { instanceId }));
+    } catch (err) {
+      setAdmins([]);
+      toast.error(String(err));
+    }
+  };
+
+  const changeBackend = async (next: AdminBackend) => {
+    await invoke("set_admin_backend", { backend: next, instanceId });
+    setBackend(next);
+    loadAdmins();
   };
 
   useEffect(() => {
     loadAdmins();
+    invoke<AdminBackend>("get_admin_backend", { instanceId }).then(setBackend);
+    invoke<AdminFlagCatalog>("get_admin_flags").then(setCatalog);
   }, []);
 
+  const describeFlags = (flags: string, table: FlagInfo[]) =>
+    table
+      .filter((info) => flags.includes(info.flag))
+      .map((info) => `${info.flag}: ${info.name} — ${info.description}`)
+      .join("\n");
+
+  const notifyReload = (reload: AdminReload) => {
+    if (reload.status === "live")
+      toast.success(`Admins recarregados no servidor (${reload.admins}).`);
+    else if (reload.status === "unconfirmed") toast.warning(reload.reason);
+  };
+
   const saveEdit = async (auth: string) => {
     const updated = editing[auth];
-    await invoke("update_admin", { updated });
+    try {
+      notifyReload(
+        await invoke<AdminReload>("update_admin", { updated, instanceId })
+      );
+    } catch (err) {
+      toast.error(String(err));
+      return;
+    }
     setEditing((prev) => {
       const copy = { ...prev };
       delete copy[auth];
@@ -57,17 +145,59 @@ export default function AdminManager() {
   };
 
   const deleteAdmin = async (auth: string) => {
-    await invoke("remove_admin", { auth });
+    try {
+      notifyReload(
+        await invoke<AdminReload>("remove_admin", { auth, instanceId })
+      );
+    } catch (err) {
+      toast.error(String(err));
+    }
     loadAdmins();
   };
 
   const addAdmin = async () => {
     if (!newAdmin.auth || !newAdmin.access) return;
-    await invoke("add_admin", { entry: newAdmin });
+    try {
+      notifyReload(
+        await invoke<AdminReload>("add_admin", { entry: newAdmin, instanceId })
+      );
+    } catch (err) {
+      toast.error(String(err));
+      return;
+    }
     setNewAdmin({ name: "", auth: "", password: "", access: "", flags: "" });
     loadAdmins();
   };
 
+  const searchPlayers = async () => {
+    try {
+      setPlayers(
+        await invoke<PlayerSummary[]>("search_players", {
+          query: {
+            text: playerQuery.text || null,
+            ip: playerQuery.ip || null,
+          },
+        })
+      );
+      setProfile(null);
+    } catch (err) {
+      toast.error(String(err));
+    }
+  };
+
+  const openProfile = async (playerId: string) => {
+    try {
+      setProfile(
+        await invoke<PlayerProfile>("get_player_profile", { playerId })
+      );
+    } catch (err) {
+      toast.error(String(err));
+    }
+  };
+
+  const formatPlaytime = (secs: number) =>
+    `${Math.floor(secs / 3600)}h ${Math.floor((secs % 3600) / 60)}min`;
+
   const filteredAdmins = admins.filter((admin) => {
     const matchesSearch =
       admin.auth.toLowerCase().includes(search.toLowerCase()) ||
@@ -76,18 +206,51 @@ export default function AdminManager() {
     return matchesSearch && matchesFlag;
   });
 
-  const renderBadge = (access: string) => {
-    if (access.includes("a")) return <Badge variant="secondary">Imune</Badge>;
-    if (access.includes("d")) return <Badge variant="secondary">Ban</Badge>;
-    if (access.includes("l")) return <Badge variant="secondary">RCON</Badge>;
-    return <Badge variant="outline">User</Badge>;
+  const renderBadge = (admin: AdminEntry) => {
+    const preset = catalog.presets.find(
+      (p) => p.access === admin.access && p.flags === admin.flags
+    );
+    const label = preset
+      ? preset.name
+      : admin.access.includes("a")
+      ? "Imune"
+      : admin.access.includes("d")
+      ? "Ban"
+      : admin.access.includes("l")
+      ? "RCON"
+      : null;
+
+    return (
+      <Badge
+        variant={label ? "secondary" : "outline"}
+        title={describeFlags(admin.access, catalog.access)}
+      >
+        {label ?? "User"}
+      </Badge>
+    );
   };
 
   return (
     <div className="max-w-6xl mx-auto p-4 space-y-8">
       <Card>
-        <CardHeader>
+        <CardHeader className="flex flex-row items-center justify-between">
           <CardTitle>Adicionar Novo Admin</CardTitle>
+          <div className="flex gap-2">
+            <Button
+              size="sm"
+              variant={backend === "users_ini" ? "default" : "outline"}
+              onClick={() => changeBackend("users_ini")}
+            >
+              users.ini
+            </Button>
+            <Button
+              size="sm"
+              variant={backend === "sql" ? "default" : "outline"}
+              onClick={() => changeBackend("sql")}
+            >
+              SQL (sql.cfg)
+            </Button>
+          </div>
         </CardHeader>
         <CardContent className="grid grid-cols-1 md:grid-cols-2 gap-4">
           <div>
@@ -120,6 +283,7 @@ export default function AdminManager() {
           <div>
             <Label className="mb-2">Acesso (flags)</Label>
             <Input
+              title={describeFlags(newAdmin.access, catalog.access)}
               value={newAdmin.access}
               onChange={(e) =>
                 setNewAdmin({ ...newAdmin, access: e.target.value })
@@ -129,14 +293,38 @@ export default function AdminManager() {
           <div>
             <Label className="mb-2">Tipo (flags)</Label>
             <Input
+              title={describeFlags(newAdmin.flags, catalog.account)}
               value={newAdmin.flags}
               onChange={(e) =>
                 setNewAdmin({ ...newAdmin, flags: e.target.value })
               }
             />
           </div>
-          <div className="flex items-end">
-            <Button onClick={addAdmin} className="w-full">
+          <div className="flex items-end gap-2">
+            <DropdownMenu>
+              <DropdownMenuTrigger asChild>
+                <Button variant="outline" className="flex-1">
+                  Perfil
+                </Button>
+              </DropdownMenuTrigger>
+              <DropdownMenuContent align="end">
+                {catalog.presets.map((preset) => (
+                  <DropdownMenuItem
+                    key={preset.id}
+                    onClick={() =>
+                      setNewAdmin({
+                        ...newAdmin,
+                        access: preset.access,
+                        flags: preset.flags,
+                      })
+                    }
+                  >
+                    {preset.name}
+                  </DropdownMenuItem>
+                ))}
+              </DropdownMenuContent>
+            </DropdownMenu>
+            <Button onClick={addAdmin} className="flex-1">
               Adicionar
             </Button>
           </div>
@@ -179,7 +367,7 @@ export default function AdminManager() {
                     <div className="text-xs text-muted-foreground font-mono">
                       {admin.auth}
                     </div>
-                    <div className="mt-1">{renderBadge(admin.access)}</div>
+                    <div className="mt-1">{renderBadge(admin)}</div>
                   </div>
                 </div>
                 <DropdownMenu>
@@ -264,6 +452,106 @@ export default function AdminManager() {
           );
         })}
       </div>
+
+      <Card>
+        <CardHeader>
+          <CardTitle>Buscar Jogadores</CardTitle>
+        </CardHeader>
+        <CardContent className="space-y-4">
+          <div className="flex flex-col sm:flex-row gap-2">
+            <Input
+              placeholder="SteamID ou nick"
+              value={playerQuery.text}
+              onChange={(e) =>
+                setPlayerQuery({ ...playerQuery, text: e.target.value })
+              }
+            />
+            <Input
+              placeholder="IP (ex.: 192.168.0.)"
+              value={playerQuery.ip}
+              onChange={(e) =>
+                setPlayerQuery({ ...playerQuery, ip: e.target.value })
+              }
+            />
+            <Button onClick={searchPlayers}>Buscar</Button>
+          </div>
+
+          {players.map((player) => (
+            <div
+              key={player.player_id}
+              className="flex justify-between items-center gap-2 text-sm cursor-pointer hover:bg-muted rounded p-2"
+              onClick={() => openProfile(player.player_id)}
+            >
+              <div>
+                <div className="font-semibold">{player.name}</div>
+                <div className="text-xs text-muted-foreground font-mono">
+                  {player.player_id}
+                </div>
+              </div>
+              <div className="text-xs text-muted-foreground text-right">
+                <div>Visto em {player.last_seen}</div>
+                <div>{formatPlaytime(player.playtime_secs)} de jogo</div>
+              </div>
+            </div>
+          ))}
+
+          {profile && (
+            <div className="border rounded p-4 space-y-3 text-sm">
+              <div className="flex justify-between items-center">
+                <div className="font-semibold">
+                  {profile.name}{" "}
+                  <span className="font-mono text-muted-foreground">
+                    {profile.player_id}
+                  </span>
+                </div>
+                <Button
+                  variant="outline"
+                  size="sm"
+                  onClick={() =>
+                    setNewAdmin({ ...newAdmin, auth: profile.player_id })
+                  }
+                >
+                  Usar como auth
+                </Button>
+              </div>
+              <div className="text-xs text-muted-foreground">
+                Primeira vez em {profile.first_seen} · última em{" "}
+                {profile.last_seen} · {formatPlaytime(profile.playtime_secs)}
+              </div>
+              <div>
+                <Label className="mb-2">Nicks</Label>
+                <div className="flex flex-wrap gap-2">
+                  {profile.aliases.map((alias) => (
+                    <Badge
+                      key={alias.value}
+                      variant="outline"
+                      title={`Último uso: ${alias.last_seen}`}
+                    >
+                      {alias.value}
+                    </Badge>
+                  ))}
+                </div>
+              </div>
+              <div>
+                <Label className="mb-2">IPs</Label>
+                <div className="flex flex-wrap gap-2">
+                  {profile.ips.map((ip) => (
+                    <Badge
+                      key={ip.value}
+                      variant="secondary"
+                      className="font-mono cursor-pointer"
+                      title="Buscar outros jogadores com este IP"
+                      onClick={() => setPlayerQuery({ text: "", ip: ip.value })}
+                    >
+                      {ip.value}
+                    </Badge>
+                  ))}
+                </div>
+              </div>
+            </div>
+          )}
+        </CardContent>
+      </Card>
     </div>
   );
 }
@@ -0,0 +1,173 @@
+"use client";
+
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
+import { Trash2 } from "lucide-react";
+import { Badge } from "@/components/ui/badge";
+import { Label } from "@/components/ui/label";
+import { Input } from "@/components/ui/input";
+import { Button } from "@/components/ui/button";
+import { toast } from "sonner";
+import { useInstance } from "@/components/instance-provider";
+
+interface BanRecord {
+  kind: "steam_id" | "ip";
+  target: string;
+  reason?: string;
+  banned_by?: string;
+  created_at?: string;
+  expires_at?: string;
+}
+
+const emptyBan = { target: "", minutes: "0", reason: "", banned_by: "" };
+
+export default function BansPage() {
+  const [bans, setBans] = useState<BanRecord[]>([]);
+  const [newBan, setNewBan] = useState(emptyBan);
+  const [search, setSearch] = useState("");
+  const { instanceId } = useInstance();
+
+  const loadBans = async () => {
+    try {
+      setBans(await invoke<BanRecord[]>("list_bans", { instanceId }));
+    } catch (err) {
+      toast.error(String(err));
+    }
+  };
+
+  useEffect(() => {
+    loadBans();
+  }, []);
+
+  const addBan = async () => {
+    if (!newBan.target) return;
+    try {
+      await invoke("add_ban", {
+        ban: {
+          target: newBan.target,
+          minutes: Number(newBan.minutes) || 0,
+          reason: newBan.reason || null,
+          banned_by: newBan.banned_by || null,
+        },
+        instanceId,
+      });
+      toast.success(`${newBan.target} banido.`);
+      setNewBan(emptyBan);
+    } catch (err) {
+      toast.error(String(err));
+    }
+    loadBans();
+  };
+
+  const removeBan = async (target: string) => {
+    try {
+      await invoke("remove_ban", { target, instanceId });
+      toast.success(`Banimento de ${target} removido.`);
+    } catch (err) {
+      toast.error(String(err));
+    }
+    loadBans();
+  };
+
+  const filtered = bans.filter(
+    (ban) =>
+      ban.target.toLowerCase().includes(search.toLowerCase()) ||
+      ban.reason?.toLowerCase().includes(search.toLowerCase())
+  );
+
+  return (
+    <div className="max-w-6xl mx-auto p-4 space-y-8">
+      <Card>
+        <CardHeader>
+          <CardTitle>Novo Banimento</CardTitle>
+        </CardHeader>
+        <CardContent className="grid grid-cols-1 md:grid-cols-2 gap-4">
+          <div>
+            <Label className="mb-2">SteamID ou IP</Label>
+            <Input
+              value={newBan.target}
+              onChange={(e) => setNewBan({ ...newBan, target: e.target.value })}
+            />
+          </div>
+          <div>
+            <Label className="mb-2">Duração (minutos, 0 = permanente)</Label>
+            <Input
+              type="number"
+              min={0}
+              value={newBan.minutes}
+              onChange={(e) =>
+                setNewBan({ ...newBan, minutes: e.target.value })
+              }
+            />
+          </div>
+          <div>
+            <Label className="mb-2">Motivo</Label>
+            <Input
+              value={newBan.reason}
+              onChange={(e) => setNewBan({ ...newBan, reason: e.target.value })}
+            />
+          </div>
+          <div>
+            <Label className="mb-2">Banido por</Label>
+            <Input
+              value={newBan.banned_by}
+              onChange={(e) =>
+                setNewBan({ ...newBan, banned_by: e.target.value })
+              }
+            />
+          </div>
+          <div className="md:col-span-2">
+            <Button onClick={addBan} className="w-full">
+              Banir
+            </Button>
+          </div>
+        </CardContent>
+      </Card>
+
+      <Input
+        placeholder="Buscar por SteamID, IP ou motivo"
+        value={search}
+        onChange={(e) => setSearch(e.target.value)}
+        className="w-full sm:max-w-md"
+      />
+
+      <div className="grid gap-4">
+        {filtered.map((ban) => (
+          <Card key={ban.target} className="w-full">
+            <CardHeader className="flex flex-row justify-between items-center gap-2">
+              <div className="space-y-1">
+                <div className="text-sm font-semibold font-mono">
+                  {ban.target}
+                </div>
+                <div className="flex gap-2">
+                  <Badge variant="outline">
+                    {ban.kind === "ip" ? "IP" : "SteamID"}
+                  </Badge>
+                  <Badge variant={ban.expires_at ? "secondary" : "default"}>
+                    {ban.expires_at
+                      ? `Até ${new Date(ban.expires_at).toLocaleString()}`
+                      : "Permanente"}
+                  </Badge>
+                </div>
+                {(ban.reason || ban.banned_by) && (
+                  <div className="text-xs text-muted-foreground">
+                    {ban.reason}
+                    {ban.banned_by && ` — por ${ban.banned_by}`}
+                  </div>
+                )}
+              </div>
+              <Button
+                variant="ghost"
+                size="icon"
+                onClick={() => removeBan(ban.target)}
+              >
+                <Trash2 className="w-4 h-4 text-red-500" />
+              </Button>
+            </CardHeader>
+          </Card>
+        ))}
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,237 @@
+"use client";
+
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
+import { Trash2 } from "lucide-react";
+import { Badge } from "@/components/ui/badge";
+import { Label } from "@/components/ui/label";
+import { Input } from "@/components/ui/input";
+import { Button } from "@/components/ui/button";
+import { Checkbox } from "@/components/ui/checkbox";
+import { toast } from "sonner";
+import { useInstance } from "@/components/instance-provider";
+
+interface ChatMessage {
+  instance: string;
+  sent_at: string;
+  player_id: string;
+  name: string;
+  team: string;
+  message: string;
+  team_only: boolean;
+  dead: boolean;
+  flagged?: string;
+}
+
+type ChatAction = "warn" | "gag" | "kick";
+
+interface ChatRule {
+  pattern: string;
+  action: ChatAction;
+  minutes: number;
+  message?: string;
+}
+
+const actionLabels: Record<ChatAction, string> = {
+  warn: "Avisar",
+  gag: "Gag",
+  kick: "Kick",
+};
+
+const emptyRule: ChatRule = {
+  pattern: "",
+  action: "warn",
+  minutes: 5,
+  message: "",
+};
+
+export default function ChatPage() {
+  const [messages, setMessages] = useState<ChatMessage[]>([]);
+  const [query, setQuery] = useState({ text: "", player: "" });
+  const [flaggedOnly, setFlaggedOnly] = useState(false);
+  const [rules, setRules] = useState<ChatRule[]>([]);
+  const [newRule, setNewRule] = useState<ChatRule>(emptyRule);
+  const { instanceId } = useInstance();
+
+  const searchChat = async () => {
+    try {
+      setMessages(
+        await invoke<ChatMessage[]>("search_chat", {
+          query: {
+            text: query.text || null,
+            player: query.player || null,
+            instance: instanceId,
+            flagged_only: flaggedOnly,
+          },
+        })
+      );
+    } catch (err) {
+      toast.error(String(err));
+    }
+  };
+
+  const loadRules = async () => {
+    setRules(await invoke<ChatRule[]>("list_chat_filters"));
+  };
+
+  useEffect(() => {
+    searchChat();
+    loadRules();
+  }, []);
+
+  const saveRules = async (next: ChatRule[]) => {
+    try {
+      await invoke("save_chat_filters", { rules: next });
+      setRules(next);
+      return true;
+    } catch (err) {
+      toast.error(String(err));
+      return false;
+    }
+  };
+
+  const addRule = async () => {
+    if (!newRule.pattern) return;
+    const rule = { ...newRule, message: newRule.message || undefined };
+    const next = [...rules.filter((r) => r.pattern !== rule.pattern), rule];
+    if (await saveRules(next)) setNewRule(emptyRule);
+  };
+
+  const removeRule = (pattern: string) =>
+    saveRules(rules.filter((r) => r.pattern !== pattern));
+
+  return (
+    <div className="max-w-6xl mx-auto p-4 space-y-8">
+      <Card>
+        <CardHeader>
+          <CardTitle>Filtros de Palavras</CardTitle>
+        </CardHeader>
+        <CardContent className="space-y-4">
+          <div className="grid grid-cols-1 md:grid-cols-2 gap-4">
+            <div>
+              <Label className="mb-2">Padrão (use * no fim para prefixo)</Label>
+              <Input
+                value={newRule.pattern}
+                onChange={(e) =>
+                  setNewRule({ ...newRule, pattern: e.target.value })
+                }
+              />
+            </div>
+            <div>
+              <Label className="mb-2">Mensagem ao jogador / motivo</Label>
+              <Input
+                value={newRule.message}
+                onChange={(e) =>
+                  setNewRule({ ...newRule, message: e.target.value })
+                }
+              />
+            </div>
+            <div>
+              <Label className="mb-2">Ação</Label>
+              <div className="flex gap-2">
+                {(Object.keys(actionLabels) as ChatAction[]).map((action) => (
+                  <Button
+                    key={action}
+                    variant={newRule.action === action ? "default" : "outline"}
+                    onClick={() => setNewRule({ ...newRule, action })}
+                  >
+                    {actionLabels[action]}
+                  </Button>
+                ))}
+              </div>
+            </div>
+            {newRule.action === "gag" && (
+              <div>
+                <Label className="mb-2">Minutos de gag</Label>
+                <Input
+                  type="number"
+                  min={1}
+                  value={newRule.minutes}
+                  onChange={(e) =>
+                    setNewRule({
+                      ...newRule,
+                      minutes: Number(e.target.value) || 1,
+                    })
+                  }
+                />
+              </div>
+            )}
+            <div className="md:col-span-2">
+              <Button onClick={addRule} className="w-full">
+                Adicionar Filtro
+              </Button>
+            </div>
+          </div>
+
+          {rules.map((rule) => (
+            <div
+              key={rule.pattern}
+              className="flex justify-between items-center text-sm"
+            >
+              <div className="flex items-center gap-2">
+                <span className="font-mono">{rule.pattern}</span>
+                <Badge variant="secondary">
+                  {actionLabels[rule.action]}
+                  {rule.action === "gag" && ` ${rule.minutes}min`}
+                </Badge>
+                {rule.message && (
+                  <span className="text-xs text-muted-foreground">
+                    {rule.message}
+                  </span>
+                )}
+              </div>
+              <Button
+                variant="ghost"
+                size="icon"
+                onClick={() => removeRule(rule.pattern)}
+              >
+                <Trash2 className="w-4 h-4 text-red-500" />
+              </Button>
+            </div>
+          ))}
+        </CardContent>
+      </Card>
+
+      <div className="flex flex-col sm:flex-row gap-2 sm:items-center">
+        <Input
+          placeholder="Buscar no texto"
+          value={query.text}
+          onChange={(e) => setQuery({ ...query, text: e.target.value })}
+          onKeyDown={(e) => e.key === "Enter" && searchChat()}
+        />
+        <Input
+          placeholder="SteamID ou nick"
+          value={query.player}
+          onChange={(e) => setQuery({ ...query, player: e.target.value })}
+          onKeyDown={(e) => e.key === "Enter" && searchChat()}
+        />
+        <Label className="flex items-center gap-2 whitespace-nowrap">
+          <Checkbox
+            checked={flaggedOnly}
+            onCheckedChange={(checked) => setFlaggedOnly(checked === true)}
+          />
+          Só sinalizadas
+        </Label>
+        <Button onClick={searchChat}>Buscar</Button>
+      </div>
+
+      <Card>
+        <CardContent className="p-4 space-y-1 font-mono text-sm">
+          {messages.map((m, idx) => (
+            <div
+              key={idx}
+              className={m.flagged ? "text-red-500" : "text-muted-foreground"}
+              title={m.flagged ? `Regra: ${m.flagged}` : m.player_id}
+            >
+              [{m.sent_at}] {m.dead && "*MORTO* "}
+              {m.team_only && `(${m.team}) `}
+              <span className="font-semibold text-foreground">{m.name}</span>:{" "}
+              {m.message}
+            </div>
+          ))}
+        </CardContent>
+      </Card>
+    </div>
+  );
+}
@@ -17,6 +17,7 @@ import {
 import { useSystemStats } from "@/hooks/use-system-stats";
 import { Card, CardContent } from "@/components/ui/card";
 import { ScrollArea } from "@/components/ui/scroll-area";
+import OnlinePlayers from "@/components/online-players";
 
 export default function Console() {
   const {
@@ -73,6 +74,8 @@ export default function Console() {
           </CardContent>
         </Card>
 
+        <OnlinePlayers />
+
         <div className="grid grid-cols-1 md:grid-cols-2 gap-4">
           <Card>
             <CardContent className="px-4 pt-4 pb-2">
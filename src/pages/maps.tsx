@@ -5,25 +5,113 @@ import {
   DialogHeader,
 } from "@/components/ui/dialog";
 import { Card, CardContent, CardFooter } from "@/components/ui/card";
-import { Star, StarOff, Trash2, Play } from "lucide-react";
+import {
+  Star,
+  StarOff,
+  Trash2,
+  Play,
+  PackageSearch,
+  PackagePlus,
+} from "lucide-react";
 import { ScrollArea } from "@/components/ui/scroll-area";
 import { DialogTrigger } from "@radix-ui/react-dialog";
 import { useEffect, useMemo, useState } from "react";
 import { Button } from "@/components/ui/button";
 import { invoke } from "@tauri-apps/api/core";
+import { open } from "@tauri-apps/plugin-dialog";
 import { Input } from "@/components/ui/input";
+import { Badge } from "@/components/ui/badge";
+import { toast } from "sonner";
+import { useInstance } from "@/components/instance-provider";
+
+type MapObjective = "bomb" | "hostage" | "vip" | "escape";
+
+interface BspInfo {
+  skyname?: string;
+  wads: string[];
+  message?: string;
+  ct_spawns: number;
+  t_spawns: number;
+  objectives: MapObjective[];
+  bounds: { mins: number[]; maxs: number[] };
+  entity_count: number;
+}
 
-interface MapEntry {
+interface MapInfo {
   name: string;
+  size_bytes: number;
+  metadata?: BspInfo;
+  error?: string;
+}
+
+interface MapAsset {
+  path: string;
+  kind: "wad" | "sky" | "model" | "sprite" | "sound" | "other";
+  source: "entity" | "res_file" | "convention";
+  found?: string;
+  stock: boolean;
+}
+
+interface MapDependencies {
+  map: string;
+  assets: MapAsset[];
+  missing: string[];
+}
+
+interface ResUpdate {
+  path: string;
+  entries: string[];
+  added: string[];
+  removed: string[];
+}
+
+interface PackageFile {
+  path: string;
+  size_bytes: number;
+  action: "new" | "overwrite" | "unchanged";
+}
+
+interface MapPackage {
+  archive: string;
+  maps: string[];
+  files: PackageFile[];
+  ignored: string[];
+}
+
+const fileActionLabels: Record<PackageFile["action"], string> = {
+  new: "novo",
+  overwrite: "sobrescreve",
+  unchanged: "igual",
+};
+
+interface MapDeletion {
+  map: string;
+  trashed: string[];
+  kept: string[];
+  trash_path?: string;
+}
+
+interface MapEntry extends MapInfo {
   isFavorite: boolean;
 }
 
+const objectiveLabels: Record<MapObjective, string> = {
+  bomb: "Bomba",
+  hostage: "Reféns",
+  vip: "VIP",
+  escape: "Fuga",
+};
+
 export default function MapManager() {
   const [maps, setMaps] = useState<MapEntry[]>([]);
   const [search, setSearch] = useState("");
   const [favorites, setFavorites] = useState<string[]>([]);
   const [selectedMap, setSelectedMap] = useState<string | null>(null);
   const [currentPage, setCurrentPage] = useState(1);
+  const [deps, setDeps] = useState<MapDependencies | null>(null);
+  const [pkg, setPkg] = useState<MapPackage | null>(null);
+  const [installing, setInstalling] = useState(false);
+  const { instanceId } = useInstance();
   const mapsPerPage = 12;
 
   useEffect(() => {
@@ -31,14 +119,17 @@ export default function MapManager() {
     setFavorites(favs);
   }, []);
 
-  useEffect(() => {
-    invoke<string[]>("list_maps").then((data) => {
-      const loadedMaps = data.map((name) => ({
-        name,
-        isFavorite: favorites.includes(name),
+  const loadMaps = () =>
+    invoke<MapInfo[]>("list_maps", { instanceId }).then((data) => {
+      const loadedMaps = data.map((map) => ({
+        ...map,
+        isFavorite: favorites.includes(map.name),
       }));
       setMaps(loadedMaps);
     });
+
+  useEffect(() => {
+    loadMaps();
   }, [favorites]);
 
   const filteredMaps = useMemo(() => {
@@ -63,13 +154,98 @@ export default function MapManager() {
 
   const deleteMap = async () => {
     if (!selectedMap) return;
-    await invoke("delete_map_files", { mapName: selectedMap });
-    setMaps((prev) => prev.filter((m) => m.name !== selectedMap));
+    try {
+      const deletion = await invoke<MapDeletion>("delete_map_files", {
+        mapName: selectedMap,
+        instanceId,
+      });
+      toast.success(
+        `${deletion.trashed.length} arquivo(s) movido(s) para a lixeira` +
+          (deletion.kept.length ? `, ${deletion.kept.length} mantido(s)` : "")
+      );
+      setMaps((prev) => prev.filter((m) => m.name !== selectedMap));
+    } catch (err) {
+      toast.error(String(err));
+    }
     setSelectedMap(null);
   };
 
+  const checkDependencies = async (name: string) => {
+    setDeps(null);
+    try {
+      setDeps(
+        await invoke<MapDependencies>("check_map_dependencies", {
+          mapName: name,
+          instanceId,
+        })
+      );
+    } catch (err) {
+      toast.error(String(err));
+    }
+  };
+
+  const generateRes = async () => {
+    if (!deps) return;
+    try {
+      const update = await invoke<ResUpdate>("generate_map_res", {
+        mapName: deps.map,
+        instanceId,
+      });
+      toast.success(
+        `${deps.map}.res: ${update.entries.length} arquivo(s), +${update.added.length} / -${update.removed.length}`
+      );
+      await checkDependencies(deps.map);
+    } catch (err) {
+      toast.error(String(err));
+    }
+  };
+
+  const choosePackage = async () => {
+    const selected = await open({
+      multiple: false,
+      title: "Selecione o pacote do mapa",
+      filters: [
+        { name: "Pacotes", extensions: ["zip", "rar", "7z", "gz", "tgz", "tar"] },
+      ],
+    });
+    if (typeof selected !== "string") return;
+
+    try {
+      setPkg(
+        await invoke<MapPackage>("preview_map_package", {
+          path: selected,
+          instanceId,
+        })
+      );
+    } catch (err) {
+      toast.error(String(err));
+    }
+  };
+
+  const installPackage = async () => {
+    if (!pkg) return;
+    setInstalling(true);
+    try {
+      const installed = await invoke<MapPackage>("install_map_package", {
+        path: pkg.archive,
+        overwrite: true,
+        instanceId,
+      });
+      toast.success(`Instalado: ${installed.maps.join(", ")}`);
+      setPkg(null);
+      loadMaps();
+    } catch (err) {
+      toast.error(String(err));
+    } finally {
+      setInstalling(false);
+    }
+  };
+
   const openMap = (name: string) => {
-    invoke("send_command_to_hlds", { command: `changelevel ${name}` });
+    invoke("send_command_to_hlds", {
+      command: `changelevel ${name}`,
+      instanceId,
+    });
   };
 
   return (
@@ -77,24 +253,100 @@ export default function MapManager() {
       <div className="p-4 space-y-4 h-full overflow-hidden flex flex-col">
         <div className="flex justify-between items-center">
           <h1 className="text-xl font-semibold">Gerenciador de Mapas</h1>
-          <Input
-            placeholder="Buscar mapas..."
-            value={search}
-            onChange={(e: any) => setSearch(e.target.value)}
-            className="w-64"
-          />
+          <div className="flex gap-2">
+            <Input
+              placeholder="Buscar mapas..."
+              value={search}
+              onChange={(e: any) => setSearch(e.target.value)}
+              className="w-64"
+            />
+            <Button variant="outline" onClick={choosePackage}>
+              <PackagePlus className="h-4 w-4" />
+              Instalar Pacote
+            </Button>
+          </div>
         </div>
 
+        <Dialog open={pkg !== null} onOpenChange={(o) => !o && setPkg(null)}>
+          <DialogContent>
+            <DialogHeader>
+              <p className="text-sm">
+                Instalar <strong>{pkg?.maps.join(", ")}</strong>
+              </p>
+            </DialogHeader>
+            <ScrollArea className="max-h-80">
+              <div className="space-y-1 text-xs font-mono">
+                {pkg?.files.map((file) => (
+                  <div
+                    key={file.path}
+                    className={`flex justify-between gap-2 ${
+                      file.action === "overwrite"
+                        ? "text-yellow-500"
+                        : "text-muted-foreground"
+                    }`}
+                  >
+                    <span className="truncate">{file.path}</span>
+                    <span>{fileActionLabels[file.action]}</span>
+                  </div>
+                ))}
+              </div>
+            </ScrollArea>
+            {pkg && pkg.ignored.length > 0 && (
+              <p
+                className="text-xs text-muted-foreground"
+                title={pkg.ignored.join("\n")}
+              >
+                {pkg.ignored.length} arquivo(s) fora das pastas do jogo serão
+                ignorados.
+              </p>
+            )}
+            <DialogFooter>
+              <Button variant="secondary" onClick={() => setPkg(null)}>
+                Cancelar
+              </Button>
+              <Button onClick={installPackage} disabled={installing}>
+                {pkg?.files.some((f) => f.action === "overwrite")
+                  ? "Instalar e sobrescrever"
+                  : "Instalar"}
+              </Button>
+            </DialogFooter>
+          </DialogContent>
+        </Dialog>
+
         <div className="grid grid-cols-2 md:grid-cols-3 xl:grid-cols-4 gap-3">
           {paginatedMaps.map((map) => (
             <Card
               key={map.name}
               className="group transition-all flex flex-col h-full justify-between"
             >
-              <CardContent className="p-3">
-                <p className="font-medium text-sm text-muted-foreground truncate max-w-full">
+              <CardContent className="p-3 space-y-2">
+                <p
+                  className="font-medium text-sm text-muted-foreground truncate max-w-full"
+                  title={map.metadata?.message}
+                >
                   {map.name}
                 </p>
+                {map.metadata ? (
+                  <div className="flex flex-wrap gap-1">
+                    {map.metadata.objectives.map((objective) => (
+                      <Badge key={objective} variant="secondary">
+                        {objectiveLabels[objective]}
+                      </Badge>
+                    ))}
+                    <Badge
+                      variant="outline"
+                      title={`Sky: ${map.metadata.skyname ?? "-"}\nWADs: ${
+                        map.metadata.wads.join(", ") || "-"
+                      }`}
+                    >
+                      {map.metadata.ct_spawns} CT / {map.metadata.t_spawns} T
+                    </Badge>
+                  </div>
+                ) : (
+                  <Badge variant="destructive" title={map.error}>
+                    BSP inválido
+                  </Badge>
+                )}
               </CardContent>
               <CardFooter className="flex justify-center items-center gap-2 mt-auto border-t">
                 <Button
@@ -115,6 +367,71 @@ export default function MapManager() {
                 >
                   <Play className="h-4 w-4 text-green-500" />
                 </Button>
+                <Dialog>
+                  <DialogTrigger asChild>
+                    <Button
+                      variant="ghost"
+                      size="icon"
+                      title="Dependências"
+                      onClick={() => checkDependencies(map.name)}
+                    >
+                      <PackageSearch className="h-4 w-4" />
+                    </Button>
+                  </DialogTrigger>
+                  <DialogContent>
+                    <DialogHeader>
+                      <p className="text-sm">
+                        Dependências de <strong>{map.name}</strong>
+                      </p>
+                    </DialogHeader>
+                    {deps?.map === map.name ? (
+                      <ScrollArea className="max-h-80">
+                        <div className="space-y-1 text-xs font-mono">
+                          {deps.assets.map((asset) => (
+                            <div
+                              key={asset.path}
+                              className={`flex justify-between gap-2 ${
+                                asset.found
+                                  ? "text-muted-foreground"
+                                  : "text-red-500"
+                              }`}
+                              title={asset.found ?? undefined}
+                            >
+                              <span className="truncate">{asset.path}</span>
+                              <span>
+                                {!asset.found
+                                  ? "ausente"
+                                  : asset.stock
+                                  ? "padrão"
+                                  : "ok"}
+                              </span>
+                            </div>
+                          ))}
+                        </div>
+                      </ScrollArea>
+                    ) : (
+                      <p className="text-sm text-muted-foreground">
+                        Verificando...
+                      </p>
+                    )}
+                    <DialogFooter>
+                      {deps?.map === map.name && (
+                        <p className="text-sm mr-auto">
+                          {deps.missing.length
+                            ? `${deps.missing.length} arquivo(s) ausente(s)`
+                            : "Nenhum arquivo ausente"}
+                        </p>
+                      )}
+                      <Button
+                        variant="secondary"
+                        onClick={generateRes}
+                        disabled={deps?.map !== map.name}
+                      >
+                        Gerar .res
+                      </Button>
+                    </DialogFooter>
+                  </DialogContent>
+                </Dialog>
                 <Dialog>
                   <DialogTrigger asChild>
                     <Button
@@ -129,7 +446,9 @@ export default function MapManager() {
                     <DialogHeader>
                       <p className="text-sm">
                         Tem certeza que deseja deletar o mapa
-                        <strong> {map.name}</strong>?
+                        <strong> {map.name}</strong>? Os arquivos do mapa vão para
+                        a lixeira; os arquivos padrão do jogo e os que outros mapas
+                        usam são mantidos.
                       </p>
                     </DialogHeader>
                     <DialogFooter>
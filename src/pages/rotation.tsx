@@ -0,0 +1,256 @@
+"use client";
+
+import { useEffect, useMemo, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
+import { ArrowDown, ArrowUp, Shuffle, Trash2 } from "lucide-react";
+import { Badge } from "@/components/ui/badge";
+import { Label } from "@/components/ui/label";
+import { Input } from "@/components/ui/input";
+import { Button } from "@/components/ui/button";
+import { Checkbox } from "@/components/ui/checkbox";
+import { toast } from "sonner";
+import { useInstance } from "@/components/instance-provider";
+
+type RotationList = "mapcycle" | "maps_ini";
+
+interface RotationEntry {
+  map: string;
+  extra?: string;
+}
+
+interface Rotation {
+  list: RotationList;
+  path: string;
+  entries: RotationEntry[];
+  missing: string[];
+  pushed: boolean;
+}
+
+const listLabels: Record<RotationList, string> = {
+  mapcycle: "mapcycle.txt",
+  maps_ini: "maps.ini (AMXX)",
+};
+
+export default function RotationPage() {
+  const [list, setList] = useState<RotationList>("mapcycle");
+  const [entries, setEntries] = useState<RotationEntry[]>([]);
+  const [installed, setInstalled] = useState<string[]>([]);
+  const [newMap, setNewMap] = useState("");
+  const [weights, setWeights] = useState<Record<string, number>>({});
+  const [push, setPush] = useState(true);
+  const { instanceId } = useInstance();
+
+  const loadRotation = async () => {
+    try {
+      const rotation = await invoke<Rotation>("get_rotation", {
+        list,
+        instanceId,
+      });
+      setEntries(rotation.entries);
+    } catch (err) {
+      toast.error(String(err));
+    }
+  };
+
+  useEffect(() => {
+    loadRotation();
+  }, [list]);
+
+  useEffect(() => {
+    invoke<{ name: string }[]>("list_maps", { instanceId })
+      .then((maps) => setInstalled(maps.map((m) => m.name)))
+      .catch(() => setInstalled([]));
+  }, []);
+
+  const missing = useMemo(
+    () =>
+      entries
+        .map((e) => e.map)
+        .filter(
+          (map) => !installed.some((m) => m.toLowerCase() === map.toLowerCase())
+        ),
+    [entries, installed]
+  );
+
+  const pool = useMemo(
+    () => Array.from(new Set(entries.map((e) => e.map))),
+    [entries]
+  );
+
+  const move = (idx: number, delta: number) => {
+    const target = idx + delta;
+    if (target < 0 || target >= entries.length) return;
+    const next = [...entries];
+    [next[idx], next[target]] = [next[target], next[idx]];
+    setEntries(next);
+  };
+
+  const addMap = () => {
+    const map = newMap.trim();
+    if (!map) return;
+    setEntries([...entries, { map }]);
+    setNewMap("");
+  };
+
+  const generate = async () => {
+    try {
+      const maps = await invoke<string[]>("generate_map_rotation", {
+        pool: pool.map((map) => ({ map, weight: weights[map] ?? 1 })),
+      });
+      setEntries(
+        maps.map((map) => ({
+          map,
+          extra: entries.find((e) => e.map === map)?.extra,
+        }))
+      );
+    } catch (err) {
+      toast.error(String(err));
+    }
+  };
+
+  const save = async () => {
+    try {
+      const rotation = await invoke<Rotation>("save_rotation", {
+        list,
+        entries,
+        push: list === "mapcycle" && push,
+        instanceId,
+      });
+      setEntries(rotation.entries);
+      if (rotation.missing.length) {
+        toast.warning(`Mapas não instalados: ${rotation.missing.join(", ")}`);
+      }
+      toast.success(
+        rotation.pushed
+          ? "Rotação salva e aplicada (vale a partir da próxima troca de mapa)"
+          : "Rotação salva"
+      );
+    } catch (err) {
+      toast.error(String(err));
+    }
+  };
+
+  return (
+    <div className="max-w-6xl mx-auto p-4 space-y-8">
+      <div className="flex gap-2">
+        {(Object.keys(listLabels) as RotationList[]).map((value) => (
+          <Button
+            key={value}
+            variant={list === value ? "default" : "outline"}
+            onClick={() => setList(value)}
+          >
+            {listLabels[value]}
+          </Button>
+        ))}
+      </div>
+
+      <Card>
+        <CardHeader>
+          <CardTitle>Rotação de Mapas</CardTitle>
+        </CardHeader>
+        <CardContent className="space-y-4">
+          {entries.map((entry, idx) => (
+            <div
+              key={`${entry.map}-${idx}`}
+              className="flex justify-between items-center text-sm"
+            >
+              <div className="flex items-center gap-2">
+                <span className="text-muted-foreground w-6">{idx + 1}.</span>
+                <span className="font-mono">{entry.map}</span>
+                {entry.extra && (
+                  <span className="text-xs text-muted-foreground">
+                    {entry.extra}
+                  </span>
+                )}
+                {missing.includes(entry.map) && (
+                  <Badge variant="destructive">não instalado</Badge>
+                )}
+              </div>
+              <div className="flex">
+                <Button variant="ghost" size="icon" onClick={() => move(idx, -1)}>
+                  <ArrowUp className="w-4 h-4" />
+                </Button>
+                <Button variant="ghost" size="icon" onClick={() => move(idx, 1)}>
+                  <ArrowDown className="w-4 h-4" />
+                </Button>
+                <Button
+                  variant="ghost"
+                  size="icon"
+                  onClick={() => setEntries(entries.filter((_, i) => i !== idx))}
+                >
+                  <Trash2 className="w-4 h-4 text-red-500" />
+                </Button>
+              </div>
+            </div>
+          ))}
+
+          <div className="flex gap-2">
+            <Input
+              placeholder="Adicionar mapa"
+              list="installed-maps"
+              value={newMap}
+              onChange={(e) => setNewMap(e.target.value)}
+              onKeyDown={(e) => e.key === "Enter" && addMap()}
+            />
+            <datalist id="installed-maps">
+              {installed.map((map) => (
+                <option key={map} value={map} />
+              ))}
+            </datalist>
+            <Button onClick={addMap}>Adicionar</Button>
+          </div>
+
+          <div className="flex justify-between items-center">
+            {list === "mapcycle" ? (
+              <Label className="flex items-center gap-2">
+                <Checkbox
+                  checked={push}
+                  onCheckedChange={(checked) => setPush(checked === true)}
+                />
+                Aplicar no servidor em execução
+              </Label>
+            ) : (
+              <p className="text-xs text-muted-foreground">
+                O mapchooser lê o maps.ini na próxima troca de mapa.
+              </p>
+            )}
+            <Button onClick={save}>Salvar</Button>
+          </div>
+        </CardContent>
+      </Card>
+
+      {pool.length > 1 && (
+        <Card>
+          <CardHeader>
+            <CardTitle>Gerar Rotação Aleatória</CardTitle>
+          </CardHeader>
+          <CardContent className="space-y-4">
+            <div className="grid grid-cols-2 md:grid-cols-3 gap-4">
+              {pool.map((map) => (
+                <div key={map}>
+                  <Label className="mb-2 font-mono">{map}</Label>
+                  <Input
+                    type="number"
+                    min={0}
+                    value={weights[map] ?? 1}
+                    onChange={(e) =>
+                      setWeights({
+                        ...weights,
+                        [map]: Math.max(0, Number(e.target.value) || 0),
+                      })
+                    }
+                  />
+                </div>
+              ))}
+            </div>
+            <Button variant="outline" onClick={generate} className="w-full">
+              <Shuffle className="w-4 h-4" />
+              Gerar (peso = vezes na rotação)
+            </Button>
+          </CardContent>
+        </Card>
+      )}
+    </div>
+  );
+}
@@ -1,21 +1,56 @@
-import { Settings, Map, Server } from "lucide-react";
+import {
+  Settings,
+  Map,
+  Server,
+  KeyRound,
+  Users,
+  Network,
+  Gauge,
+  FileCode,
+  Terminal,
+} from "lucide-react";
 import { Button } from "@/components/ui/button";
 import { Label } from "@/components/ui/label";
 import { invoke } from "@tauri-apps/api/core";
 import { Input } from "@/components/ui/input";
+import { Checkbox } from "@/components/ui/checkbox";
 import { useEffect, useState } from "react";
 import { toast } from "sonner";
+import { useInstance } from "@/components/instance-provider";
 
 export default function SettingsPage() {
   const [port, setPort] = useState("27015");
   const [defaultMap, setDefaultMap] = useState("de_dust2");
+  const [rconPassword, setRconPassword] = useState("");
+  const [maxPlayers, setMaxPlayers] = useState("32");
+  const [ip, setIp] = useState("");
+  const [pingboost, setPingboost] = useState("");
+  const [sysTicrate, setSysTicrate] = useState("");
+  const [exec, setExec] = useState("");
+  const [extraArgs, setExtraArgs] = useState("");
+  const [svLan, setSvLan] = useState(false);
+  const [insecure, setInsecure] = useState(false);
+  const [debug, setDebug] = useState(false);
+  const [savedConfig, setSavedConfig] = useState<Record<string, unknown>>({});
   const [loading, setLoading] = useState(false);
+  const { instanceId } = useInstance();
 
   useEffect(() => {
-    invoke("get_server_config")
+    invoke("get_server_config", { instanceId })
       .then((data: any) => {
+        setSavedConfig(data ?? {});
         setPort(data.port);
         setDefaultMap(data.map);
+        setRconPassword(data.rcon_password ?? "");
+        setMaxPlayers(String(data.max_players ?? 32));
+        setIp(data.ip ?? "");
+        setPingboost(data.pingboost ? String(data.pingboost) : "");
+        setSysTicrate(data.sys_ticrate ? String(data.sys_ticrate) : "");
+        setExec(data.exec ?? "");
+        setExtraArgs((data.extra_args ?? []).join(" "));
+        setSvLan(!!data.sv_lan);
+        setInsecure(!!data.insecure);
+        setDebug(!!data.debug);
       })
       .catch(() => {});
   }, []);
@@ -24,12 +59,27 @@ export default function SettingsPage() {
     setLoading(true);
     try {
       await invoke("save_server_config", {
-        config: { port, map: defaultMap },
+        config: {
+          ...savedConfig,
+          port,
+          map: defaultMap,
+          rcon_password: rconPassword,
+          max_players: Number(maxPlayers),
+          ip: ip.trim() || null,
+          pingboost: pingboost ? Number(pingboost) : null,
+          sys_ticrate: sysTicrate ? Number(sysTicrate) : null,
+          exec: exec.trim() || null,
+          extra_args: extraArgs.split(/\s+/).filter(Boolean),
+          sv_lan: svLan,
+          insecure,
+          debug,
+        },
+        instanceId,
       });
       toast.success("Configurações salvas com sucesso!");
     } catch (error) {
       console.error("Erro ao salvar configurações:", error);
-      toast.error("Erro ao salvar configurações");
+      toast.error(String(error));
     } finally {
       setLoading(false);
     }
@@ -75,6 +125,124 @@ export default function SettingsPage() {
           />
         </div>
 
+        <div className="space-y-1">
+          <Label htmlFor="rcon" className="flex items-center gap-2">
+            <KeyRound className="w-4 h-4" /> Senha RCON
+          </Label>
+          <Input
+            id="rcon"
+            type="password"
+            value={rconPassword}
+            onChange={(e) => setRconPassword(e.target.value)}
+            placeholder="rcon_password do server.cfg"
+          />
+        </div>
+
+        <div className="space-y-1">
+          <Label htmlFor="maxplayers" className="flex items-center gap-2">
+            <Users className="w-4 h-4" /> Máximo de Jogadores
+          </Label>
+          <Input
+            id="maxplayers"
+            type="number"
+            min={1}
+            max={32}
+            value={maxPlayers}
+            onChange={(e) => setMaxPlayers(e.target.value)}
+          />
+        </div>
+
+        <div className="space-y-1">
+          <Label htmlFor="ip" className="flex items-center gap-2">
+            <Network className="w-4 h-4" /> IP (+ip)
+          </Label>
+          <Input
+            id="ip"
+            value={ip}
+            onChange={(e) => setIp(e.target.value)}
+            placeholder="Todas as interfaces"
+          />
+        </div>
+
+        <div className="grid grid-cols-2 gap-4">
+          <div className="space-y-1">
+            <Label htmlFor="pingboost" className="flex items-center gap-2">
+              <Gauge className="w-4 h-4" /> Pingboost
+            </Label>
+            <Input
+              id="pingboost"
+              type="number"
+              min={1}
+              max={3}
+              value={pingboost}
+              onChange={(e) => setPingboost(e.target.value)}
+              placeholder="Desligado"
+            />
+          </div>
+          <div className="space-y-1">
+            <Label htmlFor="ticrate" className="flex items-center gap-2">
+              <Gauge className="w-4 h-4" /> sys_ticrate
+            </Label>
+            <Input
+              id="ticrate"
+              type="number"
+              min={100}
+              max={10000}
+              value={sysTicrate}
+              onChange={(e) => setSysTicrate(e.target.value)}
+              placeholder="Padrão"
+            />
+          </div>
+        </div>
+
+        <div className="space-y-1">
+          <Label htmlFor="exec" className="flex items-center gap-2">
+            <FileCode className="w-4 h-4" /> Config Extra (+exec)
+          </Label>
+          <Input
+            id="exec"
+            value={exec}
+            onChange={(e) => setExec(e.target.value)}
+            placeholder="scrim.cfg"
+          />
+        </div>
+
+        <div className="space-y-1">
+          <Label htmlFor="extra" className="flex items-center gap-2">
+            <Terminal className="w-4 h-4" /> Argumentos Extras
+          </Label>
+          <Input
+            id="extra"
+            value={extraArgs}
+            onChange={(e) => setExtraArgs(e.target.value)}
+            placeholder="+sv_region 2"
+          />
+        </div>
+
+        <div className="space-y-2">
+          <Label className="flex items-center gap-2">
+            <Checkbox
+              checked={svLan}
+              onCheckedChange={(checked) => setSvLan(checked === true)}
+            />
+            Somente LAN (+sv_lan 1)
+          </Label>
+          <Label className="flex items-center gap-2">
+            <Checkbox
+              checked={insecure}
+              onCheckedChange={(checked) => setInsecure(checked === true)}
+            />
+            Sem VAC (-insecure)
+          </Label>
+          <Label className="flex items-center gap-2">
+            <Checkbox
+              checked={debug}
+              onCheckedChange={(checked) => setDebug(checked === true)}
+            />
+            Modo debug (-debug)
+          </Label>
+        </div>
+
         <Button type="submit" disabled={loading}>
           {loading ? "Salvando..." : "Salvar Configurações"}
         </Button>
@@ -16,11 +16,20 @@ import {
 interface Webhook {
   url: string;
   events: string[];
+  instances: string[];
+}
+
+interface Instance {
+  id: string;
+  name: string;
 }
 
 export default function WebhookManager() {
   const [url, setUrl] = useState("");
   const [events, setEvents] = useState<string[]>([]);
+  // Empty means every instance.
+  const [instances, setInstances] = useState<string[]>([]);
+  const [allInstances, setAllInstances] = useState<Instance[]>([]);
   const [webhooks, setWebhooks] = useState<Webhook[]>([]);
   const [editingUrl, setEditingUrl] = useState<string | null>(null);
 
@@ -28,9 +37,29 @@ export default function WebhookManager() {
     "server_started",
     "server_stopped",
     "error_occurred",
+    "missing_asset",
+    "server_crashed",
+    "server_restarted",
+    "restart_gave_up",
     "map_added",
     "map_deleted",
     "map_changed",
+    "map_started",
+    "player_connected",
+    "player_entered",
+    "player_disconnected",
+    "player_changed_name",
+    "player_joined_team",
+    "player_killed",
+    "player_say",
+    "round_start",
+    "round_end",
+    "world_triggered",
+    "team_triggered",
+    "player_banned",
+    "ban_removed",
+    "ban_expired",
+    "chat_flagged",
     "custom_event",
   ];
 
@@ -41,8 +70,20 @@ export default function WebhookManager() {
 
   useEffect(() => {
     loadWebhooks();
+    invoke<Instance[]>("list_instances")
+      .then(setAllInstances)
+      .catch(() => setAllInstances([]));
   }, []);
 
+  const instanceName = (id: string) =>
+    allInstances.find((i) => i.id === id)?.name ?? id;
+
+  const toggleInstance = (id: string) => {
+    setInstances((prev) =>
+      prev.includes(id) ? prev.filter((i) => i !== id) : [...prev, id]
+    );
+  };
+
   const toggleEvent = (event: string) => {
     setEvents((prev) =>
       prev.includes(event) ? prev.filter((e) => e !== event) : [...prev, event]
@@ -51,9 +92,10 @@ export default function WebhookManager() {
 
   const addWebhook = async () => {
     if (!url || events.length === 0) return;
-    await invoke("add_webhook", { url, events });
+    await invoke("add_webhook", { url, events, instances });
     setUrl("");
     setEvents([]);
+    setInstances([]);
     setEditingUrl(null);
     loadWebhooks();
   };
@@ -66,6 +108,7 @@ export default function WebhookManager() {
   const startEdit = (webhook: Webhook) => {
     setUrl(webhook.url);
     setEvents(webhook.events);
+    setInstances(webhook.instances ?? []);
     setEditingUrl(webhook.url);
     // Deleta temporariamente o antigo para evitar duplicação
     removeWebhook(webhook.url);
@@ -100,6 +143,29 @@ export default function WebhookManager() {
                 </Button>
               ))}
             </div>
+            {allInstances.length > 1 && (
+              <div className="flex flex-wrap gap-2">
+                <Button
+                  variant={instances.length === 0 ? "default" : "outline"}
+                  size="sm"
+                  onClick={() => setInstances([])}
+                >
+                  Todas as instâncias
+                </Button>
+                {allInstances.map((instance) => (
+                  <Button
+                    key={instance.id}
+                    variant={
+                      instances.includes(instance.id) ? "default" : "outline"
+                    }
+                    size="sm"
+                    onClick={() => toggleInstance(instance.id)}
+                  >
+                    {instance.name}
+                  </Button>
+                ))}
+              </div>
+            )}
             <div className="flex justify-end">
               <Button onClick={addWebhook} disabled={!isValid}>
                 {editingUrl ? "Salvar Edição" : "Salvar Webhook"}
@@ -138,6 +204,14 @@ export default function WebhookManager() {
                 </div>
               </CardHeader>
               <CardContent className="flex flex-wrap gap-2">
+                {(wh.instances ?? []).map((id) => (
+                  <span
+                    key={id}
+                    className="text-xs bg-primary text-primary-foreground px-2 py-1 rounded-md"
+                  >
+                    {instanceName(id)}
+                  </span>
+                ))}
                 {wh.events.map((ev) => (
                   <span
                     key={ev}